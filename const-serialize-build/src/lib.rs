@@ -0,0 +1,81 @@
+//! Serialize values computed in a build script so the crate can read them back in const.
+//!
+//! The build script and the crate need to share the serialized type, for example through a small
+//! shared crate or a module included in both with `#[path]`.
+//!
+//! ```rust,no_run
+//! // build.rs
+//! # #[derive(Clone, Copy, const_serialize::SerializeConst)]
+//! # struct Config { port: u16 }
+//! let config = Config { port: 8080 };
+//! const_serialize_build::write_const("config", "crate::Config", &config).unwrap();
+//! ```
+//!
+//! ```rust,ignore
+//! // src/lib.rs
+//! const CONFIG: Config = include!(concat!(env!("OUT_DIR"), "/config.rs"));
+//! ```
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use const_serialize::{DynLayout, SerializeConst};
+
+/// The files written for a value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Output {
+    /// The serialized value
+    pub data: PathBuf,
+    /// The rust expression that deserializes the value in const
+    pub snippet: PathBuf,
+}
+
+/// Serialize a value into `OUT_DIR`. This writes `{name}.bin` with the serialized data and `{name}.rs` with
+/// an expression that deserializes it in const with `const_serialize::include_const!`. `type_path` is the
+/// path of the type as it is written in the crate that includes the snippet.
+///
+/// The value is serialized at runtime, so unlike [`serialize_const`](const_serialize::serialize_const) it isn't
+/// limited to [`ConstWriteBuffer::CAPACITY`](const_serialize::ConstWriteBuffer::CAPACITY) bytes.
+pub fn write_const<T: SerializeConst>(
+    name: &str,
+    type_path: &str,
+    value: &T,
+) -> io::Result<Output> {
+    let out_dir = std::env::var_os("OUT_DIR").ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "OUT_DIR is not set. write_const should be called from a build script",
+        )
+    })?;
+    write_const_to(Path::new(&out_dir), name, type_path, value)
+}
+
+/// Like [`write_const`], but writes the files into a directory other than `OUT_DIR`
+pub fn write_const_to<T: SerializeConst>(
+    dir: &Path,
+    name: &str,
+    type_path: &str,
+    value: &T,
+) -> io::Result<Output> {
+    let data = dir.join(format!("{name}.bin"));
+    let snippet = dir.join(format!("{name}.rs"));
+    let path = data.to_str().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not valid UTF-8", data.display()),
+        )
+    })?;
+
+    // SAFETY: the memory of a SerializeConst type has the layout of T::MEMORY_LAYOUT
+    let memory = unsafe {
+        std::slice::from_raw_parts(value as *const T as *const u8, std::mem::size_of::<T>())
+    };
+    let bytes = DynLayout::from(&T::MEMORY_LAYOUT).serialize(memory);
+    std::fs::write(&data, bytes)?;
+    // The debug representation of a str is a valid rust string literal
+    std::fs::write(
+        &snippet,
+        format!("::const_serialize::include_const!({type_path}, {path:?})\n"),
+    )?;
+    Ok(Output { data, snippet })
+}
@@ -0,0 +1,69 @@
+use const_serialize::{include_const_raw, serialize_const, ConstWriteBuffer, SerializeConst};
+use const_serialize_build::{write_const_to, Output};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Config {
+    port: u16,
+    verbose: bool,
+}
+
+#[test]
+fn test_write_const() {
+    let dir = std::env::temp_dir().join(format!("const-serialize-build-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let config = Config {
+        port: 8080,
+        verbose: true,
+    };
+    let output = write_const_to(&dir, "config", "crate::Config", &config).unwrap();
+    assert_eq!(
+        output,
+        Output {
+            data: dir.join("config.bin"),
+            snippet: dir.join("config.rs"),
+        }
+    );
+
+    let data = std::fs::read(&output.data).unwrap();
+    assert_eq!(
+        data,
+        serialize_const(&config, ConstWriteBuffer::new()).as_ref()
+    );
+    let read = include_const_raw::<Config>(&data, "config.bin");
+    assert_eq!(read, config);
+
+    let snippet = std::fs::read_to_string(&output.snippet).unwrap();
+    assert_eq!(
+        snippet,
+        format!(
+            "::const_serialize::include_const!(crate::Config, {:?})\n",
+            output.data.to_str().unwrap()
+        )
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_write_large_const() {
+    let dir = std::env::temp_dir().join(format!(
+        "const-serialize-build-large-{}",
+        std::process::id()
+    ));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    // Larger than a ConstWriteBuffer can hold
+    let mut values = [0u64; 100];
+    for (i, value) in values.iter_mut().enumerate() {
+        *value = i as u64 * 3;
+    }
+    assert!(std::mem::size_of_val(&values) > ConstWriteBuffer::CAPACITY);
+    let output = write_const_to(&dir, "values", "[u64; 100]", &values).unwrap();
+
+    let data = std::fs::read(&output.data).unwrap();
+    assert_eq!(data.len(), std::mem::size_of_val(&values));
+    assert_eq!(include_const_raw::<[u64; 100]>(&data, "values.bin"), values);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
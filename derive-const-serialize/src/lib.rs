@@ -1,12 +1,15 @@
 use proc_macro::TokenStream;
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote, ToTokens};
+use syn::ext::IdentExt;
+use syn::spanned::Spanned;
 use syn::{parse_macro_input, DeriveInput};
-use syn::{parse_quote, Generics, WhereClause, WherePredicate};
+use syn::{parse_quote, Attribute, Generics, WhereClause, WherePredicate};
 
 fn add_bounds(where_clause: &mut Option<WhereClause>, generics: &Generics) {
     let bounds = generics.params.iter().filter_map(|param| match param {
         syn::GenericParam::Type(ty) => {
-            Some::<WherePredicate>(parse_quote! { #ty: const_serialize::SerializeConst, })
+            let ty = &ty.ident;
+            Some::<WherePredicate>(parse_quote! { #ty: const_serialize::SerializeConst })
         }
         syn::GenericParam::Lifetime(_) => None,
         syn::GenericParam::Const(_) => None,
@@ -14,18 +17,129 @@ fn add_bounds(where_clause: &mut Option<WhereClause>, generics: &Generics) {
     if let Some(clause) = where_clause {
         clause.predicates.extend(bounds);
     } else {
-        *where_clause = Some(parse_quote! { where #(#bounds)* });
+        *where_clause = Some(parse_quote! { where #(#bounds),* });
+    }
+}
+
+/// The options set with `#[const_serialize(...)]` on a type or field
+#[derive(Default)]
+struct Options {
+    /// Generate a zero-copy view for the type, or return the view of the field from view accessors
+    view: bool,
+    /// Generate a `const_eq` method
+    eq: bool,
+    /// Generate a `const_cmp` method
+    ord: bool,
+    /// Generate a `const_hash` method
+    hash: bool,
+    /// The stable id of a field in tagged formats
+    id: Option<u32>,
+    /// The value of a field when it is missing from data in a tagged format
+    default: Option<syn::Expr>,
+    /// Where `id` or `default` was set, to report them outside of fields
+    field_option: Option<proc_macro2::Span>,
+}
+
+impl Options {
+    fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut options = Self::default();
+        for attr in attrs {
+            if attr.path().is_ident("const_serialize") {
+                attr.parse_nested_meta(|meta| {
+                    // #[const_serialize(view)]
+                    if meta.path.is_ident("view") {
+                        options.view = true;
+                        return Ok(());
+                    }
+
+                    // #[const_serialize(eq)]
+                    if meta.path.is_ident("eq") {
+                        options.eq = true;
+                        return Ok(());
+                    }
+
+                    // #[const_serialize(ord)]
+                    if meta.path.is_ident("ord") {
+                        options.ord = true;
+                        return Ok(());
+                    }
+
+                    // #[const_serialize(hash)]
+                    if meta.path.is_ident("hash") {
+                        options.hash = true;
+                        return Ok(());
+                    }
+
+                    // #[const_serialize(id = 1)]
+                    if meta.path.is_ident("id") {
+                        let id: syn::LitInt = meta.value()?.parse()?;
+                        options.id = Some(id.base10_parse()?);
+                        options.field_option = Some(meta.path.span());
+                        return Ok(());
+                    }
+
+                    // #[const_serialize(default = value)]
+                    if meta.path.is_ident("default") {
+                        options.default = Some(meta.value()?.parse()?);
+                        options.field_option = Some(meta.path.span());
+                        return Ok(());
+                    }
+
+                    Err(meta.error("unrecognized const_serialize option"))
+                })?;
+            }
+        }
+        Ok(options)
+    }
+
+    /// Parse the options of a type or enum variant, where `id` and `default` don't apply
+    fn parse_outside_field(attrs: &[Attribute], location: &str) -> syn::Result<Self> {
+        let options = Self::parse(attrs)?;
+        match options.field_option {
+            Some(span) => Err(syn::Error::new(
+                span,
+                format!("`id` and `default` can only be set on fields, not on {location}"),
+            )),
+            None => Ok(options),
+        }
     }
 }
 
 /// Derive the const serialize trait for a struct
-#[proc_macro_derive(SerializeConst)]
+///
+/// Add `#[const_serialize(view)]` to the type to also generate a zero-copy view over the serialized data.
+/// See `SerializeConstView` for details.
+///
+/// Add `#[const_serialize(eq)]`, `#[const_serialize(ord)]` or `#[const_serialize(hash)]` to generate
+/// `const_eq`, `const_cmp` or `const_hash` methods that call the functions with the same names.
+///
+/// Add `#[const_serialize(id = N)]` to fields to give them stable ids in tagged formats like `serialize_tagged`,
+/// and `#[const_serialize(default = value)]` to fill in the field when it is missing from tagged data.
+#[proc_macro_derive(SerializeConst, attributes(const_serialize))]
 pub fn derive_parse(input: TokenStream) -> TokenStream {
     // Parse the input tokens into a syntax tree
     let input = parse_macro_input!(input as DeriveInput);
+    let options = match Options::parse_outside_field(&input.attrs, "types") {
+        Ok(options) => options,
+        Err(err) => return err.to_compile_error().into(),
+    };
+    let mut output = derive_serialize_const(&input, &options);
+    output.extend(TokenStream::from(compare_methods(&input, &options)));
+    output
+}
+
+fn derive_serialize_const(input: &DeriveInput, options: &Options) -> TokenStream {
+    if options.view && !input.generics.params.is_empty() {
+        return syn::Error::new(
+            input.ident.span(),
+            "Views are not supported for generic types",
+        )
+        .to_compile_error()
+        .into();
+    }
 
-    match input.data {
-        syn::Data::Struct(data) => match data.fields {
+    match &input.data {
+        syn::Data::Struct(data) => match &data.fields {
             syn::Fields::Named(fields) => {
                 let ty = &input.ident;
                 let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
@@ -35,19 +149,52 @@ pub fn derive_parse(input: TokenStream) -> TokenStream {
                     .named
                     .iter()
                     .map(|field| field.ident.as_ref().unwrap());
+                let field_name_strings = fields
+                    .named
+                    .iter()
+                    .map(|field| field.ident.as_ref().unwrap().unraw().to_string());
                 let field_types = fields.named.iter().map(|field| &field.ty);
+                let FieldOptions {
+                    calls: field_options,
+                    defaults,
+                } = match field_options(fields.named.iter()) {
+                    Ok(options) => options,
+                    Err(err) => return err.to_compile_error().into(),
+                };
+                let defaults = if defaults.is_empty() {
+                    quote! {}
+                } else {
+                    quote! {
+                        impl #impl_generics #ty #ty_generics #where_clause {
+                            #(#defaults)*
+                        }
+                    }
+                };
+                let name = ty.unraw().to_string();
+                let view = if options.view {
+                    match struct_view(input, fields.named.iter()) {
+                        Ok(view) => view,
+                        Err(err) => return err.to_compile_error().into(),
+                    }
+                } else {
+                    quote! {}
+                };
                 quote! {
                     unsafe impl #impl_generics const_serialize::SerializeConst for #ty #ty_generics #where_clause {
                         const MEMORY_LAYOUT: const_serialize::Layout = const_serialize::Layout::Struct(const_serialize::StructEncoding::new(
                             std::mem::size_of::<Self>(),
                             &[#(
                                 const_serialize::PlainOldData::new(
-                                    std::mem::offset_of!(#ty, #field_names),
+                                    std::mem::offset_of!(Self, #field_names),
                                     <#field_types as const_serialize::SerializeConst>::MEMORY_LAYOUT,
-                                ),
+                                ).with_name(#field_name_strings)#field_options,
                             )*],
-                        ));
+                        ).with_name(#name));
                     }
+
+                    #defaults
+
+                    #view
                 }.into()
             }
             syn::Fields::Unit => {
@@ -55,13 +202,24 @@ pub fn derive_parse(input: TokenStream) -> TokenStream {
                 let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
                 let mut where_clause = where_clause.cloned();
                 add_bounds(&mut where_clause, &input.generics);
+                let name = ty.unraw().to_string();
+                let view = if options.view {
+                    match struct_view(input, std::iter::empty()) {
+                        Ok(view) => view,
+                        Err(err) => return err.to_compile_error().into(),
+                    }
+                } else {
+                    quote! {}
+                };
                 quote! {
                     unsafe impl #impl_generics const_serialize::SerializeConst for #ty #ty_generics #where_clause {
                         const MEMORY_LAYOUT: const_serialize::Layout = const_serialize::Layout::Struct(const_serialize::StructEncoding::new(
                             std::mem::size_of::<Self>(),
                             &[],
-                        ));
+                        ).with_name(#name));
                     }
+
+                    #view
                 }.into()
             }
             _ => syn::Error::new(
@@ -76,6 +234,12 @@ pub fn derive_parse(input: TokenStream) -> TokenStream {
                 .to_compile_error()
                 .into(),
             1.. => {
+                for variant in &data.variants {
+                    if let Err(err) = Options::parse_outside_field(&variant.attrs, "enum variants")
+                    {
+                        return err.to_compile_error().into();
+                    }
+                }
                 let mut repr_c = false;
                 let mut discriminant_size = None;
                 for attr in &input.attrs {
@@ -135,23 +299,46 @@ pub fn derive_parse(input: TokenStream) -> TokenStream {
                 let mut where_clause = where_clause.cloned();
                 add_bounds(&mut where_clause, &input.generics);
                 let mut last_discriminant = None;
-                let variants = data.variants.iter().map(|variant| {
-                    let discriminant = variant
-                        .discriminant
-                        .as_ref()
-                        .map(|(_, discriminant)| discriminant.to_token_stream())
-                        .unwrap_or_else(|| match &last_discriminant {
-                            Some(discriminant) => quote! { #discriminant + 1 },
-                            None => {
-                                quote! { 0 }
-                            }
-                        });
-                    last_discriminant = Some(discriminant.clone());
+                let discriminants: Vec<_> = data
+                    .variants
+                    .iter()
+                    .map(|variant| {
+                        let discriminant = variant
+                            .discriminant
+                            .as_ref()
+                            .map(|(_, discriminant)| discriminant.to_token_stream())
+                            .unwrap_or_else(|| match &last_discriminant {
+                                Some(discriminant) => quote! { #discriminant + 1 },
+                                None => {
+                                    quote! { 0 }
+                                }
+                            });
+                        last_discriminant = Some(discriminant.clone());
+                        discriminant
+                    })
+                    .collect();
+                let view = if options.view {
+                    match enum_view(input, data, &discriminants) {
+                        Ok(view) => view,
+                        Err(err) => return err.to_compile_error().into(),
+                    }
+                } else {
+                    quote! {}
+                };
+                let variants = data.variants.iter().zip(&discriminants).map(|(variant, discriminant)| {
                     let field_names = variant
                         .fields
                         .iter()
                         .map(|field| field.ident.as_ref().unwrap());
                     let field_types = variant.fields.iter().map(|field| &field.ty);
+                    let field_attrs = variant.fields.iter().map(|field| {
+                        field
+                            .attrs
+                            .iter()
+                            .filter(|attr| attr.path().is_ident("const_serialize"))
+                            .collect::<Vec<_>>()
+                    });
+                    let variant_name = variant.ident.unraw().to_string();
                     let generics = &input.generics;
                     quote! {
                         {
@@ -159,13 +346,14 @@ pub fn derive_parse(input: TokenStream) -> TokenStream {
                             #[repr(C)]
                             struct VariantStruct #generics {
                                 #(
+                                    #(#field_attrs)*
                                     #field_names: #field_types,
                                 )*
                             }
                             const_serialize::EnumVariant::new(
                                 #discriminant as u32,
                                 match VariantStruct::MEMORY_LAYOUT {
-                                    const_serialize::Layout::Struct(encoding) => encoding,
+                                    const_serialize::Layout::Struct(encoding) => encoding.with_name(#variant_name),
                                     _ => panic!("VariantStruct::MEMORY_LAYOUT must be a struct"),
                                 },
                                 std::mem::align_of::<VariantStruct>(),
@@ -173,6 +361,7 @@ pub fn derive_parse(input: TokenStream) -> TokenStream {
                         }
                     }
                 });
+                let name = ty.unraw().to_string();
                 quote! {
                     unsafe impl #impl_generics const_serialize::SerializeConst for #ty #ty_generics #where_clause {
                         const MEMORY_LAYOUT: const_serialize::Layout = const_serialize::Layout::Enum(const_serialize::EnumEncoding::new(
@@ -188,8 +377,10 @@ pub fn derive_parse(input: TokenStream) -> TokenStream {
                                 ];
                                 DATA
                             },
-                        ));
+                        ).with_name(#name));
                     }
+
+                    #view
                 }.into()
             }
         },
@@ -198,3 +389,294 @@ pub fn derive_parse(input: TokenStream) -> TokenStream {
             .into(),
     }
 }
+
+/// The options of the fields of a struct
+struct FieldOptions {
+    /// The `with_id` and `with_default_serialized` calls for the layout of every field
+    calls: Vec<proc_macro2::TokenStream>,
+    /// The associated consts that hold the serialized default values. Items nested in the layout can't use the
+    /// generic parameters of the struct, so the defaults live on the struct instead.
+    defaults: Vec<proc_macro2::TokenStream>,
+}
+
+/// The options of every field. Either every field has an id or none do, so ids can't collide with the positions
+/// of fields without one.
+fn field_options<'f>(fields: impl Iterator<Item = &'f syn::Field>) -> syn::Result<FieldOptions> {
+    let mut ids = Vec::new();
+    let mut without_id = None;
+    let mut calls = Vec::new();
+    let mut defaults = Vec::new();
+    for (index, field) in fields.enumerate() {
+        let options = Options::parse(&field.attrs)?;
+        let mut call = quote! {};
+        match options.id {
+            Some(id) => {
+                if ids.contains(&id) {
+                    return Err(syn::Error::new_spanned(
+                        field,
+                        format!("the id {id} is used by another field"),
+                    ));
+                }
+                ids.push(id);
+                call.extend(quote! { .with_id(#id) });
+            }
+            None => without_id = Some(field),
+        }
+        if let Some(default) = options.default {
+            let ty = &field.ty;
+            let name = format_ident!("__CONST_SERIALIZE_DEFAULT_{}", index);
+            defaults.push(quote! {
+                #[doc(hidden)]
+                const #name: &'static const_serialize::ConstWriteBuffer = &const_serialize::serialize_default::<#ty>(#default);
+            });
+            call.extend(quote! { .with_default_serialized(Self::#name.as_ref()) });
+        }
+        calls.push(call);
+    }
+    if let (Some(field), false) = (without_id, ids.is_empty()) {
+        return Err(syn::Error::new_spanned(
+            field,
+            "every field needs an id if any field has one",
+        ));
+    }
+    Ok(FieldOptions { calls, defaults })
+}
+
+/// The return type of a view accessor for a field and the expression that reads it from `bytes`
+fn view_field(
+    field: &syn::Field,
+) -> syn::Result<(proc_macro2::TokenStream, proc_macro2::TokenStream)> {
+    let ty = &field.ty;
+    if Options::parse(&field.attrs)?.view {
+        let view = quote! { <#ty as const_serialize::SerializeConstView<'a>>::View };
+        Ok((view.clone(), quote! { <#view>::from_validated(bytes) }))
+    } else {
+        Ok((
+            ty.to_token_stream(),
+            quote! {
+                match const_serialize::deserialize_const!(#ty, const_serialize::ConstReadBuffer::new(bytes)) {
+                    Some(value) => value,
+                    None => panic!("the view data is not valid"),
+                }
+            },
+        ))
+    }
+}
+
+fn view_ident(input: &DeriveInput) -> syn::Ident {
+    syn::Ident::new(&format!("{}View", input.ident.unraw()), input.ident.span())
+}
+
+/// Generate a view struct with an accessor for every field
+fn struct_view<'f>(
+    input: &DeriveInput,
+    fields: impl Iterator<Item = &'f syn::Field>,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let ty = &input.ident;
+    let view = view_ident(input);
+    let doc = format!("A zero-copy view over the serialized data of [`{ty}`]");
+    let methods = quote! {
+        /// Create a view over serialized data. Returns None if the data doesn't start with a valid value. Any data after the value is ignored.
+        pub const fn new(bytes: &'a [u8]) -> Option<Self> {
+            match const_serialize::view_len(bytes, &<#ty as const_serialize::SerializeConst>::MEMORY_LAYOUT) {
+                Some(len) => Some(Self::from_validated(bytes.split_at(len).0)),
+                None => None,
+            }
+        }
+
+        /// The serialized data of the value
+        pub const fn as_bytes(&self) -> &'a [u8] {
+            self.bytes
+        }
+    };
+    let offsets = quote! {
+        const_serialize::view_offsets(bytes, &<#ty as const_serialize::SerializeConst>::MEMORY_LAYOUT)
+    };
+    let fields_view = fields_view(input, &view, &doc, fields, offsets, methods)?;
+    Ok(quote! {
+        #fields_view
+
+        impl<'a> const_serialize::SerializeConstView<'a> for #ty {
+            type View = #view<'a>;
+        }
+    })
+}
+
+/// Generate a struct that views the serialized fields of a struct or an enum variant. The offsets of the fields
+/// are found once when the view is created, so every accessor only reads its own field.
+fn fields_view<'f>(
+    input: &DeriveInput,
+    view: &syn::Ident,
+    doc: &str,
+    fields: impl Iterator<Item = &'f syn::Field>,
+    offsets: proc_macro2::TokenStream,
+    methods: proc_macro2::TokenStream,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let vis = &input.vis;
+    let mut accessors = Vec::new();
+    for (index, field) in fields.enumerate() {
+        let name = field.ident.as_ref().unwrap();
+        let (return_type, read) = view_field(field)?;
+        let doc = format!("Read the `{}` field", name.unraw());
+        accessors.push(quote! {
+            #[doc = #doc]
+            pub const fn #name(&self) -> #return_type {
+                let bytes = const_serialize::view_field(self.bytes, &self.offsets, #index);
+                #read
+            }
+        });
+    }
+    // The start of every field and the end of the last field
+    let offset_count = accessors.len() + 1;
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy)]
+        #vis struct #view<'a> {
+            bytes: &'a [u8],
+            offsets: [usize; #offset_count],
+        }
+
+        impl<'a> #view<'a> {
+            #[doc(hidden)]
+            pub const fn from_validated(bytes: &'a [u8]) -> Self {
+                Self {
+                    bytes,
+                    offsets: #offsets,
+                }
+            }
+
+            #methods
+
+            #(#accessors)*
+        }
+    })
+}
+
+/// Generate a view enum with the same variants as the enum. Variants with fields hold a view over the fields.
+fn enum_view(
+    input: &DeriveInput,
+    data: &syn::DataEnum,
+    discriminants: &[proc_macro2::TokenStream],
+) -> syn::Result<proc_macro2::TokenStream> {
+    let ty = &input.ident;
+    let vis = &input.vis;
+    let view = view_ident(input);
+    let doc = format!("A zero-copy view over the serialized data of [`{ty}`]");
+    // The view only borrows the data if one of the variants has fields
+    let mut borrows = false;
+    let mut variants = Vec::new();
+    let mut reads = Vec::new();
+    let mut variant_views = Vec::new();
+    for (variant, discriminant) in data.variants.iter().zip(discriminants) {
+        let name = &variant.ident;
+        if let syn::Fields::Unit = variant.fields {
+            variants.push(quote! { #name });
+            reads.push(quote! {
+                if tag == (#discriminant) as u32 {
+                    return Self::#name;
+                }
+            });
+            continue;
+        }
+        borrows = true;
+        let variant_view =
+            syn::Ident::new(&format!("{}{}View", ty.unraw(), name.unraw()), name.span());
+        let doc = format!(
+            "A zero-copy view over the fields of [`{ty}::{}`]",
+            name.unraw()
+        );
+        let offsets = quote! {
+            const_serialize::view_variant_offsets(bytes, &<#ty as const_serialize::SerializeConst>::MEMORY_LAYOUT)
+        };
+        variant_views.push(fields_view(
+            input,
+            &variant_view,
+            &doc,
+            variant.fields.iter(),
+            offsets,
+            quote! {},
+        )?);
+        variants.push(quote! { #name(#variant_view<'a>) });
+        reads.push(quote! {
+            if tag == (#discriminant) as u32 {
+                return Self::#name(#variant_view::from_validated(bytes));
+            }
+        });
+    }
+    let generics = if borrows {
+        quote! { <'a> }
+    } else {
+        quote! {}
+    };
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Clone, Copy)]
+        #vis enum #view #generics {
+            #(#variants,)*
+        }
+
+        impl<'a> #view #generics {
+            /// Create a view over serialized data. Returns None if the data doesn't start with a valid value. Any data after the value is ignored.
+            pub const fn new(bytes: &'a [u8]) -> Option<Self> {
+                match const_serialize::view_len(bytes, &<#ty as const_serialize::SerializeConst>::MEMORY_LAYOUT) {
+                    Some(len) => Some(Self::from_validated(bytes.split_at(len).0)),
+                    None => None,
+                }
+            }
+
+            #[doc(hidden)]
+            pub const fn from_validated(bytes: &'a [u8]) -> Self {
+                let tag = const_serialize::view_tag(bytes, &<#ty as const_serialize::SerializeConst>::MEMORY_LAYOUT);
+                #(#reads)*
+                panic!("the enum data holds an unknown variant")
+            }
+        }
+
+        #(#variant_views)*
+
+        impl<'a> const_serialize::SerializeConstView<'a> for #ty {
+            type View = #view #generics;
+        }
+    })
+}
+
+/// Generate const methods that compare and hash the value through its layout
+fn compare_methods(input: &DeriveInput, options: &Options) -> proc_macro2::TokenStream {
+    let mut methods = Vec::new();
+    if options.eq {
+        methods.push(quote! {
+            /// Check if two values are equal in const. See `const_serialize::const_eq`.
+            pub const fn const_eq(&self, other: &Self) -> bool {
+                const_serialize::const_eq(self, other)
+            }
+        });
+    }
+    if options.ord {
+        methods.push(quote! {
+            /// Compare two values in const. See `const_serialize::const_cmp`.
+            pub const fn const_cmp(&self, other: &Self) -> std::cmp::Ordering {
+                const_serialize::const_cmp(self, other)
+            }
+        });
+    }
+    if options.hash {
+        methods.push(quote! {
+            /// Hash the value in const. See `const_serialize::const_hash`.
+            pub const fn const_hash(&self) -> u64 {
+                const_serialize::const_hash(self)
+            }
+        });
+    }
+    if methods.is_empty() {
+        return quote! {};
+    }
+    let ty = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let mut where_clause = where_clause.cloned();
+    add_bounds(&mut where_clause, &input.generics);
+    quote! {
+        impl #impl_generics #ty #ty_generics #where_clause {
+            #(#methods)*
+        }
+    }
+}
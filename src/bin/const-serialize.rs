@@ -0,0 +1,181 @@
+//! Inspect data serialized with const-serialize without the rust types it was serialized from.
+//!
+//! Most commands take a schema file, which is a [`DynLayout`] saved as JSON. Schemas can be
+//! created from a rust type with `serde_json::to_string(&DynLayout::from(T::MEMORY_LAYOUT))`.
+//! The `records` command reads the schemas that are embedded in the file instead.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use const_serialize::{diff_values, read_records, read_wasm_records_lossy, DynLayout, Value};
+
+#[derive(Parser)]
+#[command(name = "const-serialize", version, about)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print an annotated hexdump of serialized data
+    Dump {
+        /// The schema of the serialized data
+        schema: PathBuf,
+        /// The file with the serialized data
+        data: PathBuf,
+    },
+    /// Decode serialized data into JSON
+    ToJson {
+        /// The schema of the serialized data
+        schema: PathBuf,
+        /// The file with the serialized data
+        data: PathBuf,
+    },
+    /// Encode JSON into serialized data
+    FromJson {
+        /// The schema of the serialized data
+        schema: PathBuf,
+        /// The JSON file to encode
+        json: PathBuf,
+        /// Where to write the serialized data. Defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Print the fingerprint of a schema
+    Hash {
+        /// The schema to fingerprint
+        schema: PathBuf,
+    },
+    /// Compare two schemas, or two serialized files if a schema is passed
+    Diff {
+        left: PathBuf,
+        right: PathBuf,
+        /// Decode both files with this schema and compare the values instead of comparing the files as schemas
+        #[arg(short, long)]
+        schema: Option<PathBuf>,
+    },
+    /// Decode the records embedded in a compiled binary or WebAssembly module as JSON
+    Records {
+        /// The compiled file
+        file: PathBuf,
+        /// The section the records were embedded in
+        #[arg(short, long, default_value = "cser")]
+        section: String,
+    },
+}
+
+fn main() -> ExitCode {
+    let args = Args::parse();
+    match run(args.command) {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+type Error = Box<dyn std::error::Error>;
+
+fn run(command: Command) -> Result<ExitCode, Error> {
+    match command {
+        Command::Dump { schema, data } => {
+            let layout = read_schema(&schema)?;
+            let data = read(&data)?;
+            let dump = layout.hexdump(&data);
+            print!("{dump}");
+            if dump.error().is_some() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::ToJson { schema, data } => {
+            let layout = read_schema(&schema)?;
+            let value = layout.decode_value(&read(&data)?)?;
+            println!("{}", serde_json::to_string_pretty(&value)?);
+        }
+        Command::FromJson {
+            schema,
+            json,
+            output,
+        } => {
+            let layout = read_schema(&schema)?;
+            let value: Value = serde_json::from_slice(&read(&json)?)?;
+            let data = layout.encode_value(&value)?;
+            match output {
+                Some(output) => write(&output, &data)?,
+                None => std::io::Write::write_all(&mut std::io::stdout(), &data)?,
+            }
+        }
+        Command::Hash { schema } => {
+            let layout = read_schema(&schema)?;
+            println!("{:016x}", layout.fingerprint());
+        }
+        Command::Diff {
+            left,
+            right,
+            schema,
+        } => {
+            let differences = match schema {
+                Some(schema) => {
+                    let layout = read_schema(&schema)?;
+                    let left = layout.decode_value(&read(&left)?)?;
+                    let right = layout.decode_value(&read(&right)?)?;
+                    diff_values(&left, &right)
+                }
+                None => read_schema(&left)?.diff(&read_schema(&right)?),
+            };
+            for difference in &differences {
+                println!("{difference}");
+            }
+            if !differences.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+        Command::Records { file, section } => {
+            let file = read(&file)?;
+            let (records, skipped) = if file.starts_with(b"\0asm") {
+                read_wasm_records_lossy(&file, &section)?
+            } else {
+                (read_records(&file, &section)?, Vec::new())
+            };
+            for error in &skipped {
+                eprintln!("warning: skipped {error}");
+            }
+            let mut failed = !skipped.is_empty();
+            for record in &records {
+                let value = match record.value() {
+                    Ok(value) => serde_json::to_string(&value)?,
+                    Err(error) => {
+                        failed = true;
+                        format!("({error})")
+                    }
+                };
+                println!(
+                    "{} {:#x} {:016x} {value}",
+                    record.section, record.address, record.fingerprint
+                );
+            }
+            if failed {
+                return Ok(ExitCode::FAILURE);
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, Error> {
+    std::fs::read(path)
+        .map_err(|error| format!("failed to read {}: {error}", path.display()).into())
+}
+
+fn write(path: &Path, data: &[u8]) -> Result<(), Error> {
+    std::fs::write(path, data)
+        .map_err(|error| format!("failed to write {}: {error}", path.display()).into())
+}
+
+fn read_schema(path: &Path) -> Result<DynLayout, Error> {
+    serde_json::from_slice(&read(path)?)
+        .map_err(|error| format!("invalid schema {}: {error}", path.display()).into())
+}
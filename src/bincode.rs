@@ -0,0 +1,53 @@
+use crate::{
+    deserialize_with, serialize_with, ConstReadBuffer, ConstWriteBuffer, LengthFormat,
+    PrimitiveFormat, SerializeConst, TagFormat, WireFormat,
+};
+
+/// The [`WireFormat`] of [`serialize_bincode`], compatible with bincode with fixed size integers
+pub struct BincodeFormat;
+
+impl WireFormat for BincodeFormat {
+    const PRIMITIVES: PrimitiveFormat = PrimitiveFormat::LittleEndian;
+    const ENUM_TAGS: TagFormat = TagFormat::IndexU32;
+    const LIST_LENGTHS: LengthFormat = LengthFormat::Omitted;
+    const STRUCT_LENGTHS: LengthFormat = LengthFormat::Omitted;
+}
+
+/// Serialize a value with the same bytes as the [bincode](https://docs.rs/bincode) crate with fixed size
+/// little-endian integers. This is the format of `bincode::serialize` in bincode 1 and
+/// `bincode::config::legacy()` in bincode 2.
+///
+/// Every primitive is written little-endian at its full size, even on big-endian targets. Enums write the index
+/// of the variant as a `u32` before the fields of the variant. Serde numbers variants in the order they are
+/// declared, so this is the position of the variant rather than its discriminant.
+///
+/// ```rust
+/// use const_serialize::{serialize_bincode, ConstWriteBuffer, SerializeConst};
+///
+/// #[derive(Clone, Copy, SerializeConst)]
+/// #[repr(C, u8)]
+/// enum Message {
+///     Ping,
+///     Move { x: i16, y: i16 } = 10,
+/// }
+///
+/// const BUF: ConstWriteBuffer =
+///     serialize_bincode(&Message::Move { x: 1, y: -1 }, ConstWriteBuffer::new());
+/// assert_eq!(BUF.as_ref(), [1, 0, 0, 0, 1, 0, 0xff, 0xff]);
+/// ```
+#[must_use = "The data is serialized into the returned buffer"]
+pub const fn serialize_bincode<T: SerializeConst>(
+    data: &T,
+    to: ConstWriteBuffer,
+) -> ConstWriteBuffer {
+    serialize_with::<BincodeFormat, T>(data, to)
+}
+
+/// Deserialize a value written by [`serialize_bincode`] or bincode. Returns None if the buffer is too short, the
+/// enum variant is unknown or a bool is invalid.
+#[must_use = "The data is deserialized from the input buffer"]
+pub const fn deserialize_bincode<T: SerializeConst>(
+    from: ConstReadBuffer,
+) -> Option<(ConstReadBuffer, T)> {
+    deserialize_with::<BincodeFormat, T>(from)
+}
@@ -0,0 +1,54 @@
+use crate::{
+    deserialize_with, serialize_with, ConstReadBuffer, ConstWriteBuffer, LengthFormat,
+    PrimitiveFormat, SerializeConst, TagFormat, WireFormat,
+};
+
+/// The [`WireFormat`] of [`serialize_compact`], compatible with postcard
+pub struct CompactFormat;
+
+impl WireFormat for CompactFormat {
+    const PRIMITIVES: PrimitiveFormat = PrimitiveFormat::Varint;
+    const ENUM_TAGS: TagFormat = TagFormat::IndexVarint;
+    const LIST_LENGTHS: LengthFormat = LengthFormat::Omitted;
+    const STRUCT_LENGTHS: LengthFormat = LengthFormat::Omitted;
+}
+
+/// Serialize a value in a compact format that uses the same bytes as the
+/// [postcard](https://docs.rs/postcard) crate for the equivalent serde types.
+///
+/// Integers larger than a byte are written as LEB128 varints, and signed integers are zigzag encoded first so
+/// small negative numbers stay small. Enums write the index of the variant as a varint before the fields of the
+/// variant. Floats are written little-endian and arrays are written item by item without a length.
+///
+/// Serde numbers variants in the order they are declared, so the index written here is the position of the
+/// variant rather than its discriminant.
+///
+/// ```rust
+/// use const_serialize::{serialize_compact, ConstWriteBuffer, SerializeConst};
+///
+/// #[derive(Clone, Copy, SerializeConst)]
+/// struct Reading {
+///     sensor: u64,
+///     delta: i32,
+/// }
+///
+/// const BUF: ConstWriteBuffer =
+///     serialize_compact(&Reading { sensor: 300, delta: -2 }, ConstWriteBuffer::new());
+/// assert_eq!(BUF.as_ref(), [0xac, 0x02, 0x03]);
+/// ```
+#[must_use = "The data is serialized into the returned buffer"]
+pub const fn serialize_compact<T: SerializeConst>(
+    data: &T,
+    to: ConstWriteBuffer,
+) -> ConstWriteBuffer {
+    serialize_with::<CompactFormat, T>(data, to)
+}
+
+/// Deserialize a value written by [`serialize_compact`] or postcard. Returns None if the buffer is too short, a
+/// varint is too long for its type, the enum variant is unknown or a bool is invalid.
+#[must_use = "The data is deserialized from the input buffer"]
+pub const fn deserialize_compact<T: SerializeConst>(
+    from: ConstReadBuffer,
+) -> Option<(ConstReadBuffer, T)> {
+    deserialize_with::<CompactFormat, T>(from)
+}
@@ -0,0 +1,231 @@
+use std::cmp::Ordering;
+
+use crate::fingerprint::ConstHasher;
+use crate::{
+    EnumEncoding, Layout, PrimitiveEncoding, PrimitiveKind, SerializeConst, StructEncoding,
+};
+
+/// Check if two values are equal by comparing their fields through the layout. Padding is ignored.
+/// Floats are compared by their bits like [`f64::total_cmp`], so `NaN` equals itself and `-0.0` does not equal `0.0`.
+pub const fn const_eq<T: SerializeConst>(first: &T, second: &T) -> bool {
+    const_cmp(first, second).is_eq()
+}
+
+/// Compare two values field by field through the layout, in the order the fields are declared. Enums are
+/// ordered by their discriminant first, arrays are ordered lexicographically and floats are ordered like
+/// [`f64::total_cmp`]. Padding is ignored.
+pub const fn const_cmp<T: SerializeConst>(first: &T, second: &T) -> Ordering {
+    cmp_ptr(
+        first as *const T as *const u8,
+        second as *const T as *const u8,
+        &T::MEMORY_LAYOUT,
+    )
+}
+
+/// Hash a value through its layout with 64 bit FNV-1a. Padding is ignored, so values that are equal
+/// according to [`const_eq`] have the same hash.
+pub const fn const_hash<T: SerializeConst>(value: &T) -> u64 {
+    hash_ptr(
+        ConstHasher::new(),
+        value as *const T as *const u8,
+        &T::MEMORY_LAYOUT,
+    )
+    .finish()
+}
+
+const fn cmp_ptr(first: *const u8, second: *const u8, layout: &Layout) -> Ordering {
+    match layout {
+        Layout::Enum(encoding) => cmp_enum(first, second, encoding),
+        Layout::Struct(encoding) => cmp_struct(first, second, encoding),
+        Layout::List(encoding) => {
+            let size = encoding.item_encoding.size();
+            let mut i = 0;
+            while i < encoding.len {
+                let ordering = unsafe {
+                    cmp_ptr(
+                        first.add(i * size),
+                        second.add(i * size),
+                        encoding.item_encoding,
+                    )
+                };
+                if !ordering.is_eq() {
+                    return ordering;
+                }
+                i += 1;
+            }
+            Ordering::Equal
+        }
+        Layout::Primitive(encoding) => cmp_primitive(first, second, encoding),
+    }
+}
+
+const fn cmp_struct(first: *const u8, second: *const u8, encoding: &StructEncoding) -> Ordering {
+    let mut i = 0;
+    while i < encoding.data.len() {
+        let field = &encoding.data[i];
+        let ordering = unsafe {
+            cmp_ptr(
+                first.add(field.offset),
+                second.add(field.offset),
+                &field.encoding,
+            )
+        };
+        if !ordering.is_eq() {
+            return ordering;
+        }
+        i += 1;
+    }
+    Ordering::Equal
+}
+
+const fn cmp_enum(first: *const u8, second: *const u8, encoding: &EnumEncoding) -> Ordering {
+    let first_tag = read_tag(first, encoding);
+    let second_tag = read_tag(second, encoding);
+    if first_tag != second_tag {
+        return if first_tag < second_tag {
+            Ordering::Less
+        } else {
+            Ordering::Greater
+        };
+    }
+    let mut i = 0;
+    while i < encoding.variants.len() {
+        let variant = &encoding.variants[i];
+        if variant.tag as u64 == first_tag {
+            return unsafe {
+                cmp_struct(
+                    first.add(encoding.variants_offset),
+                    second.add(encoding.variants_offset),
+                    &variant.data,
+                )
+            };
+        }
+        i += 1;
+    }
+    Ordering::Equal
+}
+
+/// Read the discriminant of an enum from memory
+pub(crate) const fn read_tag(ptr: *const u8, encoding: &EnumEncoding) -> u64 {
+    let mut tag = 0;
+    let mut i = 0;
+    // Only the first 8 bytes fit in the tag. Memory always holds one of the u32 variant tags, so any bytes after
+    // the first four are zero.
+    while i < encoding.discriminant.size && i < 8 {
+        tag |= (unsafe { byte_le(ptr, encoding.discriminant.size, i) } as u64) << (i * 8);
+        i += 1;
+    }
+    tag
+}
+
+/// Read the byte of a number in memory at the index counting from the least significant byte
+pub(crate) const unsafe fn byte_le(ptr: *const u8, size: usize, index: usize) -> u8 {
+    if cfg!(target_endian = "big") {
+        unsafe { ptr.add(size - index - 1).read() }
+    } else {
+        unsafe { ptr.add(index).read() }
+    }
+}
+
+/// Map the byte of a primitive at the index counting from the most significant byte to a byte that
+/// orders the same way as the primitive when the bytes are compared as an unsigned number
+pub(crate) const fn ordered_byte(ptr: *const u8, encoding: &PrimitiveEncoding, index: usize) -> u8 {
+    let size = encoding.size;
+    let byte = unsafe { byte_le(ptr, size, size - index - 1) };
+    match encoding.kind {
+        PrimitiveKind::Unsigned | PrimitiveKind::Bool => byte,
+        PrimitiveKind::Signed => {
+            if index == 0 {
+                byte ^ 0x80
+            } else {
+                byte
+            }
+        }
+        PrimitiveKind::Float => {
+            // Negative floats are ordered backwards, so flip every bit. Positive floats only need the sign bit set.
+            let negative = unsafe { byte_le(ptr, size, size - 1) } & 0x80 != 0;
+            if negative {
+                !byte
+            } else if index == 0 {
+                byte ^ 0x80
+            } else {
+                byte
+            }
+        }
+    }
+}
+
+const fn cmp_primitive(
+    first: *const u8,
+    second: *const u8,
+    encoding: &PrimitiveEncoding,
+) -> Ordering {
+    let mut i = 0;
+    while i < encoding.size {
+        let first = ordered_byte(first, encoding, i);
+        let second = ordered_byte(second, encoding, i);
+        if first != second {
+            return if first < second {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            };
+        }
+        i += 1;
+    }
+    Ordering::Equal
+}
+
+const fn hash_ptr(mut hasher: ConstHasher, ptr: *const u8, layout: &Layout) -> ConstHasher {
+    match layout {
+        Layout::Enum(encoding) => {
+            let tag = read_tag(ptr, encoding);
+            hasher = hasher.write_u64(tag);
+            let mut i = 0;
+            while i < encoding.variants.len() {
+                let variant = &encoding.variants[i];
+                if variant.tag as u64 == tag {
+                    return hash_struct(
+                        hasher,
+                        unsafe { ptr.add(encoding.variants_offset) },
+                        &variant.data,
+                    );
+                }
+                i += 1;
+            }
+            hasher
+        }
+        Layout::Struct(encoding) => hash_struct(hasher, ptr, encoding),
+        Layout::List(encoding) => {
+            let size = encoding.item_encoding.size();
+            let mut i = 0;
+            while i < encoding.len {
+                hasher = hash_ptr(hasher, unsafe { ptr.add(i * size) }, encoding.item_encoding);
+                i += 1;
+            }
+            hasher
+        }
+        Layout::Primitive(encoding) => {
+            let mut i = 0;
+            while i < encoding.size {
+                hasher = hasher.write_u8(unsafe { byte_le(ptr, encoding.size, i) });
+                i += 1;
+            }
+            hasher
+        }
+    }
+}
+
+const fn hash_struct(
+    mut hasher: ConstHasher,
+    ptr: *const u8,
+    encoding: &StructEncoding,
+) -> ConstHasher {
+    let mut i = 0;
+    while i < encoding.data.len() {
+        let field = &encoding.data[i];
+        hasher = hash_ptr(hasher, unsafe { ptr.add(field.offset) }, &field.encoding);
+        i += 1;
+    }
+    hasher
+}
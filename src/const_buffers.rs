@@ -1,5 +1,6 @@
-use crate::const_vec::ConstVec;
+use crate::const_vec::{ConstVec, MAX_SIZE};
 
+#[derive(Clone, Copy)]
 pub struct ConstReadBuffer<'a> {
     location: usize,
     memory: &'a [u8],
@@ -25,6 +26,17 @@ impl<'a> ConstReadBuffer<'a> {
     pub const fn as_ref(&self) -> &[u8] {
         self.memory
     }
+
+    /// The bytes that have not been read yet
+    pub(crate) const fn remaining(&self) -> &[u8] {
+        self.memory.split_at(self.location).1
+    }
+
+    /// Move past bytes without reading them. The length must not be more than the remaining bytes.
+    pub(crate) const fn advance(mut self, len: usize) -> Self {
+        self.location += len;
+        self
+    }
 }
 
 pub struct ConstWriteBuffer {
@@ -38,6 +50,9 @@ impl Default for ConstWriteBuffer {
 }
 
 impl ConstWriteBuffer {
+    /// The most bytes a buffer can hold
+    pub const CAPACITY: usize = MAX_SIZE;
+
     pub const fn new() -> Self {
         Self {
             memory: ConstVec::new(),
@@ -57,12 +72,17 @@ impl ConstWriteBuffer {
         self.memory.as_ref()
     }
 
+    /// The written bytes, which can be changed in place
+    pub const fn as_mut(&mut self) -> &mut [u8] {
+        self.memory.as_mut()
+    }
+
     /// Get the underlying const vec for this buffer
     pub const fn inner(self) -> ConstVec<u8> {
         self.memory
     }
 
-    pub const fn read(&self) -> ConstReadBuffer {
+    pub const fn read(&self) -> ConstReadBuffer<'_> {
         ConstReadBuffer::new(self.memory.as_ref())
     }
 }
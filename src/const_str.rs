@@ -0,0 +1,96 @@
+use std::fmt::{Debug, Display};
+
+use crate::const_vec::{ConstVec, MAX_SIZE};
+
+/// A string that can be built in const. Like [`ConstVec`], it can hold up to 512 bytes.
+pub struct ConstStr {
+    bytes: ConstVec<u8>,
+}
+
+impl Default for ConstStr {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Debug for ConstStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl Display for ConstStr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl PartialEq<str> for ConstStr {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl ConstStr {
+    /// The most bytes a string can hold
+    pub const CAPACITY: usize = MAX_SIZE;
+
+    pub const fn new() -> Self {
+        Self {
+            bytes: ConstVec::new(),
+        }
+    }
+
+    /// Append a string
+    pub const fn push_str(mut self, string: &str) -> Self {
+        let bytes = string.as_bytes();
+        let mut i = 0;
+        while i < bytes.len() {
+            self.bytes = self.bytes.push(bytes[i]);
+            i += 1;
+        }
+        self
+    }
+
+    /// Append a character
+    pub const fn push(self, character: char) -> Self {
+        let mut bytes = [0; 4];
+        let encoded = character.encode_utf8(&mut bytes);
+        self.push_str(encoded)
+    }
+
+    /// The length of the string in bytes
+    pub const fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.bytes.len() == 0
+    }
+
+    pub const fn as_str(&self) -> &str {
+        // SAFETY: only complete UTF-8 strings are pushed into the bytes
+        unsafe { std::str::from_utf8_unchecked(self.bytes.as_ref()) }
+    }
+}
+
+/// Append a number in decimal
+pub(crate) const fn push_number(mut to: ConstStr, number: usize) -> ConstStr {
+    let mut digits = 1;
+    while number / digits >= 10 {
+        digits *= 10;
+    }
+    while digits > 0 {
+        to = to.push((b'0' + (number / digits % 10) as u8) as char);
+        digits /= 10;
+    }
+    to
+}
+
+#[test]
+fn test_const_str() {
+    const STRING: ConstStr = ConstStr::new().push_str("hello ").push('w').push('ö');
+    assert_eq!(STRING.as_str(), "hello wö");
+    assert_eq!(STRING.len(), 9);
+    assert!(ConstStr::new().is_empty());
+}
@@ -1,7 +1,7 @@
 #![allow(dead_code)]
 use std::{fmt::Debug, mem::MaybeUninit};
 
-const MAX_SIZE: usize = 2usize.pow(9);
+pub(crate) const MAX_SIZE: usize = 2usize.pow(9);
 
 pub struct ConstVec<T> {
     memory: [MaybeUninit<T>; MAX_SIZE],
@@ -32,6 +32,9 @@ impl<T> ConstVec<T> {
     }
 
     pub const fn push(mut self, value: T) -> Self {
+        if self.len == MAX_SIZE {
+            panic!("ConstVec is full: const buffers can hold at most 512 items");
+        }
         self.memory[self.len] = MaybeUninit::new(value);
         self.len += 1;
         self
@@ -53,6 +56,10 @@ impl<T> ConstVec<T> {
         unsafe { &*(self.memory.split_at(self.len).0 as *const [MaybeUninit<T>] as *const [T]) }
     }
 
+    pub const fn as_mut(&mut self) -> &mut [T] {
+        unsafe { &mut *(self.memory.split_at_mut(self.len).0 as *mut [MaybeUninit<T>] as *mut [T]) }
+    }
+
     pub const fn swap(mut self, first: usize, second: usize) -> Self
     where
         T: Copy,
@@ -0,0 +1,283 @@
+use std::fmt::Display;
+
+use crate::pretty::primitive_name;
+use crate::{DynEnumEncoding, DynLayout, DynStructEncoding, FieldValue, Value};
+
+/// A difference between two values or two layouts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Difference {
+    /// The path to the part that is different, like `d.A.one` or `c[1]`
+    pub path: String,
+    /// A description of the left side, or `(missing)` if it only exists on the right side
+    pub left: String,
+    /// A description of the right side, or `(missing)` if it only exists on the left side
+    pub right: String,
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path = if self.path.is_empty() {
+            "(root)"
+        } else {
+            &self.path
+        };
+        write!(f, "{path}: {} -> {}", self.left, self.right)
+    }
+}
+
+const MISSING: &str = "(missing)";
+
+/// Find the differences between two decoded values. Fields with the same name are compared with each other, even if they are in a different order.
+pub fn diff_values(left: &Value, right: &Value) -> Vec<Difference> {
+    let mut differ = Differ::default();
+    differ.diff_value(left, right);
+    differ.differences
+}
+
+impl DynLayout {
+    /// Find the differences between two layouts. Fields with the same name and variants with the same tag are compared with each other.
+    pub fn diff(&self, other: &DynLayout) -> Vec<Difference> {
+        let mut differ = Differ::default();
+        differ.diff_layout(self, other);
+        differ.differences
+    }
+}
+
+#[derive(Default)]
+struct Differ {
+    path: String,
+    differences: Vec<Difference>,
+}
+
+impl Differ {
+    fn push(&mut self, left: impl Into<String>, right: impl Into<String>) {
+        self.differences.push(Difference {
+            path: self.path.clone(),
+            left: left.into(),
+            right: right.into(),
+        });
+    }
+
+    /// Run the closure with a segment added to the path
+    fn with_segment(&mut self, segment: &str, f: impl FnOnce(&mut Self)) {
+        let len = self.path.len();
+        if !self.path.is_empty() && !segment.starts_with('[') {
+            self.path.push('.');
+        }
+        self.path.push_str(segment);
+        f(self);
+        self.path.truncate(len);
+    }
+
+    /// Pair up the items on both sides by name, or by position if any item is unnamed
+    fn diff_named<T>(
+        &mut self,
+        left: &[T],
+        right: &[T],
+        name: impl Fn(&T) -> Option<&str>,
+        mut diff: impl FnMut(&mut Self, Option<&T>, Option<&T>),
+    ) {
+        let named = left.iter().chain(right).all(|item| name(item).is_some());
+        if named {
+            for item in left {
+                let segment = name(item).unwrap();
+                let other = right.iter().find(|other| name(other) == Some(segment));
+                self.with_segment(segment, |differ| diff(differ, Some(item), other));
+            }
+            for item in right {
+                let segment = name(item).unwrap();
+                if !left.iter().any(|other| name(other) == Some(segment)) {
+                    self.with_segment(segment, |differ| diff(differ, None, Some(item)));
+                }
+            }
+        } else {
+            for i in 0..left.len().max(right.len()) {
+                self.with_segment(&i.to_string(), |differ| {
+                    diff(differ, left.get(i), right.get(i))
+                });
+            }
+        }
+    }
+
+    fn diff_value(&mut self, left: &Value, right: &Value) {
+        match (left, right) {
+            (Value::Struct(left), Value::Struct(right)) => {
+                self.diff_fields(&left.fields, &right.fields)
+            }
+            (Value::Enum(left), Value::Enum(right))
+                if left.tag == right.tag && left.variant == right.variant =>
+            {
+                let segment = match &left.variant {
+                    Some(variant) => variant.clone(),
+                    None => left.tag.to_string(),
+                };
+                self.with_segment(&segment, |differ| {
+                    differ.diff_fields(&left.fields, &right.fields)
+                });
+            }
+            (Value::List(left), Value::List(right)) if left.len() == right.len() => {
+                for (i, (left, right)) in left.iter().zip(right).enumerate() {
+                    self.with_segment(&format!("[{i}]"), |differ| differ.diff_value(left, right));
+                }
+            }
+            (Value::Primitive(left_primitive), Value::Primitive(right_primitive)) => {
+                if left_primitive != right_primitive {
+                    self.push(describe_value(left), describe_value(right));
+                }
+            }
+            _ => self.push(describe_value(left), describe_value(right)),
+        }
+    }
+
+    fn diff_fields(&mut self, left: &[FieldValue], right: &[FieldValue]) {
+        self.diff_named(
+            left,
+            right,
+            |field| field.name.as_deref(),
+            |differ, left, right| match (left, right) {
+                (Some(left), Some(right)) => differ.diff_value(&left.value, &right.value),
+                (Some(left), None) => differ.push(describe_value(&left.value), MISSING),
+                (None, Some(right)) => differ.push(MISSING, describe_value(&right.value)),
+                (None, None) => {}
+            },
+        );
+    }
+
+    fn diff_layout(&mut self, left: &DynLayout, right: &DynLayout) {
+        match (left, right) {
+            (DynLayout::Struct(left_encoding), DynLayout::Struct(right_encoding))
+                if left_encoding.name == right_encoding.name =>
+            {
+                self.diff_struct(left_encoding, right_encoding)
+            }
+            (DynLayout::Enum(left_encoding), DynLayout::Enum(right_encoding))
+                if left_encoding.name == right_encoding.name
+                    && left_encoding.discriminant == right_encoding.discriminant =>
+            {
+                self.diff_enum(left_encoding, right_encoding)
+            }
+            (DynLayout::List(left_encoding), DynLayout::List(right_encoding))
+                if left_encoding.len == right_encoding.len =>
+            {
+                self.with_segment("[]", |differ| {
+                    differ.diff_layout(&left_encoding.item_encoding, &right_encoding.item_encoding)
+                })
+            }
+            _ => {
+                if left != right {
+                    self.push(describe_layout(left), describe_layout(right))
+                }
+            }
+        }
+    }
+
+    fn diff_struct(&mut self, left: &DynStructEncoding, right: &DynStructEncoding) {
+        self.diff_named(
+            &left.data,
+            &right.data,
+            |field| field.name.as_deref(),
+            |differ, left, right| match (left, right) {
+                (Some(left), Some(right)) => differ.diff_layout(&left.encoding, &right.encoding),
+                (Some(left), None) => differ.push(describe_layout(&left.encoding), MISSING),
+                (None, Some(right)) => differ.push(MISSING, describe_layout(&right.encoding)),
+                (None, None) => {}
+            },
+        );
+        // Fields that match by name may still be in a different order, which changes the serialized data
+        let order = |encoding: &DynStructEncoding| {
+            encoding
+                .data
+                .iter()
+                .filter_map(|field| field.name.clone())
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        let (left_order, right_order) = (order(left), order(right));
+        let same_fields = left.data.len() == right.data.len()
+            && left.data.iter().all(|field| {
+                right
+                    .data
+                    .iter()
+                    .any(|other| field.name.is_some() && other.name == field.name)
+            });
+        if same_fields && left_order != right_order {
+            self.push(
+                format!("fields {{ {left_order} }}"),
+                format!("fields {{ {right_order} }}"),
+            );
+        }
+    }
+
+    fn diff_enum(&mut self, left: &DynEnumEncoding, right: &DynEnumEncoding) {
+        for variant in &left.variants {
+            let segment = variant_segment(variant.tag, variant.data.name.as_deref());
+            let other = right.variants.iter().find(|other| other.tag == variant.tag);
+            self.with_segment(&segment, |differ| match other {
+                Some(other) if other.data.name == variant.data.name => {
+                    differ.diff_struct(&variant.data, &other.data)
+                }
+                Some(other) => differ.push(
+                    variant_segment(variant.tag, variant.data.name.as_deref()),
+                    variant_segment(other.tag, other.data.name.as_deref()),
+                ),
+                None => differ.push(format!("tag {}", variant.tag), MISSING),
+            });
+        }
+        for variant in &right.variants {
+            if !left.variants.iter().any(|other| other.tag == variant.tag) {
+                let segment = variant_segment(variant.tag, variant.data.name.as_deref());
+                self.with_segment(&segment, |differ| {
+                    differ.push(MISSING, format!("tag {}", variant.tag))
+                });
+            }
+        }
+    }
+}
+
+fn variant_segment(tag: u32, name: Option<&str>) -> String {
+    match name {
+        Some(name) => name.to_string(),
+        None => tag.to_string(),
+    }
+}
+
+/// A short description of a value, like `42`, `Enum::A` or `[..; 3]`
+fn describe_value(value: &Value) -> String {
+    match value {
+        Value::Struct(value) => match &value.name {
+            Some(name) => format!("{name} {{ .. }}"),
+            None => "{ .. }".to_string(),
+        },
+        Value::Enum(value) => {
+            let variant = variant_segment(value.tag, value.variant.as_deref());
+            match &value.name {
+                Some(name) => format!("{name}::{variant}"),
+                None => variant,
+            }
+        }
+        Value::List(values) => format!("[..; {}]", values.len()),
+        Value::Primitive(primitive) => primitive.to_string(),
+    }
+}
+
+/// A short description of a layout, like `u32`, `struct Struct` or `[u8; 3]`
+fn describe_layout(layout: &DynLayout) -> String {
+    match layout {
+        DynLayout::Enum(encoding) => match &encoding.name {
+            Some(name) => format!("enum {name}"),
+            None => "enum".to_string(),
+        },
+        DynLayout::Struct(encoding) => match &encoding.name {
+            Some(name) => format!("struct {name}"),
+            None => "struct".to_string(),
+        },
+        DynLayout::List(encoding) => {
+            format!(
+                "[{}; {}]",
+                describe_layout(&encoding.item_encoding),
+                encoding.len
+            )
+        }
+        DynLayout::Primitive(encoding) => primitive_name(encoding),
+    }
+}
@@ -0,0 +1,685 @@
+use std::fmt::Display;
+
+use crate::{
+    variants_offset, EnumEncoding, EnumVariant, FieldDefault, Layout, ListEncoding, PlainOldData,
+    PrimitiveEncoding, StructEncoding,
+};
+
+/// An owned version of [`PlainOldData`]. Stores the offset of the field in the struct and the encoding of the field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DynPlainOldData {
+    pub(crate) offset: usize,
+    #[cfg_attr(feature = "serde", serde(rename = "layout"))]
+    pub(crate) encoding: DynLayout,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub(crate) name: Option<String>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub(crate) id: Option<u32>,
+    /// The default value, serialized with the layout of the field
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub(crate) default: Option<Vec<u8>>,
+}
+
+impl DynPlainOldData {
+    pub fn new(offset: usize, encoding: DynLayout) -> Self {
+        Self {
+            offset,
+            encoding,
+            name: None,
+            id: None,
+            default: None,
+        }
+    }
+
+    /// Set the name of the field
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
+    /// Set the stable id of the field. See [`PlainOldData::with_id`].
+    pub fn with_id(mut self, id: u32) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Set the default value of the field, serialized with the layout of the field. See [`PlainOldData::with_default`].
+    pub fn with_default(mut self, serialized: Vec<u8>) -> Self {
+        self.default = Some(serialized);
+        self
+    }
+}
+
+/// An owned version of [`StructEncoding`]. The struct encoding is just a list of fields with offsets
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DynStructEncoding {
+    pub(crate) size: usize,
+    #[cfg_attr(feature = "serde", serde(rename = "fields"))]
+    pub(crate) data: Vec<DynPlainOldData>,
+    #[cfg_attr(
+        feature = "serde",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub(crate) name: Option<String>,
+}
+
+impl DynStructEncoding {
+    pub fn new(size: usize, data: Vec<DynPlainOldData>) -> Self {
+        Self {
+            size,
+            data,
+            name: None,
+        }
+    }
+
+    /// Set the name of the struct. For the data of an enum variant, this is the name of the variant.
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+}
+
+/// An owned version of [`EnumEncoding`]. The enum encoding is just a discriminate size and a tag encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(from = "serde_impl::EnumSchema", into = "serde_impl::EnumSchema")
+)]
+pub struct DynEnumEncoding {
+    pub(crate) size: usize,
+    pub(crate) discriminant: PrimitiveEncoding,
+    pub(crate) variants_offset: usize,
+    pub(crate) variants: Vec<DynEnumVariant>,
+    pub(crate) name: Option<String>,
+}
+
+impl DynEnumEncoding {
+    pub fn new(
+        size: usize,
+        discriminant: PrimitiveEncoding,
+        variants: Vec<DynEnumVariant>,
+    ) -> Self {
+        let max_align = variants
+            .iter()
+            .map(|variant| variant.align)
+            .fold(1, usize::max);
+        let variants_offset = variants_offset(&discriminant, max_align);
+
+        Self {
+            size,
+            discriminant,
+            variants_offset,
+            variants,
+            name: None,
+        }
+    }
+
+    /// Set the name of the enum
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+}
+
+/// An owned version of [`EnumVariant`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DynEnumVariant {
+    // Note: tags may not be sequential
+    pub(crate) tag: u32,
+    pub(crate) data: DynStructEncoding,
+    pub(crate) align: usize,
+}
+
+impl DynEnumVariant {
+    pub fn new(tag: u32, data: DynStructEncoding, align: usize) -> Self {
+        Self { tag, data, align }
+    }
+}
+
+/// An owned version of [`ListEncoding`]. The array encoding is just a length and an item encoding.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DynListEncoding {
+    pub(crate) len: usize,
+    #[cfg_attr(feature = "serde", serde(rename = "item"))]
+    pub(crate) item_encoding: Box<DynLayout>,
+}
+
+impl DynListEncoding {
+    pub fn new(len: usize, item_encoding: DynLayout) -> Self {
+        Self {
+            len,
+            item_encoding: Box::new(item_encoding),
+        }
+    }
+}
+
+/// An owned version of [`Layout`] that can be built or loaded at runtime.
+///
+/// A `DynLayout` describes the same memory layout and wire format as a [`Layout`], but it does not
+/// need to correspond to a rust type that exists in the current program. Memory images are plain
+/// byte slices with the size and field offsets the layout describes.
+///
+/// With the `serde` feature, layouts can be saved to and loaded from schema files. Loaded layouts are checked
+/// with [`DynLayout::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "serde_impl::LayoutSchema")
+)]
+pub enum DynLayout {
+    Enum(DynEnumEncoding),
+    Struct(DynStructEncoding),
+    List(DynListEncoding),
+    Primitive(PrimitiveEncoding),
+}
+
+/// An error in a [`DynLayout`] found by [`DynLayout::validate`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LayoutError {
+    /// The size of a list doesn't fit in a usize
+    SizeOverflow,
+    /// A field, an enum discriminant or the fields of an enum variant end after the end of the type that holds them
+    OutOfBounds { end: usize, size: usize },
+    /// The default value of a field is not exactly one serialized value of the field
+    InvalidDefault,
+}
+
+impl Display for LayoutError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LayoutError::SizeOverflow => write!(f, "the size of a list doesn't fit in a usize"),
+            LayoutError::OutOfBounds { end, size } => write!(
+                f,
+                "a field ends at byte {end}, but the type that holds it is only {size} bytes"
+            ),
+            LayoutError::InvalidDefault => write!(
+                f,
+                "the default value of a field doesn't match the layout of the field"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+impl DynLayout {
+    /// Check that the sizes of lists fit in a usize, every field fits in the struct or enum variant that holds it,
+    /// the discriminant and variants of every enum fit in the enum, and every field default is one serialized
+    /// value of the field.
+    ///
+    /// Layouts converted from a [`Layout`] are always valid, and layouts loaded with serde or
+    /// [`DynLayout::from_schema`] are validated when they are loaded. Other methods may panic on a layout that
+    /// is not valid.
+    pub fn validate(&self) -> Result<(), LayoutError> {
+        // Check the sizes first, so the defaults can be read with the layouts of their fields
+        self.checked_size()?;
+        self.check_defaults()
+    }
+
+    /// The size of the type, after checking that everything in the type fits inside it
+    fn checked_size(&self) -> Result<usize, LayoutError> {
+        match self {
+            DynLayout::Enum(encoding) => {
+                check_end(0, encoding.discriminant.size, encoding.size)?;
+                for variant in &encoding.variants {
+                    let size = variant.data.checked_size()?;
+                    check_end(encoding.variants_offset, size, encoding.size)?;
+                }
+                Ok(encoding.size)
+            }
+            DynLayout::Struct(encoding) => encoding.checked_size(),
+            DynLayout::List(encoding) => encoding
+                .len
+                .checked_mul(encoding.item_encoding.checked_size()?)
+                .ok_or(LayoutError::SizeOverflow),
+            DynLayout::Primitive(encoding) => Ok(encoding.size),
+        }
+    }
+
+    fn check_defaults(&self) -> Result<(), LayoutError> {
+        match self {
+            DynLayout::Enum(encoding) => encoding
+                .variants
+                .iter()
+                .try_for_each(|variant| variant.data.check_defaults()),
+            DynLayout::Struct(encoding) => encoding.check_defaults(),
+            DynLayout::List(encoding) => encoding.item_encoding.check_defaults(),
+            DynLayout::Primitive(_) => Ok(()),
+        }
+    }
+
+    /// The size of the type in bytes.
+    pub fn size(&self) -> usize {
+        match self {
+            DynLayout::Enum(encoding) => encoding.size,
+            DynLayout::Struct(encoding) => encoding.size,
+            DynLayout::List(encoding) => encoding.len * encoding.item_encoding.size(),
+            DynLayout::Primitive(encoding) => encoding.size,
+        }
+    }
+
+    /// Convert this layout into a [`Layout`].
+    ///
+    /// [`Layout`] only holds `'static` references, so the fields, variants, list items and field defaults of this
+    /// layout are leaked.
+    ///
+    /// # Panics
+    /// Panics if the default value of a field can't be deserialized with the layout of the field
+    pub fn into_layout(self) -> Layout {
+        match self {
+            DynLayout::Enum(encoding) => Layout::Enum(encoding.into_encoding()),
+            DynLayout::Struct(encoding) => Layout::Struct(encoding.into_encoding()),
+            DynLayout::List(encoding) => Layout::List(ListEncoding::new(
+                encoding.len,
+                Box::leak(Box::new(encoding.item_encoding.into_layout())),
+            )),
+            DynLayout::Primitive(encoding) => Layout::Primitive(encoding),
+        }
+    }
+
+    /// Serialize the memory image of a value with this layout. This has the same output as [`crate::serialize_const`] for the type the layout describes.
+    ///
+    /// # Panics
+    /// Panics if the memory is not exactly [`DynLayout::size`] bytes long
+    pub fn serialize(&self, memory: &[u8]) -> Vec<u8> {
+        assert_eq!(
+            memory.len(),
+            self.size(),
+            "the memory image must be the size of the layout"
+        );
+        let mut to = Vec::new();
+        serialize_dyn(memory, &mut to, self);
+        to
+    }
+
+    /// Deserialize a buffer into the memory image of a value with this layout. This will return None if the buffer doesn't have enough data to fill the type, a discriminant doesn't match any variant or a bool is not 0 or 1.
+    ///
+    /// Bytes that are not part of any field (padding) are set to zero.
+    pub fn deserialize(&self, from: &[u8]) -> Option<Vec<u8>> {
+        let mut out = vec![0; self.size()];
+        deserialize_dyn(from, self, Some(&mut out))?;
+        Some(out)
+    }
+}
+
+impl DynStructEncoding {
+    fn checked_size(&self) -> Result<usize, LayoutError> {
+        for field in &self.data {
+            let size = field.encoding.checked_size()?;
+            check_end(field.offset, size, self.size)?;
+        }
+        Ok(self.size)
+    }
+
+    fn check_defaults(&self) -> Result<(), LayoutError> {
+        for field in &self.data {
+            field.encoding.check_defaults()?;
+            if let Some(default) = &field.default {
+                // Check the default without allocating the memory of the field
+                if deserialize_dyn(default, &field.encoding, None) != Some(&[]) {
+                    return Err(LayoutError::InvalidDefault);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn into_encoding(self) -> StructEncoding {
+        let data: Vec<_> = self
+            .data
+            .into_iter()
+            .map(|field| {
+                let mut data = PlainOldData::new(field.offset, field.encoding.into_layout());
+                if let Some(name) = field.name {
+                    data = data.with_name(name.leak());
+                }
+                if let Some(id) = field.id {
+                    data = data.with_id(id);
+                }
+                if let Some(default) = field.default {
+                    data = data.with_default_serialized(default.leak());
+                }
+                data
+            })
+            .collect();
+        let encoding = StructEncoding::new(self.size, data.leak());
+        match self.name {
+            Some(name) => encoding.with_name(name.leak()),
+            None => encoding,
+        }
+    }
+}
+
+impl DynEnumEncoding {
+    fn into_encoding(self) -> EnumEncoding {
+        let variants: Vec<_> = self
+            .variants
+            .into_iter()
+            .map(|variant| {
+                EnumVariant::new(variant.tag, variant.data.into_encoding(), variant.align)
+            })
+            .collect();
+        let encoding = EnumEncoding::new(self.size, self.discriminant, variants.leak());
+        match self.name {
+            Some(name) => encoding.with_name(name.leak()),
+            None => encoding,
+        }
+    }
+}
+
+impl From<&StructEncoding> for DynStructEncoding {
+    fn from(encoding: &StructEncoding) -> Self {
+        Self {
+            size: encoding.size,
+            data: encoding
+                .data
+                .iter()
+                .map(|field| {
+                    let encoding = DynLayout::from(&field.encoding);
+                    let default = field.default.map(|default| match default {
+                        FieldDefault::Memory(default) => {
+                            // SAFETY: the default points to a value with the layout of the field
+                            let memory =
+                                unsafe { std::slice::from_raw_parts(default, encoding.size()) };
+                            encoding.serialize(memory)
+                        }
+                        FieldDefault::Serialized(default) => default.to_vec(),
+                    });
+                    DynPlainOldData {
+                        offset: field.offset,
+                        encoding,
+                        name: field.name.map(String::from),
+                        id: field.id,
+                        default,
+                    }
+                })
+                .collect(),
+            name: encoding.name.map(String::from),
+        }
+    }
+}
+
+impl From<&EnumEncoding> for DynEnumEncoding {
+    fn from(encoding: &EnumEncoding) -> Self {
+        Self {
+            size: encoding.size,
+            discriminant: encoding.discriminant,
+            variants_offset: encoding.variants_offset,
+            variants: encoding
+                .variants
+                .iter()
+                .map(|variant| {
+                    DynEnumVariant::new(variant.tag, (&variant.data).into(), variant.align)
+                })
+                .collect(),
+            name: encoding.name.map(String::from),
+        }
+    }
+}
+
+impl From<&Layout> for DynLayout {
+    fn from(layout: &Layout) -> Self {
+        match layout {
+            Layout::Enum(encoding) => DynLayout::Enum(encoding.into()),
+            Layout::Struct(encoding) => DynLayout::Struct(encoding.into()),
+            Layout::List(encoding) => DynLayout::List(DynListEncoding::new(
+                encoding.len,
+                encoding.item_encoding.into(),
+            )),
+            Layout::Primitive(encoding) => DynLayout::Primitive(*encoding),
+        }
+    }
+}
+
+impl From<Layout> for DynLayout {
+    fn from(layout: Layout) -> Self {
+        (&layout).into()
+    }
+}
+
+/// Check that something at the offset with the size ends inside a type with the parent size
+fn check_end(offset: usize, size: usize, parent_size: usize) -> Result<(), LayoutError> {
+    match offset.checked_add(size) {
+        Some(end) if end <= parent_size => Ok(()),
+        end => Err(LayoutError::OutOfBounds {
+            end: end.unwrap_or(usize::MAX),
+            size: parent_size,
+        }),
+    }
+}
+
+/// Serialize the memory image of a type with the layout passed in
+fn serialize_dyn(memory: &[u8], to: &mut Vec<u8>, encoding: &DynLayout) {
+    match encoding {
+        DynLayout::Enum(encoding) => {
+            let discriminant_size = encoding.discriminant.size;
+            let discriminant = &memory[..discriminant_size];
+            // If the bytes are reversed, walk backwards from the end of the number when pushing bytes
+            if cfg!(target_endian = "big") {
+                to.extend(discriminant.iter().rev());
+            } else {
+                to.extend(discriminant);
+            }
+            let tag = read_tag(&to[to.len() - discriminant_size..]);
+            if let Some(variant) = encoding
+                .variants
+                .iter()
+                .find(|variant| u64::from(variant.tag) == tag)
+            {
+                serialize_dyn_struct(&memory[encoding.variants_offset..], to, &variant.data);
+            }
+        }
+        DynLayout::Struct(encoding) => serialize_dyn_struct(memory, to, encoding),
+        DynLayout::List(encoding) => {
+            let item_size = encoding.item_encoding.size();
+            for i in 0..encoding.len {
+                serialize_dyn(&memory[i * item_size..], to, &encoding.item_encoding);
+            }
+        }
+        DynLayout::Primitive(encoding) => {
+            let bytes = &memory[..encoding.size];
+            // If the bytes are reversed, walk backwards from the end of the number when pushing bytes
+            if cfg!(any(target_endian = "big", feature = "test-big-endian")) {
+                to.extend(bytes.iter().rev());
+            } else {
+                to.extend(bytes);
+            }
+        }
+    }
+}
+
+/// Serialize the memory image of a struct with the encoding passed in
+fn serialize_dyn_struct(memory: &[u8], to: &mut Vec<u8>, encoding: &DynStructEncoding) {
+    for field in &encoding.data {
+        serialize_dyn(&memory[field.offset..], to, &field.encoding);
+    }
+}
+
+/// Deserialize a type into the memory image passed in, or only check the data if there is no memory image.
+/// Returns the rest of the buffer after the type.
+fn deserialize_dyn<'a>(
+    from: &'a [u8],
+    encoding: &DynLayout,
+    mut out: Option<&mut [u8]>,
+) -> Option<&'a [u8]> {
+    match encoding {
+        DynLayout::Enum(encoding) => {
+            let discriminant_size = encoding.discriminant.size;
+            if from.len() < discriminant_size {
+                return None;
+            }
+            let (discriminant, from) = from.split_at(discriminant_size);
+            let tag = read_tag(discriminant);
+            if let Some(out) = out.as_deref_mut() {
+                // If the bytes are reversed, walk backwards from the end of the number when filling in bytes
+                out[..discriminant_size].copy_from_slice(discriminant);
+                if cfg!(target_endian = "big") {
+                    out[..discriminant_size].reverse();
+                }
+            }
+            let variant = encoding
+                .variants
+                .iter()
+                .find(|variant| u64::from(variant.tag) == tag)?;
+            let out = out.map(|out| &mut out[encoding.variants_offset..]);
+            deserialize_dyn_struct(from, &variant.data, out)
+        }
+        DynLayout::Struct(encoding) => deserialize_dyn_struct(from, encoding, out),
+        DynLayout::List(encoding) => {
+            let item_size = encoding.item_encoding.size();
+            let mut from = from;
+            for i in 0..encoding.len {
+                let item = out.as_deref_mut().map(|out| &mut out[i * item_size..]);
+                let rest = deserialize_dyn(from, &encoding.item_encoding, item)?;
+                // An item that doesn't read any data doesn't write any memory either, and every item after it
+                // reads the same nothing, so a long list of them doesn't need to be walked
+                if rest.len() == from.len() {
+                    break;
+                }
+                from = rest;
+            }
+            Some(from)
+        }
+        DynLayout::Primitive(encoding) => {
+            if from.len() < encoding.size {
+                return None;
+            }
+            let (bytes, from) = from.split_at(encoding.size);
+            if !encoding.is_valid(read_unsigned(bytes)) {
+                return None;
+            }
+            if let Some(out) = out {
+                out[..encoding.size].copy_from_slice(bytes);
+                // If the bytes are reversed, walk backwards from the end of the number when filling in bytes
+                if cfg!(any(target_endian = "big", feature = "test-big-endian")) {
+                    out[..encoding.size].reverse();
+                }
+            }
+            Some(from)
+        }
+    }
+}
+
+/// Deserialize a struct into the memory image passed in, or only check it. Returns the rest of the buffer after the struct.
+fn deserialize_dyn_struct<'a>(
+    mut from: &'a [u8],
+    encoding: &DynStructEncoding,
+    mut out: Option<&mut [u8]>,
+) -> Option<&'a [u8]> {
+    for field in &encoding.data {
+        let data = out.as_deref_mut().map(|out| &mut out[field.offset..]);
+        from = deserialize_dyn(from, &field.encoding, data)?;
+    }
+    Some(from)
+}
+
+/// Read a serialized enum discriminant. Discriminants are always little endian. A discriminant that doesn't fit
+/// in a u64 is read as `u64::MAX`, which doesn't match any variant.
+pub(crate) fn read_tag(bytes: &[u8]) -> u64 {
+    let (low, high) = bytes.split_at(bytes.len().min(8));
+    if high.iter().any(|byte| *byte != 0) {
+        return u64::MAX;
+    }
+    low.iter()
+        .enumerate()
+        .fold(0, |tag, (i, byte)| tag | (*byte as u64) << (i * 8))
+}
+
+/// Read a serialized primitive as an unsigned number. Primitives are little endian unless the test-big-endian feature reverses them on a little endian target.
+pub(crate) fn read_unsigned(bytes: &[u8]) -> u64 {
+    let read = |number: u64, byte: &u8| (number << 8) | *byte as u64;
+    if cfg!(all(target_endian = "little", feature = "test-big-endian")) {
+        bytes.iter().fold(0, read)
+    } else {
+        bytes.iter().rev().fold(0, read)
+    }
+}
+
+/// Write a serialized enum discriminant. Discriminants are always little endian.
+pub(crate) fn write_tag(to: &mut Vec<u8>, tag: u32, size: usize) {
+    to.extend((0..size).map(|i| tag.checked_shr(i as u32 * 8).unwrap_or(0) as u8));
+}
+
+/// Write an unsigned number as a serialized primitive with the given size
+pub(crate) fn write_unsigned(to: &mut Vec<u8>, number: u64, size: usize) {
+    let start = to.len();
+    to.extend((0..size).map(|i| number.checked_shr(i as u32 * 8).unwrap_or(0) as u8));
+    if cfg!(all(target_endian = "little", feature = "test-big-endian")) {
+        to[start..].reverse();
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+
+    /// A layout that hasn't been validated yet. It has the same representation as [`DynLayout`].
+    #[derive(serde::Deserialize)]
+    pub(super) enum LayoutSchema {
+        Enum(DynEnumEncoding),
+        Struct(DynStructEncoding),
+        List(DynListEncoding),
+        Primitive(PrimitiveEncoding),
+    }
+
+    impl TryFrom<LayoutSchema> for DynLayout {
+        type Error = LayoutError;
+
+        fn try_from(schema: LayoutSchema) -> Result<Self, LayoutError> {
+            let layout = match schema {
+                LayoutSchema::Enum(encoding) => DynLayout::Enum(encoding),
+                LayoutSchema::Struct(encoding) => DynLayout::Struct(encoding),
+                LayoutSchema::List(encoding) => DynLayout::List(encoding),
+                LayoutSchema::Primitive(encoding) => DynLayout::Primitive(encoding),
+            };
+            layout.validate()?;
+            Ok(layout)
+        }
+    }
+
+    /// The schema for an enum. The offset of the variants is computed from the variants when the schema is loaded.
+    #[derive(serde::Serialize, serde::Deserialize)]
+    pub(super) struct EnumSchema {
+        size: usize,
+        discriminant: PrimitiveEncoding,
+        variants: Vec<DynEnumVariant>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+    }
+
+    impl From<EnumSchema> for DynEnumEncoding {
+        fn from(schema: EnumSchema) -> Self {
+            let encoding = DynEnumEncoding::new(schema.size, schema.discriminant, schema.variants);
+            match schema.name {
+                Some(name) => encoding.with_name(name),
+                None => encoding,
+            }
+        }
+    }
+
+    impl From<DynEnumEncoding> for EnumSchema {
+        fn from(encoding: DynEnumEncoding) -> Self {
+            Self {
+                size: encoding.size,
+                discriminant: encoding.discriminant,
+                variants: encoding.variants,
+                name: encoding.name,
+            }
+        }
+    }
+}
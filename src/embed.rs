@@ -0,0 +1,170 @@
+use crate::const_str::push_number;
+use crate::{
+    serialize_const, serialize_layout, ConstStr, ConstWriteBuffer, Layout, SerializeConst,
+};
+
+/// The magic bytes at the start of every embedded record
+pub const RECORD_MAGIC: [u8; 4] = *b"CSER";
+
+/// The magic bytes at the start of a record that carries its own schema
+pub const DESCRIBED_RECORD_MAGIC: [u8; 4] = *b"CSED";
+
+/// The magic bytes at the start of an entry in a schema table
+pub const SCHEMA_RECORD_MAGIC: [u8; 4] = *b"CSLY";
+
+/// The size of the header before the data of an embedded record: the magic bytes, the fingerprint of
+/// the layout as a little endian u64, and the length of the data as a little endian u32
+pub const RECORD_HEADER_SIZE: usize = 16;
+
+/// Serialize a value into a record that can be found in a binary: a header with [`RECORD_MAGIC`], the
+/// [`Layout::fingerprint`] of the type and the length of the data, followed by the serialized data
+#[must_use = "The record is serialized into the returned buffer"]
+pub const fn serialize_record<T: SerializeConst>(data: &T) -> ConstWriteBuffer {
+    let data = serialize_const(data, ConstWriteBuffer::new());
+    record(RECORD_MAGIC, &T::MEMORY_LAYOUT, data.as_ref())
+}
+
+/// Serialize a value into a record that carries the schema of its type, so it can be decoded without
+/// knowing the type. The header starts with [`DESCRIBED_RECORD_MAGIC`]. The data is the length of the
+/// schema as a little endian u32, the schema written by [`serialize_layout`], and then the serialized value.
+///
+/// The whole record must fit in [`ConstWriteBuffer::CAPACITY`] bytes, otherwise this panics.
+#[must_use = "The record is serialized into the returned buffer"]
+pub const fn serialize_described_record<T: SerializeConst>(data: &T) -> ConstWriteBuffer {
+    let schema = serialize_layout(&T::MEMORY_LAYOUT, ConstWriteBuffer::new());
+    let schema = schema.as_ref();
+    let value = serialize_const(data, ConstWriteBuffer::new());
+    let value = value.as_ref();
+    let len = RECORD_HEADER_SIZE + 4 + schema.len() + value.len();
+    if len > ConstWriteBuffer::CAPACITY {
+        let mut message = ConstStr::new().push_str("the described record is ");
+        message = push_number(message, len);
+        message = message.push_str(" bytes with its schema, but records can be at most ");
+        message = push_number(message, ConstWriteBuffer::CAPACITY);
+        message = message.push_str(" bytes. Embed the schema once with embed_schema! instead");
+        panic!("{}", message.as_str());
+    }
+    let mut body = push_bytes(
+        ConstWriteBuffer::new(),
+        &(schema.len() as u32).to_le_bytes(),
+    );
+    body = push_bytes(body, schema);
+    body = push_bytes(body, value);
+    record(DESCRIBED_RECORD_MAGIC, &T::MEMORY_LAYOUT, body.as_ref())
+}
+
+/// Serialize the schema of a type into an entry of a schema table. Plain records with the same
+/// fingerprint in the same file can be decoded with the schema. The header starts with
+/// [`SCHEMA_RECORD_MAGIC`] and the data is the schema written by [`serialize_layout`].
+#[must_use = "The record is serialized into the returned buffer"]
+pub const fn serialize_schema_record<T: SerializeConst>() -> ConstWriteBuffer {
+    let schema = serialize_layout(&T::MEMORY_LAYOUT, ConstWriteBuffer::new());
+    record(SCHEMA_RECORD_MAGIC, &T::MEMORY_LAYOUT, schema.as_ref())
+}
+
+const fn record(magic: [u8; 4], layout: &Layout, data: &[u8]) -> ConstWriteBuffer {
+    let mut record = push_bytes(ConstWriteBuffer::new(), &magic);
+    record = push_bytes(record, &layout.fingerprint().to_le_bytes());
+    record = push_bytes(record, &(data.len() as u32).to_le_bytes());
+    push_bytes(record, data)
+}
+
+const fn push_bytes(mut to: ConstWriteBuffer, bytes: &[u8]) -> ConstWriteBuffer {
+    let mut i = 0;
+    while i < bytes.len() {
+        to = to.push(bytes[i]);
+        i += 1;
+    }
+    to
+}
+
+/// Copy a serialized record into an array so it can be stored in a static. N must be the length of the record.
+pub const fn record_array<const N: usize>(record: &ConstWriteBuffer) -> [u8; N] {
+    let bytes = record.as_ref();
+    if bytes.len() != N {
+        panic!("the array length must match the length of the record");
+    }
+    let mut array = [0; N];
+    let mut i = 0;
+    while i < N {
+        array[i] = bytes[i];
+        i += 1;
+    }
+    array
+}
+
+/// Embed a serialized value in a linker section of the binary. The record can be read back from the
+/// compiled file with `read_records` (behind the `object` feature).
+///
+/// Add `self_describing` after the section to store the schema of the type in the record, so tools
+/// can decode it without knowing the type. See [`serialize_described_record`].
+///
+/// Records are built in a [`ConstWriteBuffer`], so a record, including its header and schema, can be at most
+/// [`ConstWriteBuffer::CAPACITY`] (512) bytes. Larger records are a compile time error.
+///
+/// On Mach-O targets the section is placed in the `__DATA` segment. Section names are limited to
+/// 16 bytes on Mach-O and should be at most 8 bytes for PE images.
+///
+/// ```rust
+/// use const_serialize::{embed_const, SerializeConst};
+///
+/// #[derive(Clone, Copy, SerializeConst)]
+/// struct Version {
+///     major: u16,
+///     minor: u16,
+/// }
+///
+/// embed_const!(static VERSION: Version = Version { major: 1, minor: 2 }, section = "cser");
+/// embed_const!(static DESCRIBED: Version = Version { major: 1, minor: 2 }, section = "cser", self_describing);
+/// ```
+#[macro_export]
+macro_rules! embed_const {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $type:ty = $value:expr, section = $section:literal $(,)?) => {
+        $crate::embed_const!(@static [$(#[$attr])*] $vis $name, $section, $crate::serialize_record::<$type>(&$value));
+    };
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $type:ty = $value:expr, section = $section:literal, self_describing $(,)?) => {
+        $crate::embed_const!(@static [$(#[$attr])*] $vis $name, $section, $crate::serialize_described_record::<$type>(&$value));
+    };
+    (@static [$(#[$attr:meta])*] $vis:vis $name:ident, $section:literal, $record:expr) => {
+        $(#[$attr])*
+        #[used]
+        #[cfg_attr(
+            any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos"),
+            link_section = concat!("__DATA,", $section)
+        )]
+        #[cfg_attr(
+            not(any(target_os = "macos", target_os = "ios", target_os = "tvos", target_os = "watchos")),
+            link_section = $section
+        )]
+        $vis static $name: [u8; {
+            const __LEN: usize = $record.as_ref().len();
+            __LEN
+        }] = {
+            const __RECORD: $crate::ConstWriteBuffer = $record;
+            $crate::record_array(&__RECORD)
+        };
+    };
+}
+
+/// Embed the schema of a type in a schema table in a linker section. Plain records embedded with
+/// [`embed_const!`] in the same section can then be decoded without knowing their type, while the
+/// schema is only stored once.
+///
+/// ```rust
+/// use const_serialize::{embed_const, embed_schema, SerializeConst};
+///
+/// #[derive(Clone, Copy, SerializeConst)]
+/// struct Version {
+///     major: u16,
+///     minor: u16,
+/// }
+///
+/// embed_schema!(static VERSION_SCHEMA: Version, section = "cser");
+/// embed_const!(static VERSION: Version = Version { major: 1, minor: 2 }, section = "cser");
+/// ```
+#[macro_export]
+macro_rules! embed_schema {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $type:ty, section = $section:literal $(,)?) => {
+        $crate::embed_const!(@static [$(#[$attr])*] $vis $name, $section, $crate::serialize_schema_record::<$type>());
+    };
+}
@@ -0,0 +1,153 @@
+use std::mem::MaybeUninit;
+
+use crate::format::{read_value, NativeFormat};
+use crate::{crc32, serialize_const, ConstReadBuffer, ConstWriteBuffer, SerializeConst};
+
+/// The magic bytes at the start of every envelope
+pub const ENVELOPE_MAGIC: [u8; 4] = *b"CSEV";
+
+/// The version of the envelope format written by [`serialize_enveloped`]
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// The size of the envelope header before the payload:
+/// - the magic bytes ([`ENVELOPE_MAGIC`])
+/// - the format version ([`ENVELOPE_VERSION`])
+/// - the byte order of the primitives in the payload, 0 for little endian and 1 for big endian
+/// - the pointer width of the target in bytes
+/// - a reserved byte that is always 0
+/// - the [`Layout::fingerprint`](crate::Layout::fingerprint) of the type as a little endian u64
+/// - the length of the payload as a little endian u32
+/// - the [`crc32`] of the payload as a little endian u32
+pub const ENVELOPE_HEADER_SIZE: usize = 24;
+
+/// Whether [`serialize_const`] writes primitives big endian. It writes little endian on every target unless the
+/// test-big-endian feature reverses the bytes on a little endian target.
+const WIRE_BIG_ENDIAN: bool = cfg!(all(target_endian = "little", feature = "test-big-endian"));
+
+const TARGET_POINTER_WIDTH: u8 = std::mem::size_of::<usize>() as u8;
+
+/// An error from [`deserialize_enveloped!`](crate::deserialize_enveloped). The header is checked before the payload is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnvelopeError {
+    /// The buffer ends before the header or the payload
+    UnexpectedEnd,
+    /// The buffer doesn't start with [`ENVELOPE_MAGIC`]
+    BadMagic,
+    /// The envelope was written with an unsupported format version
+    UnsupportedVersion { found: u8 },
+    /// The payload was written with a different byte order or on a target with a different pointer width
+    TargetMismatch { big_endian: bool, pointer_width: u8 },
+    /// The envelope holds a different type
+    LayoutMismatch { expected: u64, found: u64 },
+    /// The checksum of the payload doesn't match the header
+    ChecksumMismatch { expected: u32, found: u32 },
+    /// The payload passed the checksum, but it isn't a valid value of the type or has bytes left over
+    InvalidPayload,
+}
+
+/// Serialize a type into a buffer, wrapped in an envelope that identifies the format, the target and
+/// the type, and checksums the payload. See [`ENVELOPE_HEADER_SIZE`] for the layout of the header.
+#[must_use = "The data is serialized into the returned buffer"]
+pub const fn serialize_enveloped<T: SerializeConst>(
+    data: &T,
+    mut to: ConstWriteBuffer,
+) -> ConstWriteBuffer {
+    let payload = serialize_const(data, ConstWriteBuffer::new());
+    let payload = payload.as_ref();
+
+    to = push_bytes(to, &ENVELOPE_MAGIC);
+    to = to.push(ENVELOPE_VERSION);
+    to = to.push(WIRE_BIG_ENDIAN as u8);
+    to = to.push(TARGET_POINTER_WIDTH);
+    to = to.push(0);
+    to = push_bytes(to, &T::MEMORY_LAYOUT.fingerprint().to_le_bytes());
+    to = push_bytes(to, &(payload.len() as u32).to_le_bytes());
+    to = push_bytes(to, &crc32(payload).to_le_bytes());
+    push_bytes(to, payload)
+}
+
+const fn push_bytes(mut to: ConstWriteBuffer, bytes: &[u8]) -> ConstWriteBuffer {
+    let mut i = 0;
+    while i < bytes.len() {
+        to = to.push(bytes[i]);
+        i += 1;
+    }
+    to
+}
+
+/// Deserialize a type from an envelope written by [`serialize_enveloped`]. Bytes after the payload are ignored.
+#[macro_export]
+macro_rules! deserialize_enveloped {
+    ($type:ty, $buffer:expr) => {
+        $crate::deserialize_enveloped_raw::<$type>($buffer)
+    };
+}
+
+/// Deserialize a type from an envelope written by [`serialize_enveloped`].
+#[must_use = "The data is deserialized from the input buffer"]
+pub const fn deserialize_enveloped_raw<T: SerializeConst>(
+    from: ConstReadBuffer,
+) -> Result<T, EnvelopeError> {
+    let bytes = from.remaining();
+    if bytes.len() < ENVELOPE_HEADER_SIZE {
+        return Err(EnvelopeError::UnexpectedEnd);
+    }
+    if !starts_with(bytes, &ENVELOPE_MAGIC) {
+        return Err(EnvelopeError::BadMagic);
+    }
+    if bytes[4] != ENVELOPE_VERSION {
+        return Err(EnvelopeError::UnsupportedVersion { found: bytes[4] });
+    }
+    if bytes[5] != WIRE_BIG_ENDIAN as u8 || bytes[6] != TARGET_POINTER_WIDTH {
+        return Err(EnvelopeError::TargetMismatch {
+            big_endian: bytes[5] != 0,
+            pointer_width: bytes[6],
+        });
+    }
+    let found = u64::from_le_bytes(read_array(bytes, 8));
+    let expected = T::MEMORY_LAYOUT.fingerprint();
+    if found != expected {
+        return Err(EnvelopeError::LayoutMismatch { expected, found });
+    }
+    let len = u32::from_le_bytes(read_array(bytes, 16)) as usize;
+    let (_, rest) = bytes.split_at(ENVELOPE_HEADER_SIZE);
+    if rest.len() < len {
+        return Err(EnvelopeError::UnexpectedEnd);
+    }
+    let (payload, _) = rest.split_at(len);
+    let expected = u32::from_le_bytes(read_array(bytes, 20));
+    let found = crc32(payload);
+    if found != expected {
+        return Err(EnvelopeError::ChecksumMismatch { expected, found });
+    }
+
+    let mut out = MaybeUninit::<T>::uninit();
+    let ptr = out.as_mut_ptr() as *mut u8;
+    match read_value::<NativeFormat>(payload, 0, &T::MEMORY_LAYOUT, ptr) {
+        Ok(end) if end == payload.len() => {}
+        _ => return Err(EnvelopeError::InvalidPayload),
+    }
+    // SAFETY: every byte of the layout of T was filled in from valid data
+    Ok(unsafe { out.assume_init() })
+}
+
+const fn starts_with(bytes: &[u8], prefix: &[u8]) -> bool {
+    let mut i = 0;
+    while i < prefix.len() {
+        if bytes[i] != prefix[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn read_array<const N: usize>(bytes: &[u8], start: usize) -> [u8; N] {
+    let mut array = [0; N];
+    let mut i = 0;
+    while i < N {
+        array[i] = bytes[start + i];
+        i += 1;
+    }
+    array
+}
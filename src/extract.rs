@@ -0,0 +1,340 @@
+use std::fmt::Display;
+use std::mem::MaybeUninit;
+
+use crate::embed::{DESCRIBED_RECORD_MAGIC, RECORD_HEADER_SIZE, RECORD_MAGIC, SCHEMA_RECORD_MAGIC};
+use crate::format::read_value;
+use crate::{DecodeError, DynLayout, NativeFormat, SerializeConst, Value};
+
+/// A record serialized with [`serialize_record`](crate::serialize_record) or
+/// [`serialize_described_record`](crate::serialize_described_record) that was found in a compiled file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedRecord<'a> {
+    /// The name of the section the record was found in
+    pub section: String,
+    /// The address of the start of the record header
+    pub address: u64,
+    /// The fingerprint of the layout the data was serialized with
+    pub fingerprint: u64,
+    /// The serialized value
+    pub data: &'a [u8],
+    /// The schema of the value, if the record carries its own schema or the file has a schema table entry with the same fingerprint
+    pub schema: Option<DynLayout>,
+}
+
+/// An error reading the records from a compiled file
+#[derive(Debug)]
+pub enum ExtractError {
+    /// The file couldn't be parsed as an object file
+    #[cfg(feature = "object")]
+    Object(object::Error),
+    /// The file couldn't be parsed as a WebAssembly module
+    #[cfg(feature = "wasm")]
+    Wasm(wasmparser::BinaryReaderError),
+    /// A record header was found, but the data it describes runs past the end of the section
+    Truncated { section: String, address: u64 },
+    /// The schema in a record is invalid or doesn't match the fingerprint of the record
+    InvalidSchema { section: String, address: u64 },
+}
+
+impl Display for ExtractError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(feature = "object")]
+            ExtractError::Object(error) => write!(f, "failed to parse the file: {error}"),
+            #[cfg(feature = "wasm")]
+            ExtractError::Wasm(error) => write!(f, "failed to parse the module: {error}"),
+            ExtractError::Truncated { section, address } => {
+                write!(f, "truncated record at {address:#x} in section {section}")
+            }
+            ExtractError::InvalidSchema { section, address } => {
+                write!(f, "invalid schema at {address:#x} in section {section}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExtractError {}
+
+#[cfg(feature = "object")]
+impl From<object::Error> for ExtractError {
+    fn from(error: object::Error) -> Self {
+        ExtractError::Object(error)
+    }
+}
+
+#[cfg(feature = "wasm")]
+impl From<wasmparser::BinaryReaderError> for ExtractError {
+    fn from(error: wasmparser::BinaryReaderError) -> Self {
+        ExtractError::Wasm(error)
+    }
+}
+
+/// An error decoding an embedded record
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecordError {
+    /// The record was serialized with a different layout
+    FingerprintMismatch { expected: u64, found: u64 },
+    /// The data doesn't match the layout
+    Decode(DecodeError),
+    /// The layout doesn't use all of the data in the record
+    TrailingBytes { len: usize },
+    /// The record doesn't carry a schema and there is no schema table entry for it
+    MissingSchema,
+}
+
+impl Display for RecordError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordError::FingerprintMismatch { expected, found } => write!(
+                f,
+                "the record has layout fingerprint {found:016x}, expected {expected:016x}"
+            ),
+            RecordError::Decode(error) => error.fmt(f),
+            RecordError::TrailingBytes { len } => {
+                write!(f, "{len} bytes are left after the value in the record")
+            }
+            RecordError::MissingSchema => write!(f, "no schema was found for the record"),
+        }
+    }
+}
+
+impl std::error::Error for RecordError {}
+
+/// Find every record in the sections with the given name in an ELF, Mach-O or PE file. Bytes between records that
+/// don't start with a record magic, like padding added by the linker, are skipped.
+///
+/// Schema table entries are not returned as records. Their schemas are attached to the records with the same fingerprint.
+#[cfg(feature = "object")]
+pub fn read_records<'a>(
+    file: &'a [u8],
+    section: &str,
+) -> Result<Vec<EmbeddedRecord<'a>>, ExtractError> {
+    use object::{Object, ObjectSection};
+
+    let file = object::File::parse(file)?;
+    let mut scanner = Scanner::default();
+    for found in file.sections() {
+        if found.name()? != section {
+            continue;
+        }
+        scanner.scan(section, found.address(), found.data()?, true)?;
+    }
+    Ok(scanner.finish())
+}
+
+/// Find every record in a WebAssembly module, both in custom sections with the given name and in data segments.
+///
+/// Records in custom sections are named after the section and their address is the offset in the section.
+/// Records in data segments are named `data[i]` after the index of the segment and their address is the
+/// address in linear memory, or the offset in the segment for passive segments.
+///
+/// Data segments contain all of the static data of the module, so a header that doesn't fit in its segment and
+/// records in data segments with an invalid schema are skipped instead of failing the whole module. Use
+/// [`read_wasm_records_lossy`] to find out which records were skipped.
+#[cfg(feature = "wasm")]
+pub fn read_wasm_records<'a>(
+    module: &'a [u8],
+    section: &str,
+) -> Result<Vec<EmbeddedRecord<'a>>, ExtractError> {
+    read_wasm_records_lossy(module, section).map(|(records, _)| records)
+}
+
+/// Like [`read_wasm_records`], but also returns an [`ExtractError::InvalidSchema`] for each record in a data
+/// segment that was skipped because its schema is invalid
+#[cfg(feature = "wasm")]
+pub fn read_wasm_records_lossy<'a>(
+    module: &'a [u8],
+    section: &str,
+) -> Result<(Vec<EmbeddedRecord<'a>>, Vec<ExtractError>), ExtractError> {
+    use wasmparser::{DataKind, Operator, Parser, Payload};
+
+    let mut scanner = Scanner::default();
+    for payload in Parser::new(0).parse_all(module) {
+        match payload? {
+            Payload::CustomSection(reader) if reader.name() == section => {
+                scanner.scan(section, 0, reader.data(), true)?;
+            }
+            Payload::DataSection(reader) => {
+                for (index, data) in reader.into_iter().enumerate() {
+                    let data = data?;
+                    let address = match data.kind {
+                        DataKind::Active { offset_expr, .. } => {
+                            match offset_expr.get_operators_reader().read()? {
+                                Operator::I32Const { value } => value as u32 as u64,
+                                Operator::I64Const { value } => value as u64,
+                                // The address is only known when the module is instantiated
+                                _ => 0,
+                            }
+                        }
+                        DataKind::Passive => 0,
+                    };
+                    let name = format!("data[{index}]");
+                    scanner.scan(&name, address, data.data, false)?;
+                }
+            }
+            _ => {}
+        }
+    }
+    let skipped = std::mem::take(&mut scanner.skipped);
+    Ok((scanner.finish(), skipped))
+}
+
+/// Collects the records and schema table entries from the sections of a file
+#[derive(Default)]
+struct Scanner<'a> {
+    records: Vec<EmbeddedRecord<'a>>,
+    schemas: Vec<(u64, DynLayout)>,
+    /// The records with an invalid schema that were skipped outside of strict mode
+    skipped: Vec<ExtractError>,
+}
+
+impl<'a> Scanner<'a> {
+    /// Find the records in the data of a section that starts at the address. In strict mode, a truncated record
+    /// or an invalid schema is an error. Otherwise truncated headers are skipped and records with an invalid schema
+    /// are skipped and added to [`Scanner::skipped`].
+    fn scan(
+        &mut self,
+        section: &str,
+        address: u64,
+        data: &'a [u8],
+        strict: bool,
+    ) -> Result<(), ExtractError> {
+        let mut offset = 0;
+        while offset + RECORD_HEADER_SIZE <= data.len() {
+            let magic = &data[offset..offset + RECORD_MAGIC.len()];
+            if magic != RECORD_MAGIC
+                && magic != DESCRIBED_RECORD_MAGIC
+                && magic != SCHEMA_RECORD_MAGIC
+            {
+                offset += 1;
+                continue;
+            }
+            let header = &data[offset..offset + RECORD_HEADER_SIZE];
+            let fingerprint = u64::from_le_bytes(header[4..12].try_into().unwrap());
+            let len = u32::from_le_bytes(header[12..16].try_into().unwrap()) as usize;
+            let start = offset + RECORD_HEADER_SIZE;
+            let record_address = address.wrapping_add(offset as u64);
+            let Some(body) = start.checked_add(len).and_then(|end| data.get(start..end)) else {
+                if strict {
+                    return Err(ExtractError::Truncated {
+                        section: section.to_string(),
+                        address: record_address,
+                    });
+                }
+                offset += 1;
+                continue;
+            };
+            let invalid_schema = || ExtractError::InvalidSchema {
+                section: section.to_string(),
+                address: record_address,
+            };
+            let read_schema = |schema: &[u8]| {
+                DynLayout::from_schema(schema).filter(|layout| layout.fingerprint() == fingerprint)
+            };
+
+            if magic == SCHEMA_RECORD_MAGIC {
+                match read_schema(body) {
+                    Some(schema) => self.schemas.push((fingerprint, schema)),
+                    None if strict => return Err(invalid_schema()),
+                    None => self.skipped.push(invalid_schema()),
+                }
+            } else if magic == DESCRIBED_RECORD_MAGIC {
+                let described = split_schema(body)
+                    .and_then(|(schema, data)| Some((read_schema(schema)?, data)));
+                match described {
+                    Some((schema, data)) => self.records.push(EmbeddedRecord {
+                        section: section.to_string(),
+                        address: record_address,
+                        fingerprint,
+                        data,
+                        schema: Some(schema),
+                    }),
+                    None if strict => return Err(invalid_schema()),
+                    None => self.skipped.push(invalid_schema()),
+                }
+            } else {
+                self.records.push(EmbeddedRecord {
+                    section: section.to_string(),
+                    address: record_address,
+                    fingerprint,
+                    data: body,
+                    schema: None,
+                });
+            }
+            offset = start + len;
+        }
+        Ok(())
+    }
+
+    /// Attach the schemas from the schema table to the records without a schema
+    fn finish(mut self) -> Vec<EmbeddedRecord<'a>> {
+        for record in &mut self.records {
+            if record.schema.is_none() {
+                record.schema = self
+                    .schemas
+                    .iter()
+                    .find(|(fingerprint, _)| *fingerprint == record.fingerprint)
+                    .map(|(_, schema)| schema.clone());
+            }
+        }
+        self.records
+    }
+}
+
+/// Split the body of a self-describing record into the schema and the serialized value
+fn split_schema(body: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (len, rest) = body.split_first_chunk::<4>()?;
+    let len = u32::from_le_bytes(*len) as usize;
+    (len <= rest.len()).then(|| rest.split_at(len))
+}
+
+impl EmbeddedRecord<'_> {
+    /// Decode the record with its schema, without knowing the type it was serialized from
+    pub fn value(&self) -> Result<Value, RecordError> {
+        let schema = self.schema.as_ref().ok_or(RecordError::MissingSchema)?;
+        self.decode(schema)
+    }
+
+    /// Decode the record with the layout it was serialized with
+    pub fn decode(&self, layout: &DynLayout) -> Result<Value, RecordError> {
+        self.check_fingerprint(layout)?;
+        let mut decoder = crate::value::Decoder::new(self.data, None);
+        let value = decoder.decode(layout).map_err(RecordError::Decode)?;
+        match self.data.len() - decoder.offset {
+            0 => Ok(value),
+            len => Err(RecordError::TrailingBytes { len }),
+        }
+    }
+
+    /// Deserialize the record into the type it was serialized from
+    pub fn deserialize<T: SerializeConst>(&self) -> Result<T, RecordError> {
+        let expected = T::MEMORY_LAYOUT.fingerprint();
+        if self.fingerprint != expected {
+            return Err(RecordError::FingerprintMismatch {
+                expected,
+                found: self.fingerprint,
+            });
+        }
+        let mut out = MaybeUninit::<T>::uninit();
+        let ptr = out.as_mut_ptr() as *mut u8;
+        match read_value::<NativeFormat>(self.data, 0, &T::MEMORY_LAYOUT, ptr) {
+            // SAFETY: every byte of the layout of T was filled in from valid data
+            Ok(len) if len == self.data.len() => Ok(unsafe { out.assume_init() }),
+            Ok(len) => Err(RecordError::TrailingBytes {
+                len: self.data.len() - len,
+            }),
+            Err(error) => Err(RecordError::Decode(error.into())),
+        }
+    }
+
+    fn check_fingerprint(&self, layout: &DynLayout) -> Result<(), RecordError> {
+        let expected = layout.fingerprint();
+        if self.fingerprint != expected {
+            return Err(RecordError::FingerprintMismatch {
+                expected,
+                found: self.fingerprint,
+            });
+        }
+        Ok(())
+    }
+}
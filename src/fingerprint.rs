@@ -0,0 +1,182 @@
+use crate::{EnumEncoding, Layout, PrimitiveEncoding, PrimitiveKind, StructEncoding};
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// A 64 bit FNV-1a hasher that can be used in const
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ConstHasher {
+    hash: u64,
+}
+
+impl ConstHasher {
+    pub(crate) const fn new() -> Self {
+        Self {
+            hash: FNV_OFFSET_BASIS,
+        }
+    }
+
+    pub(crate) const fn write_u8(mut self, byte: u8) -> Self {
+        self.hash ^= byte as u64;
+        self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        self
+    }
+
+    pub(crate) const fn write_bytes(mut self, bytes: &[u8]) -> Self {
+        let mut i = 0;
+        while i < bytes.len() {
+            self = self.write_u8(bytes[i]);
+            i += 1;
+        }
+        self
+    }
+
+    pub(crate) const fn write_u64(self, number: u64) -> Self {
+        self.write_bytes(&number.to_le_bytes())
+    }
+
+    const fn write_name(self, name: Option<&str>) -> Self {
+        match name {
+            Some(name) => self
+                .write_u8(1)
+                .write_u64(name.len() as u64)
+                .write_bytes(name.as_bytes()),
+            None => self.write_u8(0),
+        }
+    }
+
+    /// Write the id of a field and whether it has a default. Nothing is written for fields without either, so
+    /// the fingerprints of layouts that don't use them stay the same. The markers can't be confused with the
+    /// start of a layout.
+    const fn write_field_options(self, id: Option<u32>, has_default: bool) -> Self {
+        let hasher = match id {
+            Some(id) => self.write_u8(b'I').write_u64(id as u64),
+            None => self,
+        };
+        if has_default {
+            hasher.write_u8(b'D')
+        } else {
+            hasher
+        }
+    }
+
+    pub(crate) const fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+impl Layout {
+    /// A fingerprint of the layout. Layouts with the same fingerprint serialize the same data to the same bytes.
+    ///
+    /// The fingerprint covers everything that changes the serialized data or what it means: the order
+    /// of fields, primitive sizes and kinds, enum discriminants and tags, list lengths, the names of
+    /// types, fields and variants, field ids and which fields have defaults. The memory offsets and sizes
+    /// of types and the values of defaults are not included.
+    pub const fn fingerprint(&self) -> u64 {
+        hash_layout(ConstHasher::new(), self).finish()
+    }
+}
+
+const fn hash_layout(hasher: ConstHasher, layout: &Layout) -> ConstHasher {
+    match layout {
+        Layout::Enum(encoding) => hash_enum(hasher, encoding),
+        Layout::Struct(encoding) => hash_struct(hasher.write_u8(b'S'), encoding),
+        Layout::List(encoding) => {
+            let hasher = hasher.write_u8(b'L').write_u64(encoding.len as u64);
+            hash_layout(hasher, encoding.item_encoding)
+        }
+        Layout::Primitive(encoding) => hash_primitive(hasher, encoding),
+    }
+}
+
+const fn hash_struct(hasher: ConstHasher, encoding: &StructEncoding) -> ConstHasher {
+    let mut hasher = hasher
+        .write_name(encoding.name)
+        .write_u64(encoding.data.len() as u64);
+    let mut i = 0;
+    while i < encoding.data.len() {
+        let field = &encoding.data[i];
+        hasher = hasher
+            .write_name(field.name)
+            .write_field_options(field.id, field.default.is_some());
+        hasher = hash_layout(hasher, &field.encoding);
+        i += 1;
+    }
+    hasher
+}
+
+const fn hash_enum(hasher: ConstHasher, encoding: &EnumEncoding) -> ConstHasher {
+    let mut hasher = hash_primitive(
+        hasher.write_u8(b'E').write_name(encoding.name),
+        &encoding.discriminant,
+    )
+    .write_u64(encoding.variants.len() as u64);
+    let mut i = 0;
+    while i < encoding.variants.len() {
+        let variant = &encoding.variants[i];
+        hasher = hash_struct(hasher.write_u64(variant.tag as u64), &variant.data);
+        i += 1;
+    }
+    hasher
+}
+
+const fn hash_primitive(hasher: ConstHasher, encoding: &PrimitiveEncoding) -> ConstHasher {
+    let kind = match encoding.kind {
+        PrimitiveKind::Unsigned => 0,
+        PrimitiveKind::Signed => 1,
+        PrimitiveKind::Float => 2,
+        PrimitiveKind::Bool => 3,
+    };
+    hasher
+        .write_u8(b'P')
+        .write_u64(encoding.size as u64)
+        .write_u8(kind)
+}
+
+#[cfg(feature = "std")]
+mod dyn_fingerprint {
+    use super::*;
+    use crate::{DynEnumEncoding, DynLayout, DynStructEncoding};
+
+    impl DynLayout {
+        /// A fingerprint of the layout. This is the same as [`Layout::fingerprint`] for the layout this was converted from.
+        pub fn fingerprint(&self) -> u64 {
+            hash_dyn_layout(ConstHasher::new(), self).finish()
+        }
+    }
+
+    fn hash_dyn_layout(hasher: ConstHasher, layout: &DynLayout) -> ConstHasher {
+        match layout {
+            DynLayout::Enum(encoding) => hash_dyn_enum(hasher, encoding),
+            DynLayout::Struct(encoding) => hash_dyn_struct(hasher.write_u8(b'S'), encoding),
+            DynLayout::List(encoding) => {
+                let hasher = hasher.write_u8(b'L').write_u64(encoding.len as u64);
+                hash_dyn_layout(hasher, &encoding.item_encoding)
+            }
+            DynLayout::Primitive(encoding) => hash_primitive(hasher, encoding),
+        }
+    }
+
+    fn hash_dyn_struct(hasher: ConstHasher, encoding: &DynStructEncoding) -> ConstHasher {
+        let hasher = hasher
+            .write_name(encoding.name.as_deref())
+            .write_u64(encoding.data.len() as u64);
+        encoding.data.iter().fold(hasher, |hasher, field| {
+            let hasher = hasher
+                .write_name(field.name.as_deref())
+                .write_field_options(field.id, field.default.is_some());
+            hash_dyn_layout(hasher, &field.encoding)
+        })
+    }
+
+    fn hash_dyn_enum(hasher: ConstHasher, encoding: &DynEnumEncoding) -> ConstHasher {
+        let hasher = hash_primitive(
+            hasher.write_u8(b'E').write_name(encoding.name.as_deref()),
+            &encoding.discriminant,
+        )
+        .write_u64(encoding.variants.len() as u64);
+        encoding.variants.iter().fold(hasher, |hasher, variant| {
+            hash_dyn_struct(hasher.write_u64(variant.tag as u64), &variant.data)
+        })
+    }
+}
@@ -0,0 +1,776 @@
+use std::mem::MaybeUninit;
+
+use crate::compare::{byte_le, ordered_byte, read_tag};
+use crate::{
+    ConstReadBuffer, ConstWriteBuffer, EnumEncoding, EnumVariant, FieldDefault, Layout,
+    PrimitiveEncoding, PrimitiveKind, SerializeConst, StructEncoding,
+};
+
+/// A wire format that decides how the parts of a value are written while the layout of the value is walked.
+/// Traits can't have const methods, so the format is chosen with associated consts instead and the walker in
+/// [`serialize_with`] and [`deserialize_with`] reads them. Paths, slices, views and `include_const!` read native
+/// data with the same walker. Decoding a `DynLayout` into a `Value` at runtime only supports the native format
+/// and walks the dynamic layout instead.
+///
+/// A format that writes numbers in network byte order and prefixes every array with a varint length:
+///
+/// ```rust
+/// use const_serialize::{
+///     serialize_with, ConstWriteBuffer, LengthFormat, PrimitiveFormat, SerializeConst, TagFormat,
+///     WireFormat,
+/// };
+///
+/// struct Network;
+///
+/// impl WireFormat for Network {
+///     const PRIMITIVES: PrimitiveFormat = PrimitiveFormat::BigEndian;
+///     const ENUM_TAGS: TagFormat = TagFormat::IndexU32;
+///     const LIST_LENGTHS: LengthFormat = LengthFormat::Varint;
+///     const STRUCT_LENGTHS: LengthFormat = LengthFormat::Omitted;
+/// }
+///
+/// const BUF: ConstWriteBuffer = serialize_with::<Network, _>(&[0x0102u16, 0x0304], ConstWriteBuffer::new());
+/// assert_eq!(BUF.as_ref(), [2, 1, 2, 3, 4]);
+/// ```
+pub trait WireFormat {
+    /// How numbers, bools and floats are written
+    const PRIMITIVES: PrimitiveFormat;
+    /// How the variant of an enum is written before the fields of the variant
+    const ENUM_TAGS: TagFormat;
+    /// How the length of an array is written before the items. The length must match when the array is read.
+    const LIST_LENGTHS: LengthFormat;
+    /// How the number of bytes in a struct or enum variant is written before the fields. When the struct is read,
+    /// any bytes after the known fields are skipped, so newer writers can append fields.
+    const STRUCT_LENGTHS: LengthFormat;
+    /// How the fields of a struct or enum variant are written
+    const STRUCT_FIELDS: FieldFormat = FieldFormat::Positional;
+}
+
+/// How a [`WireFormat`] writes primitives
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PrimitiveFormat {
+    /// Little-endian, or big-endian with the `test-big-endian` feature. This is the format of [`serialize_const`](crate::serialize_const).
+    Native,
+    /// Little-endian at the full size of the type
+    LittleEndian,
+    /// Big-endian at the full size of the type
+    BigEndian,
+    /// Big-endian with the sign bit of signed integers flipped and floats transformed to sort like
+    /// [`f64::total_cmp`], so the bytes compare like the values
+    Ordered,
+    /// Bytes as is and larger integers as LEB128 varints. Signed integers are zigzag encoded first.
+    Varint,
+}
+
+/// How a [`WireFormat`] writes the variant of an enum
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TagFormat {
+    /// The discriminant little-endian at the size of the discriminant
+    Discriminant,
+    /// The discriminant big-endian at the size of the discriminant
+    OrderedDiscriminant,
+    /// The position of the variant in the declaration as a little-endian `u32`, like serde
+    IndexU32,
+    /// The position of the variant in the declaration as a LEB128 varint
+    IndexVarint,
+}
+
+/// How a [`WireFormat`] writes a length
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LengthFormat {
+    /// The length is not written
+    Omitted,
+    /// A little-endian `u32`
+    U32,
+    /// A little-endian `u64`
+    U64,
+    /// A LEB128 varint
+    Varint,
+}
+
+/// How a [`WireFormat`] writes the fields of a struct
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FieldFormat {
+    /// Every field in the order it is declared
+    Positional,
+    /// The number of fields as a varint, then every field as a varint id, a varint length and the field. The id is
+    /// set with `#[const_serialize(id = N)]` and defaults to the position of the field. When the struct is read,
+    /// unknown ids are skipped and missing fields are filled in with the value from
+    /// `#[const_serialize(default = value)]`. A missing field without a default is an error.
+    Tagged,
+}
+
+/// The default format of [`serialize_const`](crate::serialize_const) and [`deserialize_const!`](crate::deserialize_const)
+pub struct NativeFormat;
+
+impl WireFormat for NativeFormat {
+    const PRIMITIVES: PrimitiveFormat = PrimitiveFormat::Native;
+    const ENUM_TAGS: TagFormat = TagFormat::Discriminant;
+    const LIST_LENGTHS: LengthFormat = LengthFormat::Omitted;
+    const STRUCT_LENGTHS: LengthFormat = LengthFormat::Omitted;
+}
+
+/// Serialize a type into a buffer with a wire format
+#[must_use = "The data is serialized into the returned buffer"]
+pub const fn serialize_with<F: WireFormat, T: SerializeConst>(
+    data: &T,
+    to: ConstWriteBuffer,
+) -> ConstWriteBuffer {
+    write_value::<F>(data as *const T as *const u8, to, &T::MEMORY_LAYOUT)
+}
+
+/// Deserialize a type written with a wire format. Returns the rest of the buffer and the value, or None if the
+/// buffer is too short or doesn't hold a valid value.
+#[must_use = "The data is deserialized from the input buffer"]
+pub const fn deserialize_with<F: WireFormat, T: SerializeConst>(
+    from: ConstReadBuffer,
+) -> Option<(ConstReadBuffer, T)> {
+    let mut out = MaybeUninit::<T>::uninit();
+    let len = match read_value::<F>(
+        from.remaining(),
+        0,
+        &T::MEMORY_LAYOUT,
+        out.as_mut_ptr() as *mut u8,
+    ) {
+        Ok(len) => len,
+        Err(_) => return None,
+    };
+    // SAFETY: every byte of the layout of T was filled in from valid data
+    Some((from.advance(len), unsafe { out.assume_init() }))
+}
+
+/// Serialize the value stored at the pointer
+pub(crate) const fn write_value<F: WireFormat>(
+    ptr: *const u8,
+    mut to: ConstWriteBuffer,
+    layout: &Layout,
+) -> ConstWriteBuffer {
+    match layout {
+        Layout::Enum(encoding) => {
+            let tag = read_tag(ptr, encoding);
+            let mut i = 0;
+            while i < encoding.variants.len() {
+                let variant = &encoding.variants[i];
+                if variant.tag as u64 == tag {
+                    to = write_tag::<F>(to, encoding, i);
+                    let data = unsafe { ptr.add(encoding.variants_offset) };
+                    return write_struct::<F>(data, to, &variant.data);
+                }
+                i += 1;
+            }
+            to
+        }
+        Layout::Struct(encoding) => write_struct::<F>(ptr, to, encoding),
+        Layout::List(encoding) => {
+            to = write_length(to, F::LIST_LENGTHS, encoding.len);
+            let item_size = encoding.item_encoding.size();
+            let mut i = 0;
+            while i < encoding.len {
+                let item = unsafe { ptr.add(i * item_size) };
+                to = write_value::<F>(item, to, encoding.item_encoding);
+                i += 1;
+            }
+            to
+        }
+        Layout::Primitive(encoding) => write_primitive::<F>(ptr, to, encoding),
+    }
+}
+
+const fn write_struct<F: WireFormat>(
+    ptr: *const u8,
+    mut to: ConstWriteBuffer,
+    encoding: &StructEncoding,
+) -> ConstWriteBuffer {
+    if let LengthFormat::Omitted = F::STRUCT_LENGTHS {
+        return write_fields::<F>(ptr, to, encoding);
+    }
+    // The length comes first, so write the fields into a separate buffer to measure them
+    let fields = write_fields::<F>(ptr, ConstWriteBuffer::new(), encoding);
+    let fields = fields.as_ref();
+    to = write_length(to, F::STRUCT_LENGTHS, fields.len());
+    push_bytes(to, fields)
+}
+
+const fn write_fields<F: WireFormat>(
+    ptr: *const u8,
+    mut to: ConstWriteBuffer,
+    encoding: &StructEncoding,
+) -> ConstWriteBuffer {
+    let tagged = matches!(F::STRUCT_FIELDS, FieldFormat::Tagged);
+    if tagged {
+        to = push_varint(to, encoding.data.len() as u64);
+    }
+    let mut i = 0;
+    while i < encoding.data.len() {
+        let field = &encoding.data[i];
+        let data = unsafe { ptr.add(field.offset) };
+        if tagged {
+            to = push_varint(to, field_id(encoding, i) as u64);
+            let payload = write_value::<F>(data, ConstWriteBuffer::new(), &field.encoding);
+            let payload = payload.as_ref();
+            to = push_varint(to, payload.len() as u64);
+            to = push_bytes(to, payload);
+        } else {
+            to = write_value::<F>(data, to, &field.encoding);
+        }
+        i += 1;
+    }
+    to
+}
+
+/// The id of a tagged field
+const fn field_id(encoding: &StructEncoding, index: usize) -> u32 {
+    match encoding.data[index].id {
+        Some(id) => id,
+        None => index as u32,
+    }
+}
+
+const fn write_tag<F: WireFormat>(
+    to: ConstWriteBuffer,
+    encoding: &EnumEncoding,
+    index: usize,
+) -> ConstWriteBuffer {
+    let tag = encoding.variants[index].tag as u64;
+    let size = encoding.discriminant.size;
+    match F::ENUM_TAGS {
+        TagFormat::Discriminant => push_le(to, size, tag),
+        TagFormat::OrderedDiscriminant => push_be(to, size, tag),
+        TagFormat::IndexU32 => push_le(to, 4, index as u64),
+        TagFormat::IndexVarint => push_varint(to, index as u64),
+    }
+}
+
+const fn write_length(to: ConstWriteBuffer, format: LengthFormat, len: usize) -> ConstWriteBuffer {
+    match format {
+        LengthFormat::Omitted => to,
+        LengthFormat::U32 => push_le(to, 4, len as u64),
+        LengthFormat::U64 => push_le(to, 8, len as u64),
+        LengthFormat::Varint => push_varint(to, len as u64),
+    }
+}
+
+const fn write_primitive<F: WireFormat>(
+    ptr: *const u8,
+    mut to: ConstWriteBuffer,
+    encoding: &PrimitiveEncoding,
+) -> ConstWriteBuffer {
+    let size = encoding.size;
+    let value = read_le(ptr, size);
+    match F::PRIMITIVES {
+        PrimitiveFormat::Native => {
+            if cfg!(all(feature = "test-big-endian", target_endian = "little")) {
+                push_be(to, size, value)
+            } else {
+                push_le(to, size, value)
+            }
+        }
+        PrimitiveFormat::LittleEndian => push_le(to, size, value),
+        PrimitiveFormat::BigEndian => push_be(to, size, value),
+        PrimitiveFormat::Ordered => {
+            let mut i = 0;
+            while i < size {
+                to = to.push(ordered_byte(ptr, encoding, i));
+                i += 1;
+            }
+            to
+        }
+        PrimitiveFormat::Varint => match encoding.kind {
+            PrimitiveKind::Float | PrimitiveKind::Bool => push_le(to, size, value),
+            _ if size == 1 => to.push(value as u8),
+            PrimitiveKind::Unsigned => push_varint(to, value),
+            PrimitiveKind::Signed => {
+                // Sign extend the value, then zigzag encode it
+                let shift = 64 - size as u32 * 8;
+                let signed = ((value << shift) as i64) >> shift;
+                push_varint(to, ((signed << 1) ^ (signed >> 63)) as u64)
+            }
+        },
+    }
+}
+
+/// Why serialized data couldn't be read, and the offset in the data where the problem was found
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ReadError {
+    pub(crate) offset: usize,
+    pub(crate) kind: ReadErrorKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReadErrorKind {
+    /// The data ends before the value does
+    UnexpectedEnd,
+    /// An enum tag or variant index that doesn't match any variant
+    UnknownVariant { tag: u64 },
+    /// A bool that is not 0 or 1
+    InvalidBool { value: u8 },
+    /// A varint that is too long or doesn't fit in the size of the number
+    InvalidVarint,
+    /// A list length that doesn't match the length of the list in the layout
+    LengthMismatch { expected: usize, found: u64 },
+    /// A tagged field that isn't in the data and doesn't have a default
+    MissingField { id: u32 },
+    /// Data after a value that should fill the whole buffer. The walker never returns this.
+    TrailingBytes,
+}
+
+const fn read_error(offset: usize, kind: ReadErrorKind) -> ReadError {
+    ReadError { offset, kind }
+}
+
+const fn unexpected_end(bytes: &[u8]) -> ReadError {
+    read_error(bytes.len(), ReadErrorKind::UnexpectedEnd)
+}
+
+/// Check a value without reading it into memory. Returns the offset after the value.
+pub(crate) const fn check_value<F: WireFormat>(
+    bytes: &[u8],
+    offset: usize,
+    layout: &Layout,
+) -> Result<usize, ReadError> {
+    read_value::<F>(bytes, offset, layout, std::ptr::null_mut())
+}
+
+/// Read a value into the memory at out, or only check it if out is null. Returns the offset after the value.
+pub(crate) const fn read_value<F: WireFormat>(
+    bytes: &[u8],
+    offset: usize,
+    layout: &Layout,
+    out: *mut u8,
+) -> Result<usize, ReadError> {
+    match layout {
+        Layout::Enum(encoding) => read_enum::<F>(bytes, offset, encoding, out),
+        Layout::Struct(encoding) => read_struct::<F>(bytes, offset, encoding, out),
+        Layout::List(encoding) => {
+            let mut offset = match read_length(bytes, offset, F::LIST_LENGTHS) {
+                Ok((len, _))
+                    if !matches!(F::LIST_LENGTHS, LengthFormat::Omitted)
+                        && len != encoding.len as u64 =>
+                {
+                    return Err(read_error(
+                        offset,
+                        ReadErrorKind::LengthMismatch {
+                            expected: encoding.len,
+                            found: len,
+                        },
+                    ));
+                }
+                Ok((_, offset)) => offset,
+                Err(error) => return Err(error),
+            };
+            let item_size = encoding.item_encoding.size();
+            let mut i = 0;
+            while i < encoding.len {
+                let item = out_at(out, i * item_size);
+                offset = match read_value::<F>(bytes, offset, encoding.item_encoding, item) {
+                    Ok(offset) => offset,
+                    Err(error) => return Err(error),
+                };
+                i += 1;
+            }
+            Ok(offset)
+        }
+        Layout::Primitive(encoding) => read_primitive::<F>(bytes, offset, encoding, out),
+    }
+}
+
+const fn read_struct<F: WireFormat>(
+    bytes: &[u8],
+    offset: usize,
+    encoding: &StructEncoding,
+    out: *mut u8,
+) -> Result<usize, ReadError> {
+    if let LengthFormat::Omitted = F::STRUCT_LENGTHS {
+        return read_fields::<F>(bytes, offset, encoding, out);
+    }
+    let (len, offset) = match read_length(bytes, offset, F::STRUCT_LENGTHS) {
+        Ok(data) => data,
+        Err(error) => return Err(error),
+    };
+    if len > (bytes.len() - offset) as u64 {
+        return Err(unexpected_end(bytes));
+    }
+    let end = offset + len as usize;
+    // Only read the fields from inside the struct, then skip any fields this version doesn't know about
+    let (fields, _) = bytes.split_at(end);
+    match read_fields::<F>(fields, offset, encoding, out) {
+        Ok(_) => Ok(end),
+        Err(error) => Err(error),
+    }
+}
+
+const fn read_fields<F: WireFormat>(
+    bytes: &[u8],
+    mut offset: usize,
+    encoding: &StructEncoding,
+    out: *mut u8,
+) -> Result<usize, ReadError> {
+    if let FieldFormat::Tagged = F::STRUCT_FIELDS {
+        return read_tagged_fields::<F>(bytes, offset, encoding, out);
+    }
+    let mut i = 0;
+    while i < encoding.data.len() {
+        let field = &encoding.data[i];
+        let data = out_at(out, field.offset);
+        offset = match read_value::<F>(bytes, offset, &field.encoding, data) {
+            Ok(offset) => offset,
+            Err(error) => return Err(error),
+        };
+        i += 1;
+    }
+    Ok(offset)
+}
+
+/// Read tagged fields. Every known field is looked up by id in the serialized fields, so the fields can be in
+/// any order and unknown fields are skipped.
+const fn read_tagged_fields<F: WireFormat>(
+    bytes: &[u8],
+    offset: usize,
+    encoding: &StructEncoding,
+    out: *mut u8,
+) -> Result<usize, ReadError> {
+    let (count, start) = match read_varint(bytes, offset, 8) {
+        Ok(data) => data,
+        Err(error) => return Err(error),
+    };
+    // Find the end of the fields first, which also checks that every field fits in the buffer
+    let mut end = start;
+    let mut i = 0;
+    while i < count {
+        end = match read_tagged_field(bytes, end) {
+            Ok((_, _, field_end)) => field_end,
+            Err(error) => return Err(error),
+        };
+        i += 1;
+    }
+
+    let mut i = 0;
+    while i < encoding.data.len() {
+        let field = &encoding.data[i];
+        let data = out_at(out, field.offset);
+        let id = field_id(encoding, i);
+        let mut found = false;
+        let mut position = start;
+        while position < end {
+            let (field_id, payload, field_end) = match read_tagged_field(bytes, position) {
+                Ok(data) => data,
+                Err(error) => return Err(error),
+            };
+            if field_id == id as u64 {
+                // Only read inside the field, and skip anything after the value
+                let (payload_bytes, _) = bytes.split_at(field_end);
+                if let Err(error) = read_value::<F>(payload_bytes, payload, &field.encoding, data) {
+                    return Err(error);
+                }
+                found = true;
+            }
+            position = field_end;
+        }
+        if !found {
+            match field.default {
+                Some(_) if data.is_null() => {}
+                Some(FieldDefault::Memory(default)) => unsafe {
+                    std::ptr::copy_nonoverlapping(default, data, field.encoding.size())
+                },
+                Some(FieldDefault::Serialized(default)) => {
+                    if let Err(error) =
+                        read_value::<NativeFormat>(default, 0, &field.encoding, data)
+                    {
+                        return Err(error);
+                    }
+                }
+                None => return Err(read_error(start, ReadErrorKind::MissingField { id })),
+            }
+        }
+        i += 1;
+    }
+    Ok(end)
+}
+
+/// Read the header of a tagged field. Returns the id, the offset of the value and the offset after the field.
+const fn read_tagged_field(bytes: &[u8], offset: usize) -> Result<(u64, usize, usize), ReadError> {
+    let (id, offset) = match read_varint(bytes, offset, 4) {
+        Ok(data) => data,
+        Err(error) => return Err(error),
+    };
+    let (len, offset) = match read_varint(bytes, offset, 8) {
+        Ok(data) => data,
+        Err(error) => return Err(error),
+    };
+    if len > (bytes.len() - offset) as u64 {
+        return Err(unexpected_end(bytes));
+    }
+    Ok((id, offset, offset + len as usize))
+}
+
+const fn read_enum<F: WireFormat>(
+    bytes: &[u8],
+    offset: usize,
+    encoding: &EnumEncoding,
+    out: *mut u8,
+) -> Result<usize, ReadError> {
+    let size = encoding.discriminant.size;
+    let (tag, end) = match F::ENUM_TAGS {
+        TagFormat::Discriminant | TagFormat::OrderedDiscriminant => {
+            let tag = if let TagFormat::Discriminant = F::ENUM_TAGS {
+                read_le_bytes(bytes, offset, size)
+            } else {
+                read_be_bytes(bytes, offset, size)
+            };
+            match tag {
+                Ok(tag) => (tag, offset + size),
+                Err(error) => return Err(error),
+            }
+        }
+        TagFormat::IndexU32 => match read_le_bytes(bytes, offset, 4) {
+            Ok(index) => (index, offset + 4),
+            Err(error) => return Err(error),
+        },
+        TagFormat::IndexVarint => match read_varint(bytes, offset, 4) {
+            Ok(data) => data,
+            Err(error) => return Err(error),
+        },
+    };
+    let variant = match F::ENUM_TAGS {
+        TagFormat::Discriminant | TagFormat::OrderedDiscriminant => find_variant(encoding, tag),
+        TagFormat::IndexU32 | TagFormat::IndexVarint if tag < encoding.variants.len() as u64 => {
+            Some(&encoding.variants[tag as usize])
+        }
+        TagFormat::IndexU32 | TagFormat::IndexVarint => None,
+    };
+    let variant = match variant {
+        Some(variant) => variant,
+        None => return Err(read_error(offset, ReadErrorKind::UnknownVariant { tag })),
+    };
+    write_le(out, size, variant.tag as u64);
+    let data = out_at(out, encoding.variants_offset);
+    read_struct::<F>(bytes, end, &variant.data, data)
+}
+
+const fn find_variant(encoding: &EnumEncoding, tag: u64) -> Option<&EnumVariant> {
+    let mut i = 0;
+    while i < encoding.variants.len() {
+        let variant = &encoding.variants[i];
+        if variant.tag as u64 == tag {
+            return Some(variant);
+        }
+        i += 1;
+    }
+    None
+}
+
+const fn read_length(
+    bytes: &[u8],
+    offset: usize,
+    format: LengthFormat,
+) -> Result<(u64, usize), ReadError> {
+    match format {
+        LengthFormat::Omitted => Ok((0, offset)),
+        LengthFormat::U32 | LengthFormat::U64 => {
+            let size = if let LengthFormat::U32 = format { 4 } else { 8 };
+            match read_le_bytes(bytes, offset, size) {
+                Ok(len) => Ok((len, offset + size)),
+                Err(error) => Err(error),
+            }
+        }
+        LengthFormat::Varint => read_varint(bytes, offset, 8),
+    }
+}
+
+const fn read_primitive<F: WireFormat>(
+    bytes: &[u8],
+    offset: usize,
+    encoding: &PrimitiveEncoding,
+    out: *mut u8,
+) -> Result<usize, ReadError> {
+    let size = encoding.size;
+    let (value, end) = match F::PRIMITIVES {
+        PrimitiveFormat::Native => {
+            let value = if cfg!(all(feature = "test-big-endian", target_endian = "little")) {
+                read_be_bytes(bytes, offset, size)
+            } else {
+                read_le_bytes(bytes, offset, size)
+            };
+            (value, offset + size)
+        }
+        PrimitiveFormat::LittleEndian => (read_le_bytes(bytes, offset, size), offset + size),
+        PrimitiveFormat::BigEndian => (read_be_bytes(bytes, offset, size), offset + size),
+        PrimitiveFormat::Ordered => {
+            let value = match read_be_bytes(bytes, offset, size) {
+                Ok(value) => value,
+                Err(error) => return Err(error),
+            };
+            let sign = 1u64 << (size * 8 - 1);
+            let value = match encoding.kind {
+                PrimitiveKind::Unsigned | PrimitiveKind::Bool => value,
+                PrimitiveKind::Signed => value ^ sign,
+                // Positive floats were written with the sign bit set, negative floats with every bit flipped
+                PrimitiveKind::Float if value & sign != 0 => value ^ sign,
+                PrimitiveKind::Float => !value & (u64::MAX >> (64 - size * 8)),
+            };
+            (Ok(value), offset + size)
+        }
+        PrimitiveFormat::Varint => match encoding.kind {
+            PrimitiveKind::Float | PrimitiveKind::Bool => {
+                (read_le_bytes(bytes, offset, size), offset + size)
+            }
+            _ if size == 1 => (read_le_bytes(bytes, offset, 1), offset + 1),
+            PrimitiveKind::Unsigned => match read_varint(bytes, offset, size) {
+                Ok((value, end)) => (Ok(value), end),
+                Err(error) => return Err(error),
+            },
+            PrimitiveKind::Signed => match read_varint(bytes, offset, size) {
+                // Undo the zigzag encoding. The varint fits in the size, so the signed value does too.
+                Ok((value, end)) => (
+                    Ok((((value >> 1) as i64) ^ -((value & 1) as i64)) as u64),
+                    end,
+                ),
+                Err(error) => return Err(error),
+            },
+        },
+    };
+    let value = match value {
+        Ok(value) => value,
+        Err(error) => return Err(error),
+    };
+    if !encoding.is_valid(value) {
+        // Only bools have invalid values
+        return Err(read_error(
+            offset,
+            ReadErrorKind::InvalidBool { value: value as u8 },
+        ));
+    }
+    write_le(out, size, value);
+    Ok(end)
+}
+
+/// The memory at the offset from out, or null if the value is only checked
+const fn out_at(out: *mut u8, offset: usize) -> *mut u8 {
+    if out.is_null() {
+        out
+    } else {
+        unsafe { out.add(offset) }
+    }
+}
+
+/// Read a number of the given size stored in memory in the byte order of the target
+const fn read_le(ptr: *const u8, size: usize) -> u64 {
+    let mut value = 0;
+    let mut i = 0;
+    while i < size {
+        value |= (unsafe { byte_le(ptr, size, i) } as u64) << (i * 8);
+        i += 1;
+    }
+    value
+}
+
+/// Write the low `size` bytes of a number into memory in the byte order of the target
+const fn write_le(out: *mut u8, size: usize, value: u64) {
+    if out.is_null() {
+        return;
+    }
+    let mut i = 0;
+    while i < size {
+        let byte = (value >> (i * 8)) as u8;
+        if cfg!(target_endian = "big") {
+            unsafe { out.add(size - i - 1).write(byte) };
+        } else {
+            unsafe { out.add(i).write(byte) };
+        }
+        i += 1;
+    }
+}
+
+const fn push_le(mut to: ConstWriteBuffer, size: usize, value: u64) -> ConstWriteBuffer {
+    let mut i = 0;
+    while i < size {
+        to = to.push((value >> (i * 8)) as u8);
+        i += 1;
+    }
+    to
+}
+
+const fn push_be(mut to: ConstWriteBuffer, size: usize, value: u64) -> ConstWriteBuffer {
+    let mut i = 0;
+    while i < size {
+        to = to.push((value >> ((size - i - 1) * 8)) as u8);
+        i += 1;
+    }
+    to
+}
+
+const fn push_bytes(mut to: ConstWriteBuffer, bytes: &[u8]) -> ConstWriteBuffer {
+    let mut i = 0;
+    while i < bytes.len() {
+        to = to.push(bytes[i]);
+        i += 1;
+    }
+    to
+}
+
+const fn push_varint(mut to: ConstWriteBuffer, mut value: u64) -> ConstWriteBuffer {
+    while value >= 0x80 {
+        to = to.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    to.push(value as u8)
+}
+
+const fn read_le_bytes(bytes: &[u8], offset: usize, size: usize) -> Result<u64, ReadError> {
+    if bytes.len() < offset + size {
+        return Err(unexpected_end(bytes));
+    }
+    let mut value = 0;
+    let mut i = 0;
+    while i < size {
+        value |= (bytes[offset + i] as u64) << (i * 8);
+        i += 1;
+    }
+    Ok(value)
+}
+
+const fn read_be_bytes(bytes: &[u8], offset: usize, size: usize) -> Result<u64, ReadError> {
+    if bytes.len() < offset + size {
+        return Err(unexpected_end(bytes));
+    }
+    let mut value = 0;
+    let mut i = 0;
+    while i < size {
+        value = (value << 8) | bytes[offset + i] as u64;
+        i += 1;
+    }
+    Ok(value)
+}
+
+/// Read a varint that must fit in `size` bytes. Returns the value and the offset after it.
+const fn read_varint(
+    bytes: &[u8],
+    mut offset: usize,
+    size: usize,
+) -> Result<(u64, usize), ReadError> {
+    let start = offset;
+    let bits = size as u32 * 8;
+    let max_len = bits.div_ceil(7);
+    let mut value = 0u64;
+    let mut i = 0;
+    while i < max_len {
+        if offset >= bytes.len() {
+            return Err(unexpected_end(bytes));
+        }
+        let byte = bytes[offset];
+        offset += 1;
+        value |= ((byte & 0x7f) as u64) << (i * 7);
+        if byte & 0x80 == 0 {
+            if bits < 64 && value >> bits != 0 {
+                break;
+            }
+            // The last byte of a u64 only has room for one bit
+            if bits == 64 && i == max_len - 1 && byte > 1 {
+                break;
+            }
+            return Ok((value, offset));
+        }
+        i += 1;
+    }
+    Err(read_error(start, ReadErrorKind::InvalidVarint))
+}
@@ -0,0 +1,230 @@
+use crate::{serialize_const, ConstWriteBuffer, SerializeConst};
+
+const SHA256_INITIAL: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const SHA256_ROUND_CONSTANTS: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// Compute the SHA-256 digest of the bytes in const
+pub const fn sha256(bytes: &[u8]) -> [u8; 32] {
+    let mut state = SHA256_INITIAL;
+
+    // The message is padded with a one bit, zeros and the length in bits to a multiple of 64 bytes
+    let padded_len = (bytes.len() + 1 + 8).div_ceil(64) * 64;
+    let bit_len = (bytes.len() as u64).wrapping_mul(8).to_be_bytes();
+    let mut start = 0;
+    while start < padded_len {
+        let mut block = [0u8; 64];
+        let mut i = 0;
+        while i < 64 {
+            let index = start + i;
+            block[i] = if index < bytes.len() {
+                bytes[index]
+            } else if index == bytes.len() {
+                0x80
+            } else if index >= padded_len - 8 {
+                bit_len[index - (padded_len - 8)]
+            } else {
+                0
+            };
+            i += 1;
+        }
+        state = sha256_compress(state, &block);
+        start += 64;
+    }
+
+    let mut digest = [0; 32];
+    let mut i = 0;
+    while i < 8 {
+        let word = state[i].to_be_bytes();
+        digest[i * 4] = word[0];
+        digest[i * 4 + 1] = word[1];
+        digest[i * 4 + 2] = word[2];
+        digest[i * 4 + 3] = word[3];
+        i += 1;
+    }
+    digest
+}
+
+const fn sha256_compress(state: [u32; 8], block: &[u8; 64]) -> [u32; 8] {
+    let mut schedule = [0u32; 64];
+    let mut i = 0;
+    while i < 16 {
+        schedule[i] = u32::from_be_bytes([
+            block[i * 4],
+            block[i * 4 + 1],
+            block[i * 4 + 2],
+            block[i * 4 + 3],
+        ]);
+        i += 1;
+    }
+    while i < 64 {
+        let s0 = schedule[i - 15].rotate_right(7)
+            ^ schedule[i - 15].rotate_right(18)
+            ^ (schedule[i - 15] >> 3);
+        let s1 = schedule[i - 2].rotate_right(17)
+            ^ schedule[i - 2].rotate_right(19)
+            ^ (schedule[i - 2] >> 10);
+        schedule[i] = schedule[i - 16]
+            .wrapping_add(s0)
+            .wrapping_add(schedule[i - 7])
+            .wrapping_add(s1);
+        i += 1;
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = state;
+    let mut i = 0;
+    while i < 64 {
+        let s1 = e.rotate_right(6) ^ e.rotate_right(11) ^ e.rotate_right(25);
+        let choice = (e & f) ^ (!e & g);
+        let temp1 = h
+            .wrapping_add(s1)
+            .wrapping_add(choice)
+            .wrapping_add(SHA256_ROUND_CONSTANTS[i])
+            .wrapping_add(schedule[i]);
+        let s0 = a.rotate_right(2) ^ a.rotate_right(13) ^ a.rotate_right(22);
+        let majority = (a & b) ^ (a & c) ^ (b & c);
+        let temp2 = s0.wrapping_add(majority);
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(temp1);
+        d = c;
+        c = b;
+        b = a;
+        a = temp1.wrapping_add(temp2);
+        i += 1;
+    }
+
+    [
+        state[0].wrapping_add(a),
+        state[1].wrapping_add(b),
+        state[2].wrapping_add(c),
+        state[3].wrapping_add(d),
+        state[4].wrapping_add(e),
+        state[5].wrapping_add(f),
+        state[6].wrapping_add(g),
+        state[7].wrapping_add(h),
+    ]
+}
+
+const XXH_PRIME64_1: u64 = 0x9e3779b185ebca87;
+const XXH_PRIME64_2: u64 = 0xc2b2ae3d27d4eb4f;
+const XXH_PRIME64_3: u64 = 0x165667b19e3779f9;
+const XXH_PRIME64_4: u64 = 0x85ebca77c2b2ae63;
+const XXH_PRIME64_5: u64 = 0x27d4eb2f165667c5;
+
+/// Compute the 64 bit xxHash (XXH64) of the bytes in const
+pub const fn xxhash64(bytes: &[u8], seed: u64) -> u64 {
+    let len = bytes.len();
+    let mut offset = 0;
+    let mut hash;
+
+    if len >= 32 {
+        let mut lanes = [
+            seed.wrapping_add(XXH_PRIME64_1).wrapping_add(XXH_PRIME64_2),
+            seed.wrapping_add(XXH_PRIME64_2),
+            seed,
+            seed.wrapping_sub(XXH_PRIME64_1),
+        ];
+        while offset + 32 <= len {
+            let mut lane = 0;
+            while lane < 4 {
+                lanes[lane] = xxh64_round(lanes[lane], read_u64(bytes, offset + lane * 8));
+                lane += 1;
+            }
+            offset += 32;
+        }
+        hash = lanes[0]
+            .rotate_left(1)
+            .wrapping_add(lanes[1].rotate_left(7))
+            .wrapping_add(lanes[2].rotate_left(12))
+            .wrapping_add(lanes[3].rotate_left(18));
+        let mut lane = 0;
+        while lane < 4 {
+            hash = (hash ^ xxh64_round(0, lanes[lane]))
+                .wrapping_mul(XXH_PRIME64_1)
+                .wrapping_add(XXH_PRIME64_4);
+            lane += 1;
+        }
+    } else {
+        hash = seed.wrapping_add(XXH_PRIME64_5);
+    }
+
+    hash = hash.wrapping_add(len as u64);
+
+    while offset + 8 <= len {
+        hash ^= xxh64_round(0, read_u64(bytes, offset));
+        hash = hash
+            .rotate_left(27)
+            .wrapping_mul(XXH_PRIME64_1)
+            .wrapping_add(XXH_PRIME64_4);
+        offset += 8;
+    }
+    if offset + 4 <= len {
+        let word = u32::from_le_bytes([
+            bytes[offset],
+            bytes[offset + 1],
+            bytes[offset + 2],
+            bytes[offset + 3],
+        ]);
+        hash ^= (word as u64).wrapping_mul(XXH_PRIME64_1);
+        hash = hash
+            .rotate_left(23)
+            .wrapping_mul(XXH_PRIME64_2)
+            .wrapping_add(XXH_PRIME64_3);
+        offset += 4;
+    }
+    while offset < len {
+        hash ^= (bytes[offset] as u64).wrapping_mul(XXH_PRIME64_5);
+        hash = hash.rotate_left(11).wrapping_mul(XXH_PRIME64_1);
+        offset += 1;
+    }
+
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(XXH_PRIME64_2);
+    hash ^= hash >> 29;
+    hash = hash.wrapping_mul(XXH_PRIME64_3);
+    hash ^ (hash >> 32)
+}
+
+const fn xxh64_round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(XXH_PRIME64_2))
+        .rotate_left(31)
+        .wrapping_mul(XXH_PRIME64_1)
+}
+
+const fn read_u64(bytes: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes([
+        bytes[offset],
+        bytes[offset + 1],
+        bytes[offset + 2],
+        bytes[offset + 3],
+        bytes[offset + 4],
+        bytes[offset + 5],
+        bytes[offset + 6],
+        bytes[offset + 7],
+    ])
+}
+
+/// Compute the [`xxhash64`] of the serialized bytes of a value with a seed of 0
+pub const fn serialize_hash<T: SerializeConst>(data: &T) -> u64 {
+    let buffer = serialize_const(data, ConstWriteBuffer::new());
+    xxhash64(buffer.as_ref(), 0)
+}
+
+/// Compute the [`sha256`] digest of the serialized bytes of a value
+pub const fn serialize_sha256<T: SerializeConst>(data: &T) -> [u8; 32] {
+    let buffer = serialize_const(data, ConstWriteBuffer::new());
+    sha256(buffer.as_ref())
+}
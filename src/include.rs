@@ -0,0 +1,81 @@
+use std::mem::MaybeUninit;
+
+use crate::const_str::push_number;
+use crate::format::{read_value, NativeFormat, ReadError, ReadErrorKind};
+use crate::{ConstStr, SerializeConst};
+
+const fn failure_message(file: &str, error: ReadError) -> ConstStr {
+    let mut message = ConstStr::new()
+        .push_str("failed to deserialize \"")
+        .push_str(file)
+        .push_str("\" at offset ");
+    message = push_number(message, error.offset);
+    message = message.push_str(": ");
+    match error.kind {
+        ReadErrorKind::UnexpectedEnd => message.push_str("unexpected end of data"),
+        ReadErrorKind::UnknownVariant { tag } => {
+            push_number(message.push_str("unknown enum variant "), tag as usize)
+        }
+        ReadErrorKind::InvalidBool { value } => {
+            const DIGITS: &[u8; 16] = b"0123456789abcdef";
+            message
+                .push_str("invalid bool 0x")
+                .push(DIGITS[(value >> 4) as usize] as char)
+                .push(DIGITS[(value & 0xf) as usize] as char)
+        }
+        ReadErrorKind::InvalidVarint => message.push_str("invalid varint"),
+        ReadErrorKind::LengthMismatch { expected, found } => {
+            message = push_number(message.push_str("expected a list of "), expected);
+            push_number(message.push_str(" items, found "), found as usize)
+        }
+        ReadErrorKind::MissingField { id } => {
+            push_number(message.push_str("missing field with id "), id as usize)
+        }
+        ReadErrorKind::TrailingBytes => message.push_str("unexpected bytes after the value"),
+    }
+}
+
+/// Deserialize a file included with [`include_const!`](crate::include_const). The whole file must be one valid
+/// value of the type, otherwise this panics with the file, the offset and the reason.
+#[doc(hidden)]
+pub const fn include_const_raw<T: SerializeConst>(bytes: &[u8], file: &str) -> T {
+    let mut out = MaybeUninit::<T>::uninit();
+    let ptr = out.as_mut_ptr() as *mut u8;
+    let error = match read_value::<NativeFormat>(bytes, 0, &T::MEMORY_LAYOUT, ptr) {
+        Ok(end) if end == bytes.len() => None,
+        Ok(end) => Some(ReadError {
+            offset: end,
+            kind: ReadErrorKind::TrailingBytes,
+        }),
+        Err(error) => Some(error),
+    };
+    if let Some(error) = error {
+        let message = failure_message(file, error);
+        panic!("{}", message.as_str());
+    }
+    // SAFETY: every byte of the layout of T was filled in from valid data
+    unsafe { out.assume_init() }
+}
+
+/// Deserialize a file in const. The file is read with `include_bytes!`, so the path is relative to the
+/// current file. The whole file must be one serialized value of the type. Anything else is a compile
+/// time error that names the file, the offset in the file and the reason.
+///
+/// ```rust
+/// use const_serialize::{include_const, SerializeConst};
+///
+/// #[derive(Clone, Copy, PartialEq, Debug, SerializeConst)]
+/// struct Config {
+///     port: u16,
+///     verbose: bool,
+/// }
+///
+/// const CONFIG: Config = include_const!(Config, "../tests/fixtures/include/config.bin");
+/// ```
+#[macro_export]
+macro_rules! include_const {
+    ($type:ty, $file:literal) => {{
+        const __VALUE: $type = $crate::include_const_raw::<$type>(include_bytes!($file), $file);
+        __VALUE
+    }};
+}
@@ -1,34 +1,194 @@
 use std::mem::MaybeUninit;
 
+use format::read_value;
+
+mod bincode;
+mod checksum;
+mod compact;
+mod compare;
 mod const_buffers;
+mod const_str;
 mod const_vec;
-
+#[cfg(feature = "std")]
+mod diff;
+#[cfg(feature = "std")]
+mod dyn_layout;
+mod embed;
+mod envelope;
+#[cfg(any(feature = "object", feature = "wasm"))]
+mod extract;
+mod fingerprint;
+mod format;
+mod hash;
+mod include;
+mod map;
+mod memcomparable;
+mod path;
+#[cfg(feature = "std")]
+mod pretty;
+mod schema;
+mod slice;
+mod tagged;
+mod text;
+#[cfg(feature = "std")]
+mod value;
+mod view;
+
+pub use bincode::{deserialize_bincode, serialize_bincode, BincodeFormat};
+pub use checksum::crc32;
+pub use compact::{deserialize_compact, serialize_compact, CompactFormat};
+pub use compare::{const_cmp, const_eq, const_hash};
 pub use const_buffers::{ConstReadBuffer, ConstWriteBuffer};
+pub use const_str::ConstStr;
 pub use derive_const_serialize::SerializeConst;
+#[cfg(feature = "std")]
+pub use diff::{diff_values, Difference};
+#[cfg(feature = "std")]
+pub use dyn_layout::{
+    DynEnumEncoding, DynEnumVariant, DynLayout, DynListEncoding, DynPlainOldData,
+    DynStructEncoding, LayoutError,
+};
+pub use embed::{
+    record_array, serialize_described_record, serialize_record, serialize_schema_record,
+    DESCRIBED_RECORD_MAGIC, RECORD_HEADER_SIZE, RECORD_MAGIC, SCHEMA_RECORD_MAGIC,
+};
+pub use envelope::{
+    deserialize_enveloped_raw, serialize_enveloped, EnvelopeError, ENVELOPE_HEADER_SIZE,
+    ENVELOPE_MAGIC, ENVELOPE_VERSION,
+};
+#[cfg(feature = "object")]
+pub use extract::read_records;
+#[cfg(feature = "wasm")]
+pub use extract::{read_wasm_records, read_wasm_records_lossy};
+#[cfg(any(feature = "object", feature = "wasm"))]
+pub use extract::{EmbeddedRecord, ExtractError, RecordError};
+pub use format::{
+    deserialize_with, serialize_with, FieldFormat, LengthFormat, NativeFormat, PrimitiveFormat,
+    TagFormat, WireFormat,
+};
+pub use hash::{serialize_hash, serialize_sha256, sha256, xxhash64};
+#[doc(hidden)]
+pub use include::include_const_raw;
+pub use map::ConstMap;
+pub use memcomparable::{deserialize_memcomparable, serialize_memcomparable, MemcomparableFormat};
+#[doc(hidden)]
+pub use path::PatchTarget;
+pub use path::{
+    deserialize_field_raw, field_offset, patch_field_raw, serialized_len_at, skip_value,
+    PatchError, PathSegment,
+};
+#[cfg(feature = "std")]
+pub use pretty::{hexdump, HexDump};
+pub use schema::{serialize_layout, SCHEMA_VERSION};
+pub use slice::SerializedSlice;
+pub use tagged::{deserialize_tagged, serialize_tagged, TaggedFormat};
+pub use text::{
+    base64_decoded_len, decode_base64, decode_hex, encode_base64, encode_hex, hex_decoded_len,
+};
+#[cfg(feature = "std")]
+pub use value::{
+    decode_value, encode_value, DecodeError, DecodeErrorKind, EncodeError, EncodeErrorKind,
+    EnumValue, FieldValue, Primitive, StructValue, Value,
+};
+pub use view::SerializeConstView;
+#[doc(hidden)]
+pub use view::{view_field, view_len, view_offsets, view_tag, view_variant_offsets};
 
 /// Plain old data for a field. Stores the offset of the field in the struct and the encoding of the field.
 #[derive(Debug, Copy, Clone)]
 pub struct PlainOldData {
     offset: usize,
     encoding: Layout,
+    name: Option<&'static str>,
+    id: Option<u32>,
+    default: Option<FieldDefault>,
 }
 
 impl PlainOldData {
     pub const fn new(offset: usize, encoding: Layout) -> Self {
-        Self { offset, encoding }
+        Self {
+            offset,
+            encoding,
+            name: None,
+            id: None,
+            default: None,
+        }
     }
+
+    /// Set the name of the field. Names are not part of the serialized data, but runtime tooling uses them to describe values.
+    pub const fn with_name(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Set the stable id of the field. Formats with [`FieldFormat::Tagged`] write the id before the field instead of
+    /// relying on the position of the field.
+    pub const fn with_id(mut self, id: u32) -> Self {
+        self.id = Some(id);
+        self
+    }
+
+    /// Set the value formats with [`FieldFormat::Tagged`] use when the field is missing from the serialized data.
+    /// The type of the value must have the same layout as the field, so panics if the
+    /// [fingerprints](Layout::fingerprint) or sizes of the layouts are different.
+    pub const fn with_default<T: SerializeConst>(mut self, value: &'static T) -> Self {
+        if self.encoding.size() != std::mem::size_of::<T>()
+            || self.encoding.fingerprint() != T::MEMORY_LAYOUT.fingerprint()
+        {
+            panic!("the default value doesn't match the layout of the field");
+        }
+        self.default = Some(FieldDefault::Memory(value as *const T as *const u8));
+        self
+    }
+
+    /// Set the default from a value of the field serialized with [`serialize_const`]. Unlike [`Self::with_default`]
+    /// this works for values that can't be borrowed for `'static` in a const, like values of a generic type.
+    /// Panics if the bytes are not exactly one value with the layout of the field.
+    pub const fn with_default_serialized(mut self, serialized: &'static [u8]) -> Self {
+        match format::check_value::<format::NativeFormat>(serialized, 0, &self.encoding) {
+            Ok(len) if len == serialized.len() => {}
+            _ => panic!("the default value doesn't match the layout of the field"),
+        }
+        self.default = Some(FieldDefault::Serialized(serialized));
+        self
+    }
+}
+
+/// The default value of a field
+#[derive(Debug, Copy, Clone)]
+enum FieldDefault {
+    /// A pointer to a value with the layout of the field
+    Memory(*const u8),
+    /// The value serialized in the native format
+    Serialized(&'static [u8]),
 }
 
+// SAFETY: the pointer is created from a `&'static T` where `T: SerializeConst`, so it points to plain old data that
+// is never mutated
+unsafe impl Send for FieldDefault {}
+unsafe impl Sync for FieldDefault {}
+
 /// Layout for a struct. The struct encoding is just a list of fields with offsets
 #[derive(Debug, Copy, Clone)]
 pub struct StructEncoding {
     size: usize,
     data: &'static [PlainOldData],
+    name: Option<&'static str>,
 }
 
 impl StructEncoding {
     pub const fn new(size: usize, data: &'static [PlainOldData]) -> Self {
-        Self { size, data }
+        Self {
+            size,
+            data,
+            name: None,
+        }
+    }
+
+    /// Set the name of the struct. For the data of an enum variant, this is the name of the variant.
+    pub const fn with_name(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
     }
 }
 
@@ -39,6 +199,7 @@ pub struct EnumEncoding {
     discriminant: PrimitiveEncoding,
     variants_offset: usize,
     variants: &'static [EnumVariant],
+    name: Option<&'static str>,
 }
 
 impl EnumEncoding {
@@ -57,15 +218,27 @@ impl EnumEncoding {
             i += 1;
         }
 
-        let variants_offset = (discriminant.size / max_align) + max_align;
+        let variants_offset = variants_offset(&discriminant, max_align);
 
         Self {
             size,
             discriminant,
             variants_offset,
             variants,
+            name: None,
         }
     }
+
+    /// Set the name of the enum
+    pub const fn with_name(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+}
+
+/// The offset of the variant data in an enum with the given discriminant and the maximum alignment of the variants
+const fn variants_offset(discriminant: &PrimitiveEncoding, max_align: usize) -> usize {
+    (discriminant.size / max_align).saturating_add(max_align)
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -96,17 +269,44 @@ impl ListEncoding {
 }
 
 /// The encoding for a primitive type. The bytes will be reversed if the target is big endian.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PrimitiveEncoding {
     size: usize,
+    kind: PrimitiveKind,
 }
 
 impl PrimitiveEncoding {
+    /// Create the encoding for an unsigned integer with the given size
     pub const fn new(size: usize) -> Self {
-        Self { size }
+        Self {
+            size,
+            kind: PrimitiveKind::Unsigned,
+        }
+    }
+
+    /// Set how the bytes of the primitive should be interpreted
+    pub const fn with_kind(mut self, kind: PrimitiveKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// Check a number read from serialized data before it is written into memory. Bools must be 0 or 1.
+    pub(crate) const fn is_valid(&self, value: u64) -> bool {
+        !matches!(self.kind, PrimitiveKind::Bool) || value <= 1
     }
 }
 
+/// How the bytes of a primitive are interpreted. This does not change the serialized data, but runtime tooling uses it to describe values.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PrimitiveKind {
+    Unsigned,
+    Signed,
+    Float,
+    Bool,
+}
+
 /// The encoding for a type. This encoding defines a sequence of locations and reversed or not bytes. These bytes will be copied from during serialization and copied into during deserialization.
 #[derive(Debug, Copy, Clone)]
 pub enum Layout {
@@ -139,26 +339,27 @@ pub unsafe trait SerializeConst: Sized {
 }
 
 macro_rules! impl_serialize_const {
-    ($type:ty) => {
+    ($type:ty, $kind:ident) => {
         unsafe impl SerializeConst for $type {
             const MEMORY_LAYOUT: Layout = Layout::Primitive(PrimitiveEncoding {
                 size: std::mem::size_of::<$type>(),
+                kind: PrimitiveKind::$kind,
             });
         }
     };
 }
 
-impl_serialize_const!(u8);
-impl_serialize_const!(u16);
-impl_serialize_const!(u32);
-impl_serialize_const!(u64);
-impl_serialize_const!(i8);
-impl_serialize_const!(i16);
-impl_serialize_const!(i32);
-impl_serialize_const!(i64);
-impl_serialize_const!(bool);
-impl_serialize_const!(f32);
-impl_serialize_const!(f64);
+impl_serialize_const!(u8, Unsigned);
+impl_serialize_const!(u16, Unsigned);
+impl_serialize_const!(u32, Unsigned);
+impl_serialize_const!(u64, Unsigned);
+impl_serialize_const!(i8, Signed);
+impl_serialize_const!(i16, Signed);
+impl_serialize_const!(i32, Signed);
+impl_serialize_const!(i64, Signed);
+impl_serialize_const!(bool, Bool);
+impl_serialize_const!(f32, Float);
+impl_serialize_const!(f64, Float);
 
 unsafe impl<const N: usize, T: SerializeConst> SerializeConst for [T; N] {
     const MEMORY_LAYOUT: Layout = Layout::List(ListEncoding {
@@ -174,14 +375,14 @@ macro_rules! impl_serialize_const_tuple {
     (@impl $inner:ty = $($generic:ident: $generic_number:expr),*) => {
         unsafe impl<$($generic: SerializeConst),*> SerializeConst for ($($generic,)*) {
             const MEMORY_LAYOUT: Layout = {
-                Layout::Struct(StructEncoding {
-                    size: std::mem::size_of::<($($generic,)*)>(),
-                    data: &[
+                Layout::Struct(StructEncoding::new(
+                    std::mem::size_of::<($($generic,)*)>(),
+                    &[
                         $(
                             PlainOldData::new(std::mem::offset_of!($inner, $generic_number), $generic::MEMORY_LAYOUT),
                         )*
                     ],
-                })
+                ))
             };
         }
     };
@@ -198,264 +399,22 @@ impl_serialize_const_tuple!(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8:
 impl_serialize_const_tuple!(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8: 7, T9: 8);
 impl_serialize_const_tuple!(T1: 0, T2: 1, T3: 2, T4: 3, T5: 4, T6: 5, T7: 6, T8: 7, T9: 8, T10: 9);
 
-/// Serialize a struct that is stored at the pointer passed in
-const fn serialize_const_struct(
-    ptr: *const (),
-    mut to: ConstWriteBuffer,
-    encoding: &StructEncoding,
-) -> ConstWriteBuffer {
-    let mut i = 0;
-    while i < encoding.data.len() {
-        // Serialize the field at the offset pointer in the struct
-        let PlainOldData { offset, encoding } = &encoding.data[i];
-        let field = unsafe { ptr.byte_add(*offset) };
-        to = serialize_const_ptr(field, to, encoding);
-        i += 1;
-    }
-    to
-}
-
-/// Serialize an enum that is stored at the pointer passed in
-const fn serialize_const_enum(
-    ptr: *const (),
-    mut to: ConstWriteBuffer,
-    encoding: &EnumEncoding,
-) -> ConstWriteBuffer {
-    let mut discriminant = 0;
-
-    let byte_ptr = ptr as *const u8;
-    let mut offset = 0;
-    while offset < encoding.discriminant.size {
-        // If the bytes are reversed, walk backwards from the end of the number when pushing bytes
-        let byte = if cfg!(target_endian = "big") {
-            unsafe {
-                byte_ptr
-                    .byte_add(encoding.discriminant.size - offset - 1)
-                    .read()
-            }
-        } else {
-            unsafe { byte_ptr.byte_add(offset).read() }
-        };
-        to = to.push(byte);
-        discriminant |= (byte as u32) << (offset * 8);
-        offset += 1;
-    }
-
-    let mut i = 0;
-    while i < encoding.variants.len() {
-        // If the variant is the discriminated one, serialize it
-        let EnumVariant { tag, data, .. } = &encoding.variants[i];
-        if discriminant == *tag {
-            let data_ptr = unsafe { ptr.byte_add(encoding.variants_offset) };
-            to = serialize_const_struct(data_ptr, to, data);
-            break;
-        }
-        i += 1;
-    }
-    to
-}
-
-/// Serialize a primitive type that is stored at the pointer passed in
-const fn serialize_const_primitive(
-    ptr: *const (),
-    mut to: ConstWriteBuffer,
-    encoding: &PrimitiveEncoding,
-) -> ConstWriteBuffer {
-    let ptr = ptr as *const u8;
-    let mut offset = 0;
-    while offset < encoding.size {
-        // If the bytes are reversed, walk backwards from the end of the number when pushing bytes
-        if cfg!(any(target_endian = "big", feature = "test-big-endian")) {
-            to = to.push(unsafe { ptr.byte_add(encoding.size - offset - 1).read() });
-        } else {
-            to = to.push(unsafe { ptr.byte_add(offset).read() });
-        }
-        offset += 1;
-    }
-    to
-}
-
-/// Serialize a constant sized array that is stored at the pointer passed in
-const fn serialize_const_list(
-    ptr: *const (),
-    mut to: ConstWriteBuffer,
-    encoding: &ListEncoding,
-) -> ConstWriteBuffer {
-    let len = encoding.len;
-    let mut i = 0;
-    while i < len {
-        let field = unsafe { ptr.byte_add(i * encoding.item_encoding.size()) };
-        to = serialize_const_ptr(field, to, encoding.item_encoding);
-        i += 1;
-    }
-    to
-}
-
-/// Serialize a pointer to a type that is stored at the pointer passed in
-const fn serialize_const_ptr(
-    ptr: *const (),
-    to: ConstWriteBuffer,
-    encoding: &Layout,
-) -> ConstWriteBuffer {
-    match encoding {
-        Layout::Enum(encoding) => serialize_const_enum(ptr, to, encoding),
-        Layout::Struct(encoding) => serialize_const_struct(ptr, to, encoding),
-        Layout::List(encoding) => serialize_const_list(ptr, to, encoding),
-        Layout::Primitive(encoding) => serialize_const_primitive(ptr, to, encoding),
-    }
-}
-
 /// Serialize a type into a buffer
 #[must_use = "The data is serialized into the returned buffer"]
 pub const fn serialize_const<T: SerializeConst>(
     data: &T,
     to: ConstWriteBuffer,
 ) -> ConstWriteBuffer {
-    let ptr = data as *const T as *const ();
-    serialize_const_ptr(ptr, to, &T::MEMORY_LAYOUT)
-}
-
-/// Deserialize a primitive type into the out buffer at the offset passed in. Returns a new version of the buffer with the data added.
-const fn deserialize_const_primitive<'a, const N: usize>(
-    mut from: ConstReadBuffer<'a>,
-    encoding: &PrimitiveEncoding,
-    out: (usize, [MaybeUninit<u8>; N]),
-) -> Option<(ConstReadBuffer<'a>, [MaybeUninit<u8>; N])> {
-    let (start, mut out) = out;
-    let mut offset = 0;
-    while offset < encoding.size {
-        // If the bytes are reversed, walk backwards from the end of the number when filling in bytes
-        let (from_new, value) = match from.get() {
-            Some(data) => data,
-            None => return None,
-        };
-        from = from_new;
-        if cfg!(any(target_endian = "big", feature = "test-big-endian")) {
-            out[start + encoding.size - offset - 1] = MaybeUninit::new(value);
-        } else {
-            out[start + offset] = MaybeUninit::new(value);
-        }
-        offset += 1;
-    }
-    Some((from, out))
-}
-
-/// Deserialize a struct type into the out buffer at the offset passed in. Returns a new version of the buffer with the data added.
-const fn deserialize_const_struct<'a, const N: usize>(
-    mut from: ConstReadBuffer<'a>,
-    encoding: &StructEncoding,
-    out: (usize, [MaybeUninit<u8>; N]),
-) -> Option<(ConstReadBuffer<'a>, [MaybeUninit<u8>; N])> {
-    let (start, mut out) = out;
-    let mut i = 0;
-    while i < encoding.data.len() {
-        // Deserialize the field at the offset pointer in the struct
-        let PlainOldData { offset, encoding } = &encoding.data[i];
-        let (new_from, new_out) =
-            match deserialize_const_ptr(from, encoding, (start + *offset, out)) {
-                Some(data) => data,
-                None => return None,
-            };
-        from = new_from;
-        out = new_out;
-        i += 1;
-    }
-    Some((from, out))
+    serialize_with::<NativeFormat, T>(data, to)
 }
 
-/// Deserialize an enum type into the out buffer at the offset passed in. Returns a new version of the buffer with the data added.
-const fn deserialize_const_enum<'a, const N: usize>(
-    mut from: ConstReadBuffer<'a>,
-    encoding: &EnumEncoding,
-    out: (usize, [MaybeUninit<u8>; N]),
-) -> Option<(ConstReadBuffer<'a>, [MaybeUninit<u8>; N])> {
-    let (start, mut out) = out;
-    let mut discriminant = 0;
-
-    // First, deserialize the discriminant
-    let mut offset = 0;
-    while offset < encoding.discriminant.size {
-        // If the bytes are reversed, walk backwards from the end of the number when filling in bytes
-        let (from_new, value) = match from.get() {
-            Some(data) => data,
-            None => return None,
-        };
-        from = from_new;
-        if cfg!(target_endian = "big") {
-            out[start + encoding.size - offset - 1] = MaybeUninit::new(value);
-            discriminant |= (value as u32) << ((encoding.discriminant.size - offset - 1) * 8);
-        } else {
-            out[start + offset] = MaybeUninit::new(value);
-            discriminant |= (value as u32) << (offset * 8);
-        }
-        offset += 1;
-    }
-
-    // Then, deserialize the variant
-    let mut i = 0;
-    let mut matched_variant = false;
-    while i < encoding.variants.len() {
-        // If the variant is the discriminated one, deserialize it
-        let EnumVariant { tag, data, .. } = &encoding.variants[i];
-        if discriminant == *tag {
-            let offset = encoding.variants_offset;
-            let (new_from, new_out) =
-                match deserialize_const_struct(from, data, (start + offset, out)) {
-                    Some(data) => data,
-                    None => return None,
-                };
-            from = new_from;
-            out = new_out;
-            matched_variant = true;
-            break;
-        }
-        i += 1;
-    }
-    if !matched_variant {
-        return None;
-    }
-
-    Some((from, out))
-}
-
-/// Deserialize a list type into the out buffer at the offset passed in. Returns a new version of the buffer with the data added.
-const fn deserialize_const_list<'a, const N: usize>(
-    mut from: ConstReadBuffer<'a>,
-    encoding: &ListEncoding,
-    out: (usize, [MaybeUninit<u8>; N]),
-) -> Option<(ConstReadBuffer<'a>, [MaybeUninit<u8>; N])> {
-    let (start, mut out) = out;
-    let len = encoding.len;
-    let item_encoding = encoding.item_encoding;
-    let mut i = 0;
-    while i < len {
-        let (new_from, new_out) = match deserialize_const_ptr(
-            from,
-            item_encoding,
-            (start + i * item_encoding.size(), out),
-        ) {
-            Some(data) => data,
-            None => return None,
-        };
-        from = new_from;
-        out = new_out;
-        i += 1;
-    }
-    Some((from, out))
-}
-
-/// Deserialize a type into the out buffer at the offset passed in. Returns a new version of the buffer with the data added.
-const fn deserialize_const_ptr<'a, const N: usize>(
-    from: ConstReadBuffer<'a>,
-    encoding: &Layout,
-    out: (usize, [MaybeUninit<u8>; N]),
-) -> Option<(ConstReadBuffer<'a>, [MaybeUninit<u8>; N])> {
-    match encoding {
-        Layout::Enum(encoding) => deserialize_const_enum(from, encoding, out),
-        Layout::Struct(encoding) => deserialize_const_struct(from, encoding, out),
-        Layout::List(encoding) => deserialize_const_list(from, encoding, out),
-        Layout::Primitive(encoding) => deserialize_const_primitive(from, encoding, out),
-    }
+/// Serialize the default value of a field for [`PlainOldData::with_default_serialized`]. Takes the value instead of
+/// a reference so the derive never drops a value of a generic type in a const.
+#[doc(hidden)]
+pub const fn serialize_default<T: SerializeConst>(value: T) -> ConstWriteBuffer {
+    let serialized = serialize_const(&value, ConstWriteBuffer::new());
+    std::mem::forget(value);
+    serialized
 }
 
 #[macro_export]
@@ -476,14 +435,20 @@ pub const unsafe fn deserialize_const_raw<const N: usize, T: SerializeConst>(
     from: ConstReadBuffer,
 ) -> Option<T> {
     // Create uninitized memory with the size of the type
-    let out = [MaybeUninit::uninit(); N];
-    // Fill in the bytes into the buffer for the type
-    let (_, out) = match deserialize_const_ptr(from, &T::MEMORY_LAYOUT, (0, out)) {
-        Some(data) => data,
-        None => return None,
-    };
-    // Now that the memory is filled in, transmute it into the type
-    Some(unsafe { std::mem::transmute_copy::<[MaybeUninit<u8>; N], T>(&out) })
+    let mut out = MaybeUninit::<T>::uninit();
+    // Fill in the bytes of the type
+    if read_value::<NativeFormat>(
+        from.remaining(),
+        0,
+        &T::MEMORY_LAYOUT,
+        out.as_mut_ptr() as *mut u8,
+    )
+    .is_err()
+    {
+        return None;
+    }
+    // Now that the memory is filled in, the type is initialized
+    Some(unsafe { out.assume_init() })
 }
 
 /// Check if the serialized representation of two items are the same
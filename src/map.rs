@@ -0,0 +1,133 @@
+use std::cmp::Ordering;
+
+use crate::{const_cmp, Layout, PlainOldData, SerializeConst, StructEncoding};
+
+/// A map with a fixed number of entries that is built and searched in const. The entries are sorted by
+/// [`const_cmp`] of their keys when the map is created, and [`ConstMap::get`] finds keys with a binary search.
+///
+/// The map is serialized as its sorted entries. Deserializing doesn't check the order, so check a map that is
+/// deserialized from data you didn't serialize yourself with [`ConstMap::is_sorted`]. Lookups in a map that isn't
+/// sorted may miss keys.
+///
+/// ```rust
+/// use const_serialize::{ConstMap, SerializeConst};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+/// struct AssetId {
+///     package: u16,
+///     index: u16,
+/// }
+///
+/// const SIZES: ConstMap<AssetId, u32, 3> = ConstMap::new([
+///     (AssetId { package: 2, index: 0 }, 300),
+///     (AssetId { package: 1, index: 5 }, 100),
+///     (AssetId { package: 1, index: 2 }, 200),
+/// ]);
+///
+/// const SIZE: Option<&u32> = SIZES.get(&AssetId { package: 1, index: 5 });
+/// assert_eq!(SIZE, Some(&100));
+/// ```
+///
+/// Duplicate keys are a compile time error:
+///
+/// ```compile_fail
+/// use const_serialize::ConstMap;
+///
+/// const MAP: ConstMap<u8, u8, 2> = ConstMap::new([(1, 2), (1, 3)]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ConstMap<K, V, const N: usize> {
+    entries: [(K, V); N],
+}
+
+impl<K: SerializeConst, V: SerializeConst, const N: usize> ConstMap<K, V, N> {
+    /// Create a map from entries in any order. Panics if two entries have the same key.
+    pub const fn new(mut entries: [(K, V); N]) -> Self {
+        // Insertion sort, which only needs to swap entries
+        let mut i = 1;
+        while i < N {
+            let mut j = i;
+            while j > 0 {
+                match const_cmp(&entries[j - 1].0, &entries[j].0) {
+                    Ordering::Greater => entries.swap(j - 1, j),
+                    Ordering::Equal => panic!("duplicate key in ConstMap"),
+                    Ordering::Less => break,
+                }
+                j -= 1;
+            }
+            i += 1;
+        }
+        Self { entries }
+    }
+
+    /// Find the value for a key
+    pub const fn get(&self, key: &K) -> Option<&V> {
+        match self.index_of(key) {
+            Some(index) => Some(&self.entries[index].1),
+            None => None,
+        }
+    }
+
+    /// Check if the map has an entry with the key
+    pub const fn contains_key(&self, key: &K) -> bool {
+        self.index_of(key).is_some()
+    }
+
+    /// The index of the entry with the key in [`ConstMap::entries`]
+    pub const fn index_of(&self, key: &K) -> Option<usize> {
+        let mut low = 0;
+        let mut high = N;
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match const_cmp(&self.entries[middle].0, key) {
+                Ordering::Less => low = middle + 1,
+                Ordering::Greater => high = middle,
+                Ordering::Equal => return Some(middle),
+            }
+        }
+        None
+    }
+
+    /// Check that the keys are sorted and unique, like they are in every map created with [`ConstMap::new`]
+    pub const fn is_sorted(&self) -> bool {
+        let mut i = 1;
+        while i < N {
+            if !const_cmp(&self.entries[i - 1].0, &self.entries[i].0).is_lt() {
+                return false;
+            }
+            i += 1;
+        }
+        true
+    }
+
+    /// The entries sorted by key
+    pub const fn entries(&self) -> &[(K, V); N] {
+        &self.entries
+    }
+
+    /// The number of entries
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    /// Check if the map has no entries
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+}
+
+unsafe impl<K: SerializeConst, V: SerializeConst, const N: usize> SerializeConst
+    for ConstMap<K, V, N>
+{
+    const MEMORY_LAYOUT: Layout = Layout::Struct(
+        StructEncoding::new(
+            std::mem::size_of::<Self>(),
+            &[PlainOldData::new(
+                std::mem::offset_of!(ConstMap<K, V, N>, entries),
+                <[(K, V); N]>::MEMORY_LAYOUT,
+            )
+            .with_name("entries")],
+        )
+        .with_name("ConstMap"),
+    );
+}
@@ -0,0 +1,55 @@
+use crate::{
+    deserialize_with, serialize_with, ConstReadBuffer, ConstWriteBuffer, LengthFormat,
+    PrimitiveFormat, SerializeConst, TagFormat, WireFormat,
+};
+
+/// The [`WireFormat`] of [`serialize_memcomparable`], where the bytes sort like the values
+pub struct MemcomparableFormat;
+
+impl WireFormat for MemcomparableFormat {
+    const PRIMITIVES: PrimitiveFormat = PrimitiveFormat::Ordered;
+    const ENUM_TAGS: TagFormat = TagFormat::OrderedDiscriminant;
+    const LIST_LENGTHS: LengthFormat = LengthFormat::Omitted;
+    const STRUCT_LENGTHS: LengthFormat = LengthFormat::Omitted;
+}
+
+/// Serialize a value so that comparing the bytes of two values with `memcmp` orders them the same way as
+/// [`const_cmp`](crate::const_cmp). This is useful for keys in a sorted key-value store.
+///
+/// Numbers are written big-endian with the sign bit of signed integers flipped, floats are transformed so they
+/// sort like [`f64::total_cmp`], enums write their discriminant big-endian before the variant and arrays are
+/// written item by item so they compare lexicographically. For types that derive `Ord`, the bytes order like
+/// `Ord` as long as enum discriminants increase in the order the variants are declared.
+///
+/// ```rust
+/// use const_serialize::{serialize_memcomparable, ConstWriteBuffer, SerializeConst};
+///
+/// #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, SerializeConst)]
+/// struct Key {
+///     shard: i16,
+///     id: u32,
+/// }
+///
+/// const LOW: ConstWriteBuffer =
+///     serialize_memcomparable(&Key { shard: -1, id: 300 }, ConstWriteBuffer::new());
+/// const HIGH: ConstWriteBuffer =
+///     serialize_memcomparable(&Key { shard: 0, id: 2 }, ConstWriteBuffer::new());
+/// assert_eq!(LOW.as_ref(), [0x7f, 0xff, 0x00, 0x00, 0x01, 0x2c]);
+/// assert!(LOW.as_ref() < HIGH.as_ref());
+/// ```
+#[must_use = "The data is serialized into the returned buffer"]
+pub const fn serialize_memcomparable<T: SerializeConst>(
+    data: &T,
+    to: ConstWriteBuffer,
+) -> ConstWriteBuffer {
+    serialize_with::<MemcomparableFormat, T>(data, to)
+}
+
+/// Deserialize a value written by [`serialize_memcomparable`]. Returns None if the buffer is too short, has an
+/// unknown enum variant or an invalid bool.
+#[must_use = "The data is deserialized from the input buffer"]
+pub const fn deserialize_memcomparable<T: SerializeConst>(
+    from: ConstReadBuffer,
+) -> Option<(ConstReadBuffer, T)> {
+    deserialize_with::<MemcomparableFormat, T>(from)
+}
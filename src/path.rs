@@ -0,0 +1,508 @@
+use std::mem::MaybeUninit;
+
+use crate::format::{check_value, read_value, NativeFormat};
+use crate::{
+    serialize_const, ConstReadBuffer, ConstStr, ConstWriteBuffer, EnumVariant, Layout,
+    SerializeConst, StructEncoding,
+};
+
+/// A segment of a path to a field, written by [`serialized_offset!`](crate::serialized_offset) and
+/// [`deserialize_field!`](crate::deserialize_field)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PathSegment {
+    /// A field of a struct or variant, or a variant of an enum
+    Name(&'static str),
+    /// An item of an array
+    Index(usize),
+}
+
+/// The serialized length of a layout if it is the same for every value. Enums only have a fixed length if
+/// every variant serializes to the same number of bytes.
+pub(crate) const fn fixed_len(layout: &Layout) -> Option<usize> {
+    match layout {
+        Layout::Enum(encoding) => {
+            let mut len = None;
+            let mut i = 0;
+            while i < encoding.variants.len() {
+                let variant_len = match struct_fixed_len(&encoding.variants[i].data) {
+                    Some(variant_len) => variant_len,
+                    None => return None,
+                };
+                match len {
+                    Some(len) if len != variant_len => return None,
+                    _ => len = Some(variant_len),
+                }
+                i += 1;
+            }
+            match len {
+                Some(len) => Some(encoding.discriminant.size + len),
+                None => Some(encoding.discriminant.size),
+            }
+        }
+        Layout::Struct(encoding) => struct_fixed_len(encoding),
+        Layout::List(encoding) => match fixed_len(encoding.item_encoding) {
+            Some(len) => Some(len * encoding.len),
+            None => None,
+        },
+        Layout::Primitive(encoding) => Some(encoding.size),
+    }
+}
+
+const fn struct_fixed_len(encoding: &StructEncoding) -> Option<usize> {
+    let mut len = 0;
+    let mut i = 0;
+    while i < encoding.data.len() {
+        len += match fixed_len(&encoding.data[i].encoding) {
+            Some(len) => len,
+            None => return None,
+        };
+        i += 1;
+    }
+    Some(len)
+}
+
+const fn str_eq(first: &str, second: &str) -> bool {
+    let first = first.as_bytes();
+    let second = second.as_bytes();
+    if first.len() != second.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < first.len() {
+        if first[i] != second[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn path_error(message: &str, name: &str) -> ! {
+    let message = ConstStr::new()
+        .push_str(message)
+        .push_str(" `")
+        .push_str(name)
+        .push('`');
+    panic!("{}", message.as_str())
+}
+
+/// The index of the field with the name in the struct
+const fn find_field(encoding: &StructEncoding, name: &str) -> usize {
+    let mut i = 0;
+    while i < encoding.data.len() {
+        if let Some(field_name) = encoding.data[i].name {
+            if str_eq(field_name, name) {
+                return i;
+            }
+        }
+        i += 1;
+    }
+    path_error("no field named", name)
+}
+
+/// The variant with the name in the enum
+const fn find_variant<'a>(variants: &'a [EnumVariant], name: &str) -> &'a EnumVariant {
+    let mut i = 0;
+    while i < variants.len() {
+        if let Some(variant_name) = variants[i].data.name {
+            if str_eq(variant_name, name) {
+                return &variants[i];
+            }
+        }
+        i += 1;
+    }
+    path_error("no variant named", name)
+}
+
+/// Get the layout of a list item at the index
+const fn list_item(layout: &Layout, index: usize) -> &'static Layout {
+    match layout {
+        Layout::List(encoding) => {
+            if index >= encoding.len {
+                panic!("the index is out of bounds for the array");
+            }
+            encoding.item_encoding
+        }
+        _ => panic!("only arrays can be indexed"),
+    }
+}
+
+/// The offset of a field in the serialized data of a type. Panics if the path doesn't exist or the offset
+/// depends on the data, which happens when an enum with variants of different lengths comes before the field.
+/// Enum variants in the path are assumed to be the variant the data holds.
+pub const fn field_offset(layout: &Layout, path: &[PathSegment]) -> usize {
+    let mut layout = *layout;
+    let mut offset = 0;
+    let mut i = 0;
+    while i < path.len() {
+        match path[i] {
+            PathSegment::Name(name) => match layout {
+                Layout::Struct(encoding) => {
+                    let field = find_field(&encoding, name);
+                    let mut j = 0;
+                    while j < field {
+                        offset += match fixed_len(&encoding.data[j].encoding) {
+                            Some(len) => len,
+                            None => path_error(
+                                "the offset depends on the variant of an enum before",
+                                name,
+                            ),
+                        };
+                        j += 1;
+                    }
+                    layout = encoding.data[field].encoding;
+                }
+                Layout::Enum(encoding) => {
+                    let variant = find_variant(encoding.variants, name);
+                    offset += encoding.discriminant.size;
+                    layout = Layout::Struct(variant.data);
+                }
+                _ => path_error("only structs and enums have a field or variant named", name),
+            },
+            PathSegment::Index(index) => {
+                let item = list_item(&layout, index);
+                offset += match fixed_len(item) {
+                    Some(len) => len * index,
+                    None => panic!("the offset depends on the variant of an enum in the array"),
+                };
+                layout = *item;
+            }
+        }
+        i += 1;
+    }
+    offset
+}
+
+/// An error from [`patch_field!`](crate::patch_field)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchError {
+    /// The data is not a valid value of the type
+    InvalidData,
+    /// An enum in the path holds a different variant than the path names
+    VariantMismatch,
+    /// The new value serializes to a different number of bytes than the old value. This happens when the
+    /// new value holds an enum variant with a different length.
+    LengthChanged { expected: usize, found: usize },
+}
+
+/// Find the range of the serialized field at the path and the layout of the field
+const fn locate(
+    bytes: &[u8],
+    layout: &Layout,
+    path: &[PathSegment],
+) -> Result<(usize, usize, Layout), PatchError> {
+    let mut layout = *layout;
+    let mut offset = 0;
+    let mut i = 0;
+    while i < path.len() {
+        match path[i] {
+            PathSegment::Name(name) => match layout {
+                Layout::Struct(encoding) => {
+                    let field = find_field(&encoding, name);
+                    let mut j = 0;
+                    while j < field {
+                        offset = match skip(bytes, offset, &encoding.data[j].encoding) {
+                            Some(offset) => offset,
+                            None => return Err(PatchError::InvalidData),
+                        };
+                        j += 1;
+                    }
+                    layout = encoding.data[field].encoding;
+                }
+                Layout::Enum(encoding) => {
+                    let variant = find_variant(encoding.variants, name);
+                    let size = encoding.discriminant.size;
+                    if bytes.len() < offset + size {
+                        return Err(PatchError::InvalidData);
+                    }
+                    // Discriminants are always little endian. Only the first 8 bytes fit in the tag.
+                    let mut tag = 0;
+                    let mut j = 0;
+                    while j < size && j < 8 {
+                        tag |= (bytes[offset + j] as u64) << (j * 8);
+                        j += 1;
+                    }
+                    if tag != variant.tag as u64 {
+                        return Err(PatchError::VariantMismatch);
+                    }
+                    offset += size;
+                    layout = Layout::Struct(variant.data);
+                }
+                _ => path_error("only structs and enums have a field or variant named", name),
+            },
+            PathSegment::Index(index) => {
+                let item = list_item(&layout, index);
+                match fixed_len(item) {
+                    // Jump straight to the item if every item has the same length
+                    Some(len) => offset += len * index,
+                    None => {
+                        let mut j = 0;
+                        while j < index {
+                            offset = match skip(bytes, offset, item) {
+                                Some(offset) => offset,
+                                None => return Err(PatchError::InvalidData),
+                            };
+                            j += 1;
+                        }
+                    }
+                }
+                layout = *item;
+            }
+        }
+        i += 1;
+    }
+    match skip(bytes, offset, &layout) {
+        Some(end) => Ok((offset, end, layout)),
+        None => Err(PatchError::InvalidData),
+    }
+}
+
+const fn assert_field_type<T: SerializeConst>(layout: &Layout) {
+    if layout.fingerprint() != T::MEMORY_LAYOUT.fingerprint()
+        || layout.size() != std::mem::size_of::<T>()
+    {
+        panic!("the type doesn't match the layout of the field");
+    }
+}
+
+/// Deserialize the field at the path from the serialized data of the layout. The type of the field is
+/// checked against the layout of T. Returns None if the data is not valid or an enum in the path holds
+/// a different variant than the path names.
+#[must_use = "The data is deserialized from the input buffer"]
+pub const fn deserialize_field_raw<T: SerializeConst>(
+    from: ConstReadBuffer,
+    layout: &Layout,
+    path: &[PathSegment],
+) -> Option<T> {
+    let bytes = from.remaining();
+    let (offset, _, layout) = match locate(bytes, layout, path) {
+        Ok(field) => field,
+        Err(_) => return None,
+    };
+    assert_field_type::<T>(&layout);
+    let mut out = MaybeUninit::<T>::uninit();
+    let _ = read_value::<NativeFormat>(bytes, offset, &layout, out.as_mut_ptr() as *mut u8);
+    // SAFETY: the data was checked by locate and T has the layout the data was read with
+    Some(unsafe { out.assume_init() })
+}
+
+/// Overwrite the field at the path in the serialized data of the layout with a new value. The type of the
+/// value is checked against the layout of the field. Nothing is written if the new value serializes to a
+/// different number of bytes than the old value.
+pub const fn patch_field_raw<T: SerializeConst>(
+    bytes: &mut [u8],
+    layout: &Layout,
+    path: &[PathSegment],
+    value: &T,
+) -> Result<(), PatchError> {
+    let (start, end, layout) = match locate(bytes, layout, path) {
+        Ok(field) => field,
+        Err(error) => return Err(error),
+    };
+    assert_field_type::<T>(&layout);
+    let new = serialize_const(value, ConstWriteBuffer::new());
+    let new = new.as_ref();
+    if new.len() != end - start {
+        return Err(PatchError::LengthChanged {
+            expected: end - start,
+            found: new.len(),
+        });
+    }
+    let mut i = 0;
+    while i < new.len() {
+        bytes[start + i] = new[i];
+        i += 1;
+    }
+    Ok(())
+}
+
+/// The buffers [`patch_field!`](crate::patch_field) can change
+#[doc(hidden)]
+pub struct PatchTarget<B>(pub B);
+
+impl<'a> PatchTarget<&'a mut [u8]> {
+    pub const fn bytes(self) -> &'a mut [u8] {
+        self.0
+    }
+}
+
+impl<'a, const N: usize> PatchTarget<&'a mut [u8; N]> {
+    pub const fn bytes(self) -> &'a mut [u8] {
+        self.0
+    }
+}
+
+impl<'a> PatchTarget<&'a mut ConstWriteBuffer> {
+    pub const fn bytes(self) -> &'a mut [u8] {
+        self.0.as_mut()
+    }
+}
+
+/// The length of the serialized value of the layout at the current position of the buffer. Enum payloads
+/// are measured with the variant the data holds. Returns None if the data is not a valid value of the layout.
+pub const fn serialized_len_at(from: &ConstReadBuffer, layout: &Layout) -> Option<usize> {
+    skip(from.remaining(), 0, layout)
+}
+
+/// Move the buffer past the serialized value of the layout without deserializing it. Returns None if the
+/// data is not a valid value of the layout.
+pub const fn skip_value<'a>(
+    from: ConstReadBuffer<'a>,
+    layout: &Layout,
+) -> Option<ConstReadBuffer<'a>> {
+    match serialized_len_at(&from, layout) {
+        Some(len) => Some(from.advance(len)),
+        None => None,
+    }
+}
+
+const fn skip(bytes: &[u8], offset: usize, layout: &Layout) -> Option<usize> {
+    match check_value::<NativeFormat>(bytes, offset, layout) {
+        Ok(end) => Some(end),
+        Err(_) => None,
+    }
+}
+
+/// Find the offset of a field in the serialized data of a type in const. The path is a list of field names
+/// separated by `.`, array indexes in `[]`, and enum variant names, which are written like fields of the enum.
+/// Anything that isn't a field of the type, or a field after an enum whose variants have different lengths,
+/// is a compile time error.
+///
+/// ```rust
+/// use const_serialize::{serialized_offset, SerializeConst};
+///
+/// #[derive(Clone, Copy, SerializeConst)]
+/// struct Config {
+///     id: u32,
+///     limits: [Limits; 3],
+/// }
+///
+/// #[derive(Clone, Copy, SerializeConst)]
+/// struct Limits {
+///     min: u16,
+///     max: u16,
+/// }
+///
+/// const OFFSET: usize = serialized_offset!(Config, limits[2].max);
+/// assert_eq!(OFFSET, 4 + 2 * 4 + 2);
+/// ```
+#[macro_export]
+macro_rules! serialized_offset {
+    ($type:ty, $($path:tt)+) => {{
+        const __OFFSET: usize = $crate::field_offset(
+            &<$type as $crate::SerializeConst>::MEMORY_LAYOUT,
+            $crate::field_path!($($path)+),
+        );
+        __OFFSET
+    }};
+}
+
+/// Deserialize one field from the serialized data of a type without deserializing the rest of the value.
+/// The path is written like the path in [`serialized_offset!`]. The type of the field is inferred from how
+/// the result is used. This returns None if the data is not valid or an enum variant in the path is not
+/// the variant the data holds.
+///
+/// ```rust
+/// use const_serialize::{deserialize_field, serialize_const, ConstWriteBuffer, SerializeConst};
+///
+/// #[derive(Clone, Copy, SerializeConst)]
+/// struct Config {
+///     id: u32,
+///     mode: Mode,
+/// }
+///
+/// #[derive(Clone, Copy, SerializeConst)]
+/// #[repr(C, u8)]
+/// enum Mode {
+///     Off,
+///     On { level: u16 },
+/// }
+///
+/// const DATA: ConstWriteBuffer = serialize_const(
+///     &Config { id: 1, mode: Mode::On { level: 3 } },
+///     ConstWriteBuffer::new(),
+/// );
+/// const LEVEL: Option<u16> = deserialize_field!(Config, mode.On.level, DATA.read());
+/// assert_eq!(LEVEL, Some(3));
+/// ```
+#[macro_export]
+macro_rules! deserialize_field {
+    ($type:ty, $($rest:tt)+) => {
+        $crate::deserialize_field!(@split $type, [] $($rest)+)
+    };
+    (@split $type:ty, [$($path:tt)+], $buffer:expr) => {
+        $crate::deserialize_field_raw(
+            $buffer,
+            &<$type as $crate::SerializeConst>::MEMORY_LAYOUT,
+            $crate::field_path!($($path)+),
+        )
+    };
+    (@split $type:ty, [$($path:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::deserialize_field!(@split $type, [$($path)* $next] $($rest)*)
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! field_path {
+    (@[$($segments:expr,)*]) => {
+        &[$($segments,)*]
+    };
+    (@[$($segments:expr,)*] . $name:ident $($rest:tt)*) => {
+        $crate::field_path!(@[$($segments,)* $crate::PathSegment::Name(stringify!($name)),] $($rest)*)
+    };
+    (@[$($segments:expr,)*] [$index:expr] $($rest:tt)*) => {
+        $crate::field_path!(@[$($segments,)* $crate::PathSegment::Index($index),] $($rest)*)
+    };
+    ($name:ident $($rest:tt)*) => {
+        $crate::field_path!(@[$crate::PathSegment::Name(stringify!($name)),] $($rest)*)
+    };
+    ([$index:expr] $($rest:tt)*) => {
+        $crate::field_path!(@[$crate::PathSegment::Index($index),] $($rest)*)
+    };
+}
+
+/// Overwrite one field in serialized data in place, without deserializing the rest of the value. The buffer
+/// is a `&mut ConstWriteBuffer`, `&mut [u8]` or `&mut [u8; N]`, and the path is written like the path in
+/// [`serialized_offset!`]. This returns a `Result<(), PatchError>`. The field is only changed if the new
+/// value serializes to the same number of bytes as the old value, so an enum can't change to a variant with
+/// a different length.
+///
+/// ```rust
+/// use const_serialize::{deserialize_const, patch_field, serialize_const, ConstWriteBuffer, SerializeConst};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+/// struct Config {
+///     version: u32,
+///     enabled: bool,
+/// }
+///
+/// const DATA: ConstWriteBuffer = {
+///     let mut data = serialize_const(&Config { version: 1, enabled: false }, ConstWriteBuffer::new());
+///     match patch_field!(Config, enabled, &mut data, true) {
+///         Ok(()) => data,
+///         Err(_) => panic!("failed to patch the config"),
+///     }
+/// };
+/// assert_eq!(
+///     deserialize_const!(Config, DATA.read()),
+///     Some(Config { version: 1, enabled: true })
+/// );
+/// ```
+#[macro_export]
+macro_rules! patch_field {
+    ($type:ty, $($rest:tt)+) => {
+        $crate::patch_field!(@split $type, [] $($rest)+)
+    };
+    (@split $type:ty, [$($path:tt)+], $buffer:expr, $value:expr $(,)?) => {
+        $crate::patch_field_raw(
+            $crate::PatchTarget($buffer).bytes(),
+            &<$type as $crate::SerializeConst>::MEMORY_LAYOUT,
+            $crate::field_path!($($path)+),
+            &$value,
+        )
+    };
+    (@split $type:ty, [$($path:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::patch_field!(@split $type, [$($path)* $next] $($rest)*)
+    };
+}
@@ -0,0 +1,153 @@
+use std::fmt::{Display, Write};
+use std::ops::Range;
+
+use crate::value::{AnnotatedItem, Annotation, Decoder};
+use crate::{DecodeError, DynLayout, Layout, PrimitiveEncoding, PrimitiveKind};
+
+/// The number of bytes shown on each line of a hexdump
+const BYTES_PER_LINE: usize = 8;
+
+/// A hexdump of serialized data annotated with the layout it was decoded with. Each primitive and
+/// enum tag is shown on its own line with the path of the field it belongs to and the decoded value.
+///
+/// If the data doesn't match the layout, the dump marks the offset where decoding failed and shows
+/// the rest of the data unannotated.
+///
+/// ```text
+/// 00000000  11 11 11 11              a     u32 286331153
+/// 00000004  fe                       b     i8 -2
+/// 00000005  01 00                    c[0]  u16 1
+/// 00000007  02 00                    c[1]  u16 2
+/// 00000009  ^^ unknown enum variant 7 at offset 9
+/// 00000009  07 03                    (unmatched)
+/// ```
+#[derive(Debug, Clone)]
+pub struct HexDump<'a> {
+    from: &'a [u8],
+    annotations: Vec<Annotation>,
+    error: Option<DecodeError>,
+    /// The offset after the last byte of the value, or the offset of the error
+    end: usize,
+}
+
+/// Create an annotated hexdump of serialized data with the layout of the type it was serialized from
+pub fn hexdump<'a>(from: &'a [u8], layout: &Layout) -> HexDump<'a> {
+    DynLayout::from(layout).hexdump(from)
+}
+
+impl DynLayout {
+    /// Create an annotated hexdump of serialized data with this layout
+    pub fn hexdump<'a>(&self, from: &'a [u8]) -> HexDump<'a> {
+        let mut decoder = Decoder::new(from, Some(Vec::new()));
+        let result = decoder.decode(self);
+        let end = decoder.offset;
+        let annotations = decoder.into_annotations().unwrap_or_default();
+        match result {
+            Ok(_) => HexDump {
+                from,
+                annotations,
+                error: None,
+                end,
+            },
+            Err(error) => HexDump {
+                from,
+                annotations,
+                end: error.offset,
+                error: Some(error),
+            },
+        }
+    }
+}
+
+impl HexDump<'_> {
+    /// The error that stopped decoding, if the data doesn't match the layout
+    pub fn error(&self) -> Option<&DecodeError> {
+        self.error.as_ref()
+    }
+
+    /// The bytes after the decoded value that are not part of the layout
+    pub fn trailing(&self) -> &[u8] {
+        match self.error {
+            Some(_) => &[],
+            None => &self.from[self.end..],
+        }
+    }
+}
+
+impl Display for HexDump<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let path_width = self
+            .annotations
+            .iter()
+            .map(|annotation| annotation.path.len())
+            .max()
+            .unwrap_or(0);
+
+        for annotation in &self.annotations {
+            let description = match &annotation.item {
+                AnnotatedItem::Primitive(encoding, value) => {
+                    format!("{} {value}", primitive_name(encoding))
+                }
+                AnnotatedItem::Tag {
+                    tag,
+                    variant: Some(variant),
+                } => format!("tag {tag} ({variant})"),
+                AnnotatedItem::Tag { tag, variant: None } => format!("tag {tag}"),
+            };
+            write_lines(
+                f,
+                self.from,
+                annotation.range.clone(),
+                &format!("{:path_width$}  {description}", annotation.path),
+            )?;
+        }
+
+        match &self.error {
+            Some(error) => {
+                writeln!(f, "{:08x}  ^^ {error}", error.offset)?;
+                write_lines(f, self.from, self.end..self.from.len(), "(unmatched)")
+            }
+            None => write_lines(f, self.from, self.end..self.from.len(), "(trailing)"),
+        }
+    }
+}
+
+/// Write a range of bytes with the label on the first line. Ranges longer than [`BYTES_PER_LINE`] are split over multiple lines.
+fn write_lines(
+    f: &mut std::fmt::Formatter<'_>,
+    from: &[u8],
+    range: Range<usize>,
+    label: &str,
+) -> std::fmt::Result {
+    let mut label = Some(label);
+    for start in range.clone().step_by(BYTES_PER_LINE) {
+        let end = (start + BYTES_PER_LINE).min(range.end);
+        let mut hex = String::new();
+        for byte in &from[start..end] {
+            if !hex.is_empty() {
+                hex.push(' ');
+            }
+            write!(hex, "{byte:02x}")?;
+        }
+        match label.take() {
+            Some(label) => writeln!(
+                f,
+                "{start:08x}  {hex:width$}  {label}",
+                width = BYTES_PER_LINE * 3 - 1
+            )?,
+            None => writeln!(f, "{start:08x}  {hex}")?,
+        }
+    }
+    Ok(())
+}
+
+/// The rust name of the primitive type, like `u32` or `f64`
+pub(crate) fn primitive_name(encoding: &PrimitiveEncoding) -> String {
+    let bits = encoding.size * 8;
+    match encoding.kind {
+        PrimitiveKind::Unsigned => format!("u{bits}"),
+        PrimitiveKind::Signed => format!("i{bits}"),
+        PrimitiveKind::Float => format!("f{bits}"),
+        PrimitiveKind::Bool => "bool".to_string(),
+    }
+}
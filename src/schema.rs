@@ -0,0 +1,291 @@
+use crate::format::{write_value, NativeFormat};
+use crate::{
+    ConstWriteBuffer, EnumEncoding, FieldDefault, Layout, PrimitiveEncoding, PrimitiveKind,
+    StructEncoding,
+};
+
+/// The version of the schema format written by [`serialize_layout`]. Version 1 schemas don't start with a version
+/// byte and don't have field ids or defaults. They can still be read.
+pub const SCHEMA_VERSION: u8 = 2;
+
+/// Serialize a layout, including its names, field ids and field defaults, into a buffer. The layout can be read back
+/// at runtime with [`DynLayout::from_schema`](crate::DynLayout::from_schema).
+///
+/// The schema starts with [`SCHEMA_VERSION`]. Every layout starts with a tag byte: `S` for structs, `E` for enums,
+/// `L` for lists and `P` for primitives. Numbers are unsigned LEB128 and names are their length plus one (zero for
+/// no name) followed by the UTF-8 bytes. Each field has its id plus one (zero for no id) and the length of its
+/// serialized default plus one (zero for no default) followed by the default.
+#[must_use = "The layout is serialized into the returned buffer"]
+pub const fn serialize_layout(layout: &Layout, to: ConstWriteBuffer) -> ConstWriteBuffer {
+    write_layout(to.push(SCHEMA_VERSION), layout)
+}
+
+const fn write_layout(to: ConstWriteBuffer, layout: &Layout) -> ConstWriteBuffer {
+    match layout {
+        Layout::Enum(encoding) => write_enum(to.push(b'E'), encoding),
+        Layout::Struct(encoding) => write_struct(to.push(b'S'), encoding),
+        Layout::List(encoding) => {
+            let to = write_number(to.push(b'L'), encoding.len);
+            write_layout(to, encoding.item_encoding)
+        }
+        Layout::Primitive(encoding) => write_primitive(to.push(b'P'), encoding),
+    }
+}
+
+const fn write_struct(to: ConstWriteBuffer, encoding: &StructEncoding) -> ConstWriteBuffer {
+    let mut to = write_number(to, encoding.size);
+    to = write_name(to, encoding.name);
+    to = write_number(to, encoding.data.len());
+    let mut i = 0;
+    while i < encoding.data.len() {
+        let field = &encoding.data[i];
+        to = write_number(to, field.offset);
+        to = write_name(to, field.name);
+        to = match field.id {
+            Some(id) => write_number(to, id as usize + 1),
+            None => to.push(0),
+        };
+        to = match field.default {
+            Some(FieldDefault::Serialized(default)) => write_bytes(to, default),
+            Some(FieldDefault::Memory(default)) => {
+                let value =
+                    write_value::<NativeFormat>(default, ConstWriteBuffer::new(), &field.encoding);
+                write_bytes(to, value.as_ref())
+            }
+            None => to.push(0),
+        };
+        to = write_layout(to, &field.encoding);
+        i += 1;
+    }
+    to
+}
+
+const fn write_enum(to: ConstWriteBuffer, encoding: &EnumEncoding) -> ConstWriteBuffer {
+    let mut to = write_number(to, encoding.size);
+    to = write_name(to, encoding.name);
+    to = write_primitive(to, &encoding.discriminant);
+    to = write_number(to, encoding.variants.len());
+    let mut i = 0;
+    while i < encoding.variants.len() {
+        let variant = &encoding.variants[i];
+        to = write_number(to, variant.tag as usize);
+        to = write_number(to, variant.align);
+        to = write_struct(to, &variant.data);
+        i += 1;
+    }
+    to
+}
+
+const fn write_primitive(to: ConstWriteBuffer, encoding: &PrimitiveEncoding) -> ConstWriteBuffer {
+    let kind = match encoding.kind {
+        PrimitiveKind::Unsigned => 0,
+        PrimitiveKind::Signed => 1,
+        PrimitiveKind::Float => 2,
+        PrimitiveKind::Bool => 3,
+    };
+    write_number(to, encoding.size).push(kind)
+}
+
+const fn write_name(to: ConstWriteBuffer, name: Option<&str>) -> ConstWriteBuffer {
+    match name {
+        Some(name) => write_bytes(to, name.as_bytes()),
+        None => to.push(0),
+    }
+}
+
+/// Write the length of the bytes plus one followed by the bytes
+const fn write_bytes(to: ConstWriteBuffer, bytes: &[u8]) -> ConstWriteBuffer {
+    let mut to = write_number(to, bytes.len() + 1);
+    let mut i = 0;
+    while i < bytes.len() {
+        to = to.push(bytes[i]);
+        i += 1;
+    }
+    to
+}
+
+const fn write_number(mut to: ConstWriteBuffer, mut number: usize) -> ConstWriteBuffer {
+    loop {
+        let byte = (number & 0x7f) as u8;
+        number >>= 7;
+        if number == 0 {
+            return to.push(byte);
+        }
+        to = to.push(byte | 0x80);
+    }
+}
+
+#[cfg(feature = "std")]
+mod read {
+    use super::SCHEMA_VERSION;
+    use crate::{
+        DynEnumEncoding, DynEnumVariant, DynLayout, DynListEncoding, DynPlainOldData,
+        DynStructEncoding, PrimitiveEncoding, PrimitiveKind,
+    };
+
+    impl DynLayout {
+        /// Read a layout written with [`serialize_layout`](crate::serialize_layout), or a version 1 schema without
+        /// a version byte. Returns None if the bytes are not a valid layout or there are bytes left after the layout.
+        /// The layout is checked with [`DynLayout::validate`].
+        pub fn from_schema(bytes: &[u8]) -> Option<DynLayout> {
+            let (version, bytes) = match bytes.split_first()? {
+                (&SCHEMA_VERSION, rest) => (SCHEMA_VERSION, rest),
+                // Version 1 schemas start with the tag of the layout
+                (b'E' | b'S' | b'L' | b'P', _) => (1, bytes),
+                _ => return None,
+            };
+            let mut reader = SchemaReader {
+                bytes,
+                version,
+                depth: 0,
+            };
+            let layout = reader.layout()?;
+            if !reader.bytes.is_empty() {
+                return None;
+            }
+            layout.validate().ok()?;
+            Some(layout)
+        }
+    }
+
+    /// How deeply layouts can be nested in a schema, so a hostile schema can't overflow the stack
+    const MAX_DEPTH: usize = 128;
+
+    struct SchemaReader<'a> {
+        bytes: &'a [u8],
+        version: u8,
+        /// The number of layouts that hold the layout being read
+        depth: usize,
+    }
+
+    impl SchemaReader<'_> {
+        fn byte(&mut self) -> Option<u8> {
+            let (first, rest) = self.bytes.split_first()?;
+            self.bytes = rest;
+            Some(*first)
+        }
+
+        fn number(&mut self) -> Option<usize> {
+            let mut number = 0usize;
+            let mut shift = 0;
+            loop {
+                let byte = self.byte()?;
+                let bits = usize::from(byte & 0x7f).checked_shl(shift)?;
+                number |= bits;
+                if byte & 0x80 == 0 {
+                    return Some(number);
+                }
+                shift += 7;
+            }
+        }
+
+        /// Read bytes written after their length plus one, or None written as zero
+        fn bytes(&mut self) -> Option<Option<&[u8]>> {
+            match self.number()? {
+                0 => Some(None),
+                len => {
+                    let len = len - 1;
+                    if len > self.bytes.len() {
+                        return None;
+                    }
+                    let (bytes, rest) = self.bytes.split_at(len);
+                    self.bytes = rest;
+                    Some(Some(bytes))
+                }
+            }
+        }
+
+        fn name(&mut self) -> Option<Option<String>> {
+            match self.bytes()? {
+                Some(name) => Some(Some(String::from_utf8(name.to_vec()).ok()?)),
+                None => Some(None),
+            }
+        }
+
+        fn field(&mut self) -> Option<DynPlainOldData> {
+            let offset = self.number()?;
+            let name = self.name()?;
+            let (id, default) = if self.version >= 2 {
+                let id = match self.number()? {
+                    0 => None,
+                    id => Some(u32::try_from(id - 1).ok()?),
+                };
+                (id, self.bytes()?.map(<[u8]>::to_vec))
+            } else {
+                (None, None)
+            };
+            let mut field = DynPlainOldData::new(offset, self.layout()?);
+            field.name = name;
+            field.id = id;
+            // The default is checked with the rest of the layout once the sizes of every field are known
+            field.default = default;
+            Some(field)
+        }
+
+        fn layout(&mut self) -> Option<DynLayout> {
+            if self.depth == MAX_DEPTH {
+                return None;
+            }
+            self.depth += 1;
+            let layout = self.layout_inner();
+            self.depth -= 1;
+            layout
+        }
+
+        fn layout_inner(&mut self) -> Option<DynLayout> {
+            match self.byte()? {
+                b'E' => self.enum_encoding().map(DynLayout::Enum),
+                b'S' => self.struct_encoding().map(DynLayout::Struct),
+                b'L' => {
+                    let len = self.number()?;
+                    let item = self.layout()?;
+                    Some(DynLayout::List(DynListEncoding::new(len, item)))
+                }
+                b'P' => self.primitive().map(DynLayout::Primitive),
+                _ => None,
+            }
+        }
+
+        fn struct_encoding(&mut self) -> Option<DynStructEncoding> {
+            let size = self.number()?;
+            let name = self.name()?;
+            let count = self.number()?;
+            let mut fields = Vec::new();
+            for _ in 0..count {
+                fields.push(self.field()?);
+            }
+            let mut encoding = DynStructEncoding::new(size, fields);
+            encoding.name = name;
+            Some(encoding)
+        }
+
+        fn enum_encoding(&mut self) -> Option<DynEnumEncoding> {
+            let size = self.number()?;
+            let name = self.name()?;
+            let discriminant = self.primitive()?;
+            let count = self.number()?;
+            let mut variants = Vec::new();
+            for _ in 0..count {
+                let tag = u32::try_from(self.number()?).ok()?;
+                let align = self.number()?;
+                let data = self.struct_encoding()?;
+                variants.push(DynEnumVariant::new(tag, data, align));
+            }
+            let mut encoding = DynEnumEncoding::new(size, discriminant, variants);
+            encoding.name = name;
+            Some(encoding)
+        }
+
+        fn primitive(&mut self) -> Option<PrimitiveEncoding> {
+            let size = self.number()?;
+            let kind = match self.byte()? {
+                0 => PrimitiveKind::Unsigned,
+                1 => PrimitiveKind::Signed,
+                2 => PrimitiveKind::Float,
+                3 => PrimitiveKind::Bool,
+                _ => return None,
+            };
+            Some(PrimitiveEncoding::new(size).with_kind(kind))
+        }
+    }
+}
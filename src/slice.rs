@@ -0,0 +1,129 @@
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+
+use crate::format::{read_value, NativeFormat};
+use crate::path::fixed_len;
+use crate::{serialized_len_at, ConstReadBuffer, SerializeConst};
+
+/// A cursor over a serialized array that deserializes one item at a time, so a long array never has to be
+/// deserialized into memory all at once. Like [`ConstReadBuffer`], every read returns the moved cursor.
+///
+/// ```rust
+/// use const_serialize::{serialize_const, ConstWriteBuffer, SerializeConst, SerializedSlice};
+///
+/// #[derive(Clone, Copy, SerializeConst)]
+/// struct Record {
+///     id: u16,
+///     value: u32,
+/// }
+///
+/// const DATA: ConstWriteBuffer = serialize_const(
+///     &[Record { id: 1, value: 10 }, Record { id: 2, value: 20 }, Record { id: 3, value: 30 }],
+///     ConstWriteBuffer::new(),
+/// );
+///
+/// const TOTAL: u32 = {
+///     let mut records = SerializedSlice::<Record>::new(DATA.read(), 3);
+///     let mut total = 0;
+///     while let Some((rest, record)) = records.next() {
+///         total += record.value;
+///         records = rest;
+///     }
+///     total
+/// };
+/// assert_eq!(TOTAL, 60);
+/// ```
+pub struct SerializedSlice<'a, T> {
+    from: ConstReadBuffer<'a>,
+    len: usize,
+    item: PhantomData<T>,
+}
+
+impl<T> Clone for SerializedSlice<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for SerializedSlice<'_, T> {}
+
+impl<'a, T: SerializeConst> SerializedSlice<'a, T> {
+    /// Create a cursor over `len` serialized items at the start of the buffer
+    pub const fn new(from: ConstReadBuffer<'a>, len: usize) -> Self {
+        Self {
+            from,
+            len,
+            item: PhantomData,
+        }
+    }
+
+    /// The number of items left
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check if there are no items left
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Deserialize the next item. Returns None if there are no items left or the item is not valid.
+    #[must_use = "The cursor is moved past the item in the returned cursor"]
+    pub const fn next(self) -> Option<(Self, T)> {
+        if self.len == 0 {
+            return None;
+        }
+        let mut out = MaybeUninit::<T>::uninit();
+        let len = match read_value::<NativeFormat>(
+            self.from.remaining(),
+            0,
+            &T::MEMORY_LAYOUT,
+            out.as_mut_ptr() as *mut u8,
+        ) {
+            Ok(len) => len,
+            Err(_) => return None,
+        };
+        let rest = Self {
+            from: self.from.advance(len),
+            len: self.len - 1,
+            item: PhantomData,
+        };
+        // SAFETY: the item was read without errors, so every byte of the layout of T was filled in
+        Some((rest, unsafe { out.assume_init() }))
+    }
+
+    /// Skip `n` items and deserialize the next one. If every item serializes to the same number of bytes, this
+    /// jumps straight to the item without reading the items before it.
+    #[must_use = "The cursor is moved past the item in the returned cursor"]
+    pub const fn nth(self, n: usize) -> Option<(Self, T)> {
+        if n >= self.len {
+            return None;
+        }
+        let mut from = self.from;
+        match fixed_len(&T::MEMORY_LAYOUT) {
+            Some(len) => {
+                if from.remaining().len() < len * n {
+                    return None;
+                }
+                from = from.advance(len * n);
+            }
+            None => {
+                let mut i = 0;
+                while i < n {
+                    let len = match serialized_len_at(&from, &T::MEMORY_LAYOUT) {
+                        Some(len) => len,
+                        None => return None,
+                    };
+                    from = from.advance(len);
+                    i += 1;
+                }
+            }
+        }
+        Self {
+            from,
+            len: self.len - n,
+            item: PhantomData,
+        }
+        .next()
+    }
+}
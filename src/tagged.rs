@@ -0,0 +1,84 @@
+use crate::{
+    deserialize_with, serialize_with, ConstReadBuffer, ConstWriteBuffer, FieldFormat, LengthFormat,
+    PrimitiveFormat, SerializeConst, TagFormat, WireFormat,
+};
+
+/// The [`WireFormat`] of [`serialize_tagged`], where fields are written with their ids
+pub struct TaggedFormat;
+
+impl WireFormat for TaggedFormat {
+    const PRIMITIVES: PrimitiveFormat = PrimitiveFormat::LittleEndian;
+    const ENUM_TAGS: TagFormat = TagFormat::Discriminant;
+    const LIST_LENGTHS: LengthFormat = LengthFormat::Omitted;
+    const STRUCT_LENGTHS: LengthFormat = LengthFormat::Omitted;
+    const STRUCT_FIELDS: FieldFormat = FieldFormat::Tagged;
+}
+
+/// Serialize a value so that data written by older or newer versions of a struct can still be read. Every field
+/// is written as its id, the length of the field and the field. Give fields stable ids with
+/// `#[const_serialize(id = N)]`, and values to use when an older writer didn't know about the field with
+/// `#[const_serialize(default = value)]`.
+///
+/// ```rust
+/// use const_serialize::{deserialize_tagged, serialize_tagged, ConstReadBuffer, ConstWriteBuffer, SerializeConst};
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+/// struct SettingsV1 {
+///     #[const_serialize(id = 1)]
+///     volume: u16,
+/// }
+///
+/// #[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+/// struct SettingsV2 {
+///     #[const_serialize(id = 2, default = 100)]
+///     brightness: u16,
+///     #[const_serialize(id = 1)]
+///     volume: u16,
+/// }
+///
+/// const OLD: ConstWriteBuffer = serialize_tagged(&SettingsV1 { volume: 7 }, ConstWriteBuffer::new());
+/// const NEW: Option<(ConstReadBuffer, SettingsV2)> = deserialize_tagged(OLD.read());
+/// assert_eq!(NEW.unwrap().1, SettingsV2 { brightness: 100, volume: 7 });
+/// ```
+///
+/// Ids and defaults belong on fields. Setting them on a type is a compile time error:
+///
+/// ```compile_fail
+/// use const_serialize::SerializeConst;
+///
+/// #[derive(SerializeConst)]
+/// #[const_serialize(id = 1)]
+/// struct Settings {
+///     volume: u16,
+/// }
+/// ```
+///
+/// And so is setting them on an enum variant instead of its fields:
+///
+/// ```compile_fail
+/// use const_serialize::SerializeConst;
+///
+/// #[derive(SerializeConst)]
+/// #[repr(C, u8)]
+/// enum Mode {
+///     #[const_serialize(default = 3)]
+///     On { level: u16 },
+///     Off,
+/// }
+/// ```
+#[must_use = "The data is serialized into the returned buffer"]
+pub const fn serialize_tagged<T: SerializeConst>(
+    data: &T,
+    to: ConstWriteBuffer,
+) -> ConstWriteBuffer {
+    serialize_with::<TaggedFormat, T>(data, to)
+}
+
+/// Deserialize a value written by [`serialize_tagged`]. Returns None if the buffer is too short, doesn't hold a
+/// valid value or is missing a field that doesn't have a default.
+#[must_use = "The data is deserialized from the input buffer"]
+pub const fn deserialize_tagged<T: SerializeConst>(
+    from: ConstReadBuffer,
+) -> Option<(ConstReadBuffer, T)> {
+    deserialize_with::<TaggedFormat, T>(from)
+}
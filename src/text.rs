@@ -0,0 +1,249 @@
+use crate::{const_str::push_number, ConstStr, ConstWriteBuffer};
+
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode the bytes of a buffer as lowercase hex. The hex must fit in [`ConstStr::CAPACITY`] bytes, so the
+/// buffer can be at most 256 bytes, otherwise this panics.
+pub const fn encode_hex(buffer: &ConstWriteBuffer) -> ConstStr {
+    let bytes = buffer.as_ref();
+    check_encoded_len("hex", bytes.len(), bytes.len() * 2);
+    let mut string = ConstStr::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        string = string.push(HEX_DIGITS[(bytes[i] >> 4) as usize] as char);
+        string = string.push(HEX_DIGITS[(bytes[i] & 0xf) as usize] as char);
+        i += 1;
+    }
+    string
+}
+
+/// Encode the bytes of a buffer as standard base64 with padding. The base64 must fit in [`ConstStr::CAPACITY`]
+/// bytes, so the buffer can be at most 384 bytes, otherwise this panics.
+pub const fn encode_base64(buffer: &ConstWriteBuffer) -> ConstStr {
+    let bytes = buffer.as_ref();
+    check_encoded_len("base64", bytes.len(), bytes.len().div_ceil(3) * 4);
+    let mut string = ConstStr::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let remaining = bytes.len() - i;
+        let first = bytes[i] as u32;
+        let second = if remaining > 1 {
+            bytes[i + 1] as u32
+        } else {
+            0
+        };
+        let third = if remaining > 2 {
+            bytes[i + 2] as u32
+        } else {
+            0
+        };
+        let group = (first << 16) | (second << 8) | third;
+
+        let mut sextet = 0;
+        while sextet < 4 {
+            string = if sextet <= remaining {
+                let index = (group >> (18 - sextet * 6)) & 0x3f;
+                string.push(BASE64_ALPHABET[index as usize] as char)
+            } else {
+                string.push('=')
+            };
+            sextet += 1;
+        }
+        i += 3;
+    }
+    string
+}
+
+const fn check_encoded_len(encoding: &str, len: usize, encoded_len: usize) {
+    if encoded_len > ConstStr::CAPACITY {
+        let mut message = ConstStr::new().push_str("encoding ");
+        message = push_number(message, len);
+        message = message
+            .push_str(" bytes as ")
+            .push_str(encoding)
+            .push_str(" takes ");
+        message = push_number(message, encoded_len);
+        message = message.push_str(" characters, but a ConstStr can hold at most ");
+        message = push_number(message, ConstStr::CAPACITY);
+        panic!("{}", message.as_str());
+    }
+}
+
+/// The index of the first byte at or after `i` that is not ASCII whitespace, or the length if there is none
+const fn skip_whitespace(text: &[u8], mut i: usize) -> usize {
+    while i < text.len() && text[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+/// The number of bytes encoded in a hex string. ASCII whitespace, like a trailing newline, is ignored. Panics
+/// if the string has an odd number of digits.
+pub const fn hex_decoded_len(hex: &str) -> usize {
+    let hex = hex.as_bytes();
+    let mut digits: usize = 0;
+    let mut i = skip_whitespace(hex, 0);
+    while i < hex.len() {
+        digits += 1;
+        i = skip_whitespace(hex, i + 1);
+    }
+    if !digits.is_multiple_of(2) {
+        panic!("hex string has an odd number of digits");
+    }
+    digits / 2
+}
+
+/// Decode a hex string with upper or lower case digits. ASCII whitespace between digits is ignored. Panics if
+/// the string is not valid hex. N must be [`hex_decoded_len`].
+pub const fn decode_hex<const N: usize>(hex: &str) -> [u8; N] {
+    if hex_decoded_len(hex) != N {
+        panic!("the array length must match the decoded length");
+    }
+    let hex = hex.as_bytes();
+    let mut bytes = [0; N];
+    let mut i = 0;
+    let mut written = 0;
+    while written < N {
+        let high = skip_whitespace(hex, i);
+        let low = skip_whitespace(hex, high + 1);
+        bytes[written] = (hex_digit(hex[high]) << 4) | hex_digit(hex[low]);
+        i = low + 1;
+        written += 1;
+    }
+    bytes
+}
+
+const fn hex_digit(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        b'A'..=b'F' => digit - b'A' + 10,
+        _ => panic!("invalid hex digit"),
+    }
+}
+
+/// The number of bytes encoded in a base64 string, with or without padding. ASCII whitespace, like a trailing
+/// newline or line breaks, is ignored. Panics if the length of the string is not valid base64.
+pub const fn base64_decoded_len(base64: &str) -> usize {
+    let bytes = base64.as_bytes();
+    let mut len: usize = 0;
+    let mut padding = 0;
+    let mut i = skip_whitespace(bytes, 0);
+    while i < bytes.len() {
+        if bytes[i] == b'=' {
+            padding += 1;
+        } else {
+            // Anything after the padding is checked when decoding
+            padding = 0;
+        }
+        len += 1;
+        i = skip_whitespace(bytes, i + 1);
+    }
+    // Padding is optional, but only up to a multiple of four characters
+    if len.is_multiple_of(4) && padding > 0 {
+        len -= if padding > 1 { 2 } else { 1 };
+    }
+    match len % 4 {
+        0 => len / 4 * 3,
+        2 => len / 4 * 3 + 1,
+        3 => len / 4 * 3 + 2,
+        _ => panic!("invalid base64 length"),
+    }
+}
+
+/// Decode a standard base64 string, with or without padding. ASCII whitespace between digits is ignored. Panics
+/// if the string is not valid base64. N must be [`base64_decoded_len`].
+pub const fn decode_base64<const N: usize>(base64: &str) -> [u8; N] {
+    if base64_decoded_len(base64) != N {
+        panic!("the array length must match the decoded length");
+    }
+    let base64 = base64.as_bytes();
+    let mut bytes = [0; N];
+    let mut bits: u32 = 0;
+    let mut bit_count = 0;
+    let mut written = 0;
+    let mut i = skip_whitespace(base64, 0);
+    while written < N {
+        bits = (bits << 6) | base64_digit(base64[i]) as u32;
+        bit_count += 6;
+        if bit_count >= 8 {
+            bit_count -= 8;
+            bytes[written] = (bits >> bit_count) as u8;
+            written += 1;
+        }
+        i = skip_whitespace(base64, i + 1);
+    }
+    // The bits of the last digit that are not part of a byte must be zero
+    if bits & ((1 << bit_count) - 1) != 0 {
+        panic!("base64 string has non-zero padding bits");
+    }
+    while i < base64.len() {
+        if base64[i] != b'=' {
+            panic!("invalid base64 padding");
+        }
+        i = skip_whitespace(base64, i + 1);
+    }
+    bytes
+}
+
+const fn base64_digit(digit: u8) -> u8 {
+    match digit {
+        b'A'..=b'Z' => digit - b'A',
+        b'a'..=b'z' => digit - b'a' + 26,
+        b'0'..=b'9' => digit - b'0' + 52,
+        b'+' => 62,
+        b'/' => 63,
+        _ => panic!("invalid base64 digit"),
+    }
+}
+
+/// Decode a hex string into a [`ConstReadBuffer`](crate::ConstReadBuffer) in const. Invalid hex is a compile time error.
+///
+/// ```rust
+/// use const_serialize::{decode_hex, deserialize_const, ConstReadBuffer};
+///
+/// const BUFFER: ConstReadBuffer = decode_hex!("2a07");
+/// const VALUE: Option<[u8; 2]> = deserialize_const!([u8; 2], BUFFER);
+/// assert_eq!(VALUE, Some([42, 7]));
+/// ```
+///
+/// ```compile_fail
+/// use const_serialize::{decode_hex, ConstReadBuffer};
+///
+/// const BUFFER: ConstReadBuffer = decode_hex!("2a0g");
+/// ```
+#[macro_export]
+macro_rules! decode_hex {
+    ($hex:expr) => {{
+        const __LEN: usize = $crate::hex_decoded_len($hex);
+        const __BYTES: [u8; __LEN] = $crate::decode_hex::<__LEN>($hex);
+        $crate::ConstReadBuffer::new(&__BYTES)
+    }};
+}
+
+/// Decode a base64 string into a [`ConstReadBuffer`](crate::ConstReadBuffer) in const. Invalid base64 is a compile time error.
+///
+/// ```rust
+/// use const_serialize::{decode_base64, deserialize_const, ConstReadBuffer};
+///
+/// const BUFFER: ConstReadBuffer = decode_base64!("Kgc=");
+/// const VALUE: Option<[u8; 2]> = deserialize_const!([u8; 2], BUFFER);
+/// assert_eq!(VALUE, Some([42, 7]));
+/// ```
+///
+/// ```compile_fail
+/// use const_serialize::{decode_base64, ConstReadBuffer};
+///
+/// const BUFFER: ConstReadBuffer = decode_base64!("KgAA*A==");
+/// ```
+#[macro_export]
+macro_rules! decode_base64 {
+    ($base64:expr) => {{
+        const __LEN: usize = $crate::base64_decoded_len($base64);
+        const __BYTES: [u8; __LEN] = $crate::decode_base64::<__LEN>($base64);
+        $crate::ConstReadBuffer::new(&__BYTES)
+    }};
+}
@@ -0,0 +1,780 @@
+use std::fmt::Display;
+use std::ops::Range;
+
+use crate::dyn_layout::{read_tag, read_unsigned, write_tag, write_unsigned};
+use crate::format::{ReadError, ReadErrorKind};
+use crate::{
+    DynEnumEncoding, DynLayout, DynStructEncoding, Layout, PrimitiveEncoding, PrimitiveKind,
+};
+
+/// A value decoded from serialized data without the rust type it was serialized from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Struct(StructValue),
+    Enum(EnumValue),
+    List(Vec<Value>),
+    Primitive(Primitive),
+}
+
+/// A decoded struct. Tuples are structs with unnamed fields.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StructValue {
+    pub name: Option<String>,
+    pub fields: Vec<FieldValue>,
+}
+
+/// A decoded field of a struct or enum variant
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldValue {
+    pub name: Option<String>,
+    pub value: Value,
+}
+
+/// A decoded enum with the tag and fields of the variant that was serialized
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnumValue {
+    pub name: Option<String>,
+    pub tag: u32,
+    pub variant: Option<String>,
+    pub fields: Vec<FieldValue>,
+}
+
+/// A decoded primitive. The variant depends on the [`PrimitiveKind`] and size of the primitive in the layout.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Primitive {
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+    Bool(bool),
+    /// A primitive that doesn't fit in any of the other variants. The bytes are in the order they appear in the serialized data.
+    Bytes(Vec<u8>),
+}
+
+/// An error that occurred while decoding a value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    /// The offset in the serialized data where decoding failed
+    pub offset: usize,
+    pub kind: DecodeErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeErrorKind {
+    /// The buffer ended before the value was complete
+    UnexpectedEnd,
+    /// An enum discriminant didn't match any variant
+    UnknownVariant { tag: u64 },
+    /// A bool was neither 0 or 1
+    InvalidBool { value: u8 },
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            DecodeErrorKind::UnexpectedEnd => write!(f, "unexpected end of data")?,
+            DecodeErrorKind::UnknownVariant { tag } => write!(f, "unknown enum variant {tag}")?,
+            DecodeErrorKind::InvalidBool { value } => write!(f, "invalid bool {value:#04x}")?,
+        }
+        write!(f, " at offset {}", self.offset)
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<ReadError> for DecodeError {
+    fn from(error: ReadError) -> Self {
+        let kind = match error.kind {
+            ReadErrorKind::UnexpectedEnd => DecodeErrorKind::UnexpectedEnd,
+            ReadErrorKind::UnknownVariant { tag } => DecodeErrorKind::UnknownVariant { tag },
+            ReadErrorKind::InvalidBool { value } => DecodeErrorKind::InvalidBool { value },
+            // Values are only decoded from the native format, which doesn't have varints, lengths or tagged fields
+            kind => unreachable!("the native format can't fail with {kind:?}"),
+        };
+        DecodeError {
+            offset: error.offset,
+            kind,
+        }
+    }
+}
+
+/// Decode serialized data into a [`Value`] with the layout of the type it was serialized from. Any data after the value is ignored.
+pub fn decode_value(from: &[u8], layout: &Layout) -> Result<Value, DecodeError> {
+    DynLayout::from(layout).decode_value(from)
+}
+
+impl DynLayout {
+    /// Decode serialized data into a [`Value`] with this layout. Any data after the value is ignored.
+    pub fn decode_value(&self, from: &[u8]) -> Result<Value, DecodeError> {
+        Decoder::new(from, None).decode(self)
+    }
+}
+
+/// A range of serialized bytes and the primitive or enum tag that was decoded from it
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Annotation {
+    pub(crate) range: Range<usize>,
+    pub(crate) path: String,
+    pub(crate) item: AnnotatedItem,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum AnnotatedItem {
+    Primitive(PrimitiveEncoding, Primitive),
+    Tag { tag: u32, variant: Option<String> },
+}
+
+pub(crate) struct Decoder<'a> {
+    from: &'a [u8],
+    pub(crate) offset: usize,
+    path: String,
+    /// If this is set, every primitive and enum tag that is decoded is recorded here
+    annotations: Option<Vec<Annotation>>,
+}
+
+impl<'a> Decoder<'a> {
+    pub(crate) fn new(from: &'a [u8], annotations: Option<Vec<Annotation>>) -> Self {
+        Self {
+            from,
+            offset: 0,
+            path: String::new(),
+            annotations,
+        }
+    }
+
+    pub(crate) fn into_annotations(self) -> Option<Vec<Annotation>> {
+        self.annotations
+    }
+
+    fn error(&self, kind: DecodeErrorKind) -> DecodeError {
+        DecodeError {
+            offset: self.offset,
+            kind,
+        }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let Some(bytes) = self.from[self.offset..].get(..len) else {
+            return Err(self.error(DecodeErrorKind::UnexpectedEnd));
+        };
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn annotate(&mut self, start: usize, item: impl FnOnce() -> AnnotatedItem) {
+        if let Some(annotations) = &mut self.annotations {
+            annotations.push(Annotation {
+                range: start..self.offset,
+                path: self.path.clone(),
+                item: item(),
+            });
+        }
+    }
+
+    /// Run the closure with a segment added to the path
+    fn with_segment<R>(&mut self, segment: &str, f: impl FnOnce(&mut Self) -> R) -> R {
+        let len = self.path.len();
+        push_segment(&mut self.path, segment);
+        let result = f(self);
+        self.path.truncate(len);
+        result
+    }
+
+    pub(crate) fn decode(&mut self, layout: &DynLayout) -> Result<Value, DecodeError> {
+        match layout {
+            DynLayout::Enum(encoding) => self.decode_enum(encoding).map(Value::Enum),
+            DynLayout::Struct(encoding) => Ok(Value::Struct(StructValue {
+                name: encoding.name.clone(),
+                fields: self.decode_fields(encoding)?,
+            })),
+            DynLayout::List(encoding) => (0..encoding.len)
+                .map(|i| {
+                    self.with_segment(&format!("[{i}]"), |decoder| {
+                        decoder.decode(&encoding.item_encoding)
+                    })
+                })
+                .collect::<Result<_, _>>()
+                .map(Value::List),
+            DynLayout::Primitive(encoding) => self.decode_primitive(encoding).map(Value::Primitive),
+        }
+    }
+
+    fn decode_fields(
+        &mut self,
+        encoding: &DynStructEncoding,
+    ) -> Result<Vec<FieldValue>, DecodeError> {
+        encoding
+            .data
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let segment = match &field.name {
+                    Some(name) => name.clone(),
+                    None => i.to_string(),
+                };
+                Ok(FieldValue {
+                    name: field.name.clone(),
+                    value: self
+                        .with_segment(&segment, |decoder| decoder.decode(&field.encoding))?,
+                })
+            })
+            .collect()
+    }
+
+    fn decode_enum(&mut self, encoding: &DynEnumEncoding) -> Result<EnumValue, DecodeError> {
+        let start = self.offset;
+        let tag = read_tag(self.take(encoding.discriminant.size)?);
+        let Some(variant) = encoding
+            .variants
+            .iter()
+            .find(|variant| u64::from(variant.tag) == tag)
+        else {
+            self.offset = start;
+            return Err(self.error(DecodeErrorKind::UnknownVariant { tag }));
+        };
+        let tag = variant.tag;
+        self.annotate(start, || AnnotatedItem::Tag {
+            tag,
+            variant: variant.data.name.clone(),
+        });
+        let segment = match &variant.data.name {
+            Some(name) => name.clone(),
+            None => tag.to_string(),
+        };
+        Ok(EnumValue {
+            name: encoding.name.clone(),
+            tag,
+            variant: variant.data.name.clone(),
+            fields: self.with_segment(&segment, |decoder| decoder.decode_fields(&variant.data))?,
+        })
+    }
+
+    fn decode_primitive(&mut self, encoding: &PrimitiveEncoding) -> Result<Primitive, DecodeError> {
+        let start = self.offset;
+        let bytes = self.take(encoding.size)?;
+        let primitive = match (encoding.kind, encoding.size) {
+            (PrimitiveKind::Bool, 1) => match bytes[0] {
+                0 => Primitive::Bool(false),
+                1 => Primitive::Bool(true),
+                value => {
+                    self.offset = start;
+                    return Err(self.error(DecodeErrorKind::InvalidBool { value }));
+                }
+            },
+            (PrimitiveKind::Float, 4) => {
+                Primitive::Float(f32::from_bits(read_unsigned(bytes) as u32) as f64)
+            }
+            (PrimitiveKind::Float, 8) => Primitive::Float(f64::from_bits(read_unsigned(bytes))),
+            (PrimitiveKind::Unsigned, 1..=8) => Primitive::Unsigned(read_unsigned(bytes)),
+            (PrimitiveKind::Signed, 1..=8) => {
+                // Sign extend the number to 64 bits
+                let unused_bits = 64 - bytes.len() * 8;
+                Primitive::Signed(((read_unsigned(bytes) << unused_bits) as i64) >> unused_bits)
+            }
+            _ => Primitive::Bytes(bytes.to_vec()),
+        };
+        self.annotate(start, || {
+            AnnotatedItem::Primitive(*encoding, primitive.clone())
+        });
+        Ok(primitive)
+    }
+}
+
+/// Add a field name, variant name or `[index]` to a path like `d.A.one` or `c[1]`
+fn push_segment(path: &mut String, segment: &str) {
+    if !path.is_empty() && !segment.starts_with('[') {
+        path.push('.');
+    }
+    path.push_str(segment);
+}
+
+impl Display for Primitive {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Primitive::Unsigned(value) => write!(f, "{value}"),
+            Primitive::Signed(value) => write!(f, "{value}"),
+            Primitive::Float(value) => write!(f, "{value:?}"),
+            Primitive::Bool(value) => write!(f, "{value}"),
+            Primitive::Bytes(bytes) => {
+                for byte in bytes {
+                    write!(f, "{byte:02x}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// An error that occurred while encoding a value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EncodeError {
+    /// The path to the value that failed to encode, like `d.A.one` or `c[1]`
+    pub path: String,
+    pub kind: EncodeErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EncodeErrorKind {
+    /// The value is a different kind of value than the layout expects
+    TypeMismatch {
+        expected: &'static str,
+        found: &'static str,
+    },
+    /// An enum variant name doesn't exist in the layout
+    UnknownVariant { variant: String },
+    /// An enum tag doesn't match any variant in the layout
+    UnknownTag { tag: u32 },
+    /// A list has a different length than the layout
+    ListLength { expected: usize, found: usize },
+    /// A struct or variant has a different number of fields than the layout
+    FieldCount { expected: usize, found: usize },
+    /// A struct or variant is missing a field from the layout
+    MissingField { name: String },
+    /// A number doesn't fit in the width of the primitive in the layout, or would lose precision when it is converted
+    OutOfRange { size: usize },
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            EncodeErrorKind::TypeMismatch { expected, found } => {
+                write!(f, "expected {expected}, found {found}")?
+            }
+            EncodeErrorKind::UnknownVariant { variant } => {
+                write!(f, "unknown enum variant `{variant}`")?
+            }
+            EncodeErrorKind::UnknownTag { tag } => write!(f, "unknown enum tag {tag}")?,
+            EncodeErrorKind::ListLength { expected, found } => {
+                write!(f, "expected a list of length {expected}, found {found}")?
+            }
+            EncodeErrorKind::FieldCount { expected, found } => {
+                write!(f, "expected {expected} fields, found {found}")?
+            }
+            EncodeErrorKind::MissingField { name } => write!(f, "missing field `{name}`")?,
+            EncodeErrorKind::OutOfRange { size } => {
+                write!(f, "value does not fit in {size} bytes")?
+            }
+        }
+        if !self.path.is_empty() {
+            write!(f, " at `{}`", self.path)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+/// Encode a [`Value`] into the serialized data for the type with the layout passed in. The encoded data can be deserialized with [`crate::deserialize_const!`].
+///
+/// Numbers may be encoded into any primitive they fit in, and enums can be found by the variant name or tag.
+pub fn encode_value(value: &Value, layout: &Layout) -> Result<Vec<u8>, EncodeError> {
+    DynLayout::from(layout).encode_value(value)
+}
+
+impl DynLayout {
+    /// Encode a [`Value`] into the serialized data for the type with this layout
+    pub fn encode_value(&self, value: &Value) -> Result<Vec<u8>, EncodeError> {
+        let mut encoder = Encoder {
+            to: Vec::new(),
+            path: String::new(),
+        };
+        encoder.encode(value, self)?;
+        Ok(encoder.to)
+    }
+}
+
+impl Value {
+    /// A short description of the kind of value for error messages
+    fn kind_name(&self) -> &'static str {
+        match self {
+            Value::Struct(_) => "struct",
+            Value::Enum(_) => "enum",
+            Value::List(_) => "list",
+            Value::Primitive(Primitive::Bool(_)) => "bool",
+            Value::Primitive(Primitive::Float(_)) => "float",
+            Value::Primitive(Primitive::Bytes(_)) => "bytes",
+            Value::Primitive(_) => "integer",
+        }
+    }
+}
+
+struct Encoder {
+    to: Vec<u8>,
+    path: String,
+}
+
+impl Encoder {
+    fn error(&self, kind: EncodeErrorKind) -> EncodeError {
+        EncodeError {
+            path: self.path.clone(),
+            kind,
+        }
+    }
+
+    fn mismatch(&self, expected: &'static str, found: &Value) -> EncodeError {
+        self.error(EncodeErrorKind::TypeMismatch {
+            expected,
+            found: found.kind_name(),
+        })
+    }
+
+    /// Run the closure with a segment added to the path
+    fn with_segment<R>(&mut self, segment: &str, f: impl FnOnce(&mut Self) -> R) -> R {
+        let len = self.path.len();
+        push_segment(&mut self.path, segment);
+        let result = f(self);
+        self.path.truncate(len);
+        result
+    }
+
+    fn encode(&mut self, value: &Value, layout: &DynLayout) -> Result<(), EncodeError> {
+        match layout {
+            DynLayout::Enum(encoding) => self.encode_enum(value, encoding),
+            DynLayout::Struct(encoding) => match value {
+                Value::Struct(value) => self.encode_fields(&value.fields, encoding),
+                // Tuples may be written as lists of their fields
+                Value::List(values) => self.encode_positional(values, encoding),
+                _ => Err(self.mismatch("struct", value)),
+            },
+            DynLayout::List(encoding) => {
+                let Value::List(values) = value else {
+                    return Err(self.mismatch("list", value));
+                };
+                if values.len() != encoding.len {
+                    return Err(self.error(EncodeErrorKind::ListLength {
+                        expected: encoding.len,
+                        found: values.len(),
+                    }));
+                }
+                for (i, value) in values.iter().enumerate() {
+                    self.with_segment(&format!("[{i}]"), |encoder| {
+                        encoder.encode(value, &encoding.item_encoding)
+                    })?;
+                }
+                Ok(())
+            }
+            DynLayout::Primitive(encoding) => self.encode_primitive(value, encoding),
+        }
+    }
+
+    fn encode_fields(
+        &mut self,
+        fields: &[FieldValue],
+        encoding: &DynStructEncoding,
+    ) -> Result<(), EncodeError> {
+        if fields.len() != encoding.data.len() {
+            return Err(self.error(EncodeErrorKind::FieldCount {
+                expected: encoding.data.len(),
+                found: fields.len(),
+            }));
+        }
+        let named = fields.iter().all(|field| field.name.is_some())
+            && encoding.data.iter().all(|field| field.name.is_some());
+        if !named {
+            let values: Vec<_> = fields.iter().map(|field| field.value.clone()).collect();
+            return self.encode_positional(&values, encoding);
+        }
+        // Named fields may be in any order, but they are always written in the order of the layout
+        for field in &encoding.data {
+            let name = field.name.as_deref().unwrap();
+            let Some(value) = fields
+                .iter()
+                .find(|value| value.name.as_deref() == Some(name))
+            else {
+                return Err(self.error(EncodeErrorKind::MissingField {
+                    name: name.to_string(),
+                }));
+            };
+            self.with_segment(name, |encoder| {
+                encoder.encode(&value.value, &field.encoding)
+            })?;
+        }
+        Ok(())
+    }
+
+    fn encode_positional(
+        &mut self,
+        values: &[Value],
+        encoding: &DynStructEncoding,
+    ) -> Result<(), EncodeError> {
+        if values.len() != encoding.data.len() {
+            return Err(self.error(EncodeErrorKind::FieldCount {
+                expected: encoding.data.len(),
+                found: values.len(),
+            }));
+        }
+        for (i, (value, field)) in values.iter().zip(&encoding.data).enumerate() {
+            let segment = match &field.name {
+                Some(name) => name.clone(),
+                None => i.to_string(),
+            };
+            self.with_segment(&segment, |encoder| encoder.encode(value, &field.encoding))?;
+        }
+        Ok(())
+    }
+
+    fn encode_enum(
+        &mut self,
+        value: &Value,
+        encoding: &DynEnumEncoding,
+    ) -> Result<(), EncodeError> {
+        let find_tag = |encoder: &Self, tag: u32| {
+            encoding
+                .variants
+                .iter()
+                .find(|variant| variant.tag == tag)
+                .ok_or_else(|| encoder.error(EncodeErrorKind::UnknownTag { tag }))
+        };
+        let find_name = |encoder: &Self, name: &str| {
+            let variant = encoding
+                .variants
+                .iter()
+                .find(|variant| variant.data.name.as_deref() == Some(name));
+            match variant {
+                Some(variant) => Ok(variant),
+                // Variants without names are written as their tag
+                None => match name.parse() {
+                    Ok(tag) => find_tag(encoder, tag),
+                    Err(_) => Err(encoder.error(EncodeErrorKind::UnknownVariant {
+                        variant: name.to_string(),
+                    })),
+                },
+            }
+        };
+
+        let (variant, fields) = match value {
+            Value::Enum(value) => {
+                let variant = match &value.variant {
+                    Some(name) => find_name(self, name)?,
+                    None => find_tag(self, value.tag)?,
+                };
+                (variant, value.fields.clone())
+            }
+            // An externally tagged enum like `{ "A": { "one": 1 } }`
+            Value::Struct(value) if value.fields.len() == 1 && value.fields[0].name.is_some() => {
+                let field = &value.fields[0];
+                let variant = find_name(self, field.name.as_deref().unwrap())?;
+                let fields = match &field.value {
+                    Value::Struct(value) => value.fields.clone(),
+                    Value::List(values) => values
+                        .iter()
+                        .map(|value| FieldValue {
+                            name: None,
+                            value: value.clone(),
+                        })
+                        .collect(),
+                    value => return Err(self.mismatch("struct", value)),
+                };
+                (variant, fields)
+            }
+            _ => return Err(self.mismatch("enum", value)),
+        };
+
+        write_tag(&mut self.to, variant.tag, encoding.discriminant.size);
+        let segment = match &variant.data.name {
+            Some(name) => name.clone(),
+            None => variant.tag.to_string(),
+        };
+        self.with_segment(&segment, |encoder| {
+            encoder.encode_fields(&fields, &variant.data)
+        })
+    }
+
+    fn encode_primitive(
+        &mut self,
+        value: &Value,
+        encoding: &PrimitiveEncoding,
+    ) -> Result<(), EncodeError> {
+        let size = encoding.size;
+        let out_of_range = |encoder: &Self| encoder.error(EncodeErrorKind::OutOfRange { size });
+        let bits = match (encoding.kind, size, value) {
+            (PrimitiveKind::Bool, 1, Value::Primitive(Primitive::Bool(value))) => *value as u64,
+            (PrimitiveKind::Bool, 1, value) => return Err(self.mismatch("bool", value)),
+            (PrimitiveKind::Float, 4 | 8, Value::Primitive(primitive)) => {
+                let (value, exact) = match primitive {
+                    Primitive::Float(value) => (*value, true),
+                    Primitive::Unsigned(value) => {
+                        (*value as f64, *value as f64 as i128 == *value as i128)
+                    }
+                    Primitive::Signed(value) => {
+                        (*value as f64, *value as f64 as i128 == *value as i128)
+                    }
+                    _ => return Err(self.mismatch("float", value)),
+                };
+                // Numbers that would be rounded are rejected instead of silently changing the value
+                match size {
+                    4 if exact && (value as f32 as f64 == value || value.is_nan()) => {
+                        (value as f32).to_bits() as u64
+                    }
+                    8 if exact => value.to_bits(),
+                    _ => return Err(out_of_range(self)),
+                }
+            }
+            (PrimitiveKind::Float, 4 | 8, value) => return Err(self.mismatch("float", value)),
+            (
+                PrimitiveKind::Unsigned | PrimitiveKind::Signed,
+                1..=8,
+                Value::Primitive(primitive),
+            ) => {
+                let bits = 8 * size as u32;
+                let (min, max) = match encoding.kind {
+                    PrimitiveKind::Signed => (-(1i128 << (bits - 1)), (1i128 << (bits - 1)) - 1),
+                    _ => (0, (1i128 << bits) - 1),
+                };
+                let number = match primitive {
+                    Primitive::Unsigned(value) => *value as i128,
+                    Primitive::Signed(value) => *value as i128,
+                    // Floats like `3.0` are accepted as long as they are whole numbers
+                    Primitive::Float(value) if value.fract() == 0.0 => *value as i128,
+                    Primitive::Float(_) => return Err(out_of_range(self)),
+                    _ => return Err(self.mismatch("integer", value)),
+                };
+                if number < min || number > max {
+                    return Err(out_of_range(self));
+                }
+                number as u64
+            }
+            (PrimitiveKind::Unsigned | PrimitiveKind::Signed, 1..=8, value) => {
+                return Err(self.mismatch("integer", value))
+            }
+            // Any other primitive is written as raw bytes
+            (_, _, value) => {
+                let bytes: Vec<u8> = match value {
+                    Value::Primitive(Primitive::Bytes(bytes)) => bytes.clone(),
+                    Value::List(values) => values
+                        .iter()
+                        .map(|value| match value {
+                            Value::Primitive(Primitive::Unsigned(byte)) => {
+                                u8::try_from(*byte).map_err(|_| out_of_range(self))
+                            }
+                            value => Err(self.mismatch("byte", value)),
+                        })
+                        .collect::<Result<_, _>>()?,
+                    value => return Err(self.mismatch("bytes", value)),
+                };
+                if bytes.len() != size {
+                    return Err(self.error(EncodeErrorKind::ListLength {
+                        expected: size,
+                        found: bytes.len(),
+                    }));
+                }
+                self.to.extend(bytes);
+                return Ok(());
+            }
+        };
+        write_unsigned(&mut self.to, bits, size);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use super::*;
+    use serde::de::{MapAccess, SeqAccess, Visitor};
+    use serde::ser::{SerializeMap, SerializeSeq};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Fields serialize as a map if every field has a name, otherwise as a sequence
+    struct Fields<'a>(&'a [FieldValue]);
+
+    impl Serialize for Fields<'_> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            if self.0.iter().all(|field| field.name.is_some()) {
+                let mut map = serializer.serialize_map(Some(self.0.len()))?;
+                for field in self.0 {
+                    map.serialize_entry(field.name.as_ref().unwrap(), &field.value)?;
+                }
+                map.end()
+            } else {
+                let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+                for field in self.0 {
+                    seq.serialize_element(&field.value)?;
+                }
+                seq.end()
+            }
+        }
+    }
+
+    impl Serialize for Value {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                Value::Struct(value) => Fields(&value.fields).serialize(serializer),
+                Value::Enum(value) => {
+                    // Enums are externally tagged with the variant name, or the tag if the variant has no name
+                    let mut map = serializer.serialize_map(Some(1))?;
+                    match &value.variant {
+                        Some(variant) => map.serialize_key(variant)?,
+                        None => map.serialize_key(&value.tag.to_string())?,
+                    }
+                    map.serialize_value(&Fields(&value.fields))?;
+                    map.end()
+                }
+                Value::List(values) => values.serialize(serializer),
+                Value::Primitive(value) => value.serialize(serializer),
+            }
+        }
+    }
+
+    impl Serialize for Primitive {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            match self {
+                Primitive::Unsigned(value) => serializer.serialize_u64(*value),
+                Primitive::Signed(value) => serializer.serialize_i64(*value),
+                Primitive::Float(value) => serializer.serialize_f64(*value),
+                Primitive::Bool(value) => serializer.serialize_bool(*value),
+                Primitive::Bytes(value) => value.serialize(serializer),
+            }
+        }
+    }
+
+    /// Values are deserialized without a layout: maps become structs with named fields, sequences become lists and numbers become the primitive with the same sign. [`encode_value`] converts them to the type the layout expects.
+    impl<'de> Deserialize<'de> for Value {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(ValueVisitor)
+        }
+    }
+
+    struct ValueVisitor;
+
+    impl<'de> Visitor<'de> for ValueVisitor {
+        type Value = Value;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("a number, bool, sequence or map")
+        }
+
+        fn visit_bool<E>(self, value: bool) -> Result<Value, E> {
+            Ok(Value::Primitive(Primitive::Bool(value)))
+        }
+
+        fn visit_u64<E>(self, value: u64) -> Result<Value, E> {
+            Ok(Value::Primitive(Primitive::Unsigned(value)))
+        }
+
+        fn visit_i64<E>(self, value: i64) -> Result<Value, E> {
+            Ok(Value::Primitive(Primitive::Signed(value)))
+        }
+
+        fn visit_f64<E>(self, value: f64) -> Result<Value, E> {
+            Ok(Value::Primitive(Primitive::Float(value)))
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+            let mut values = Vec::new();
+            while let Some(value) = seq.next_element()? {
+                values.push(value);
+            }
+            Ok(Value::List(values))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+            let mut fields = Vec::new();
+            while let Some((name, value)) = map.next_entry::<String, Value>()? {
+                fields.push(FieldValue {
+                    name: Some(name),
+                    value,
+                });
+            }
+            Ok(Value::Struct(StructValue { name: None, fields }))
+        }
+    }
+}
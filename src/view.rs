@@ -0,0 +1,132 @@
+use crate::format::{check_value, NativeFormat};
+use crate::{Layout, SerializeConst, StructEncoding};
+
+/// A type with a zero-copy view over its serialized data. Implemented by `#[derive(SerializeConst)]` for types
+/// marked with `#[const_serialize(view)]`.
+///
+/// Structs get a `{Name}View<'a>` struct with a const accessor for every field. Enums get a `{Name}View` enum
+/// with the same variants, where every variant with fields holds a `{Name}{Variant}View<'a>` struct with an
+/// accessor for every field of the variant. The offsets of the fields are found once when a view is created.
+/// Fields marked with `#[const_serialize(view)]` return the view of the field type instead of copying the field
+/// out of the data.
+///
+/// ```rust
+/// use const_serialize::{serialize_const, ConstWriteBuffer, SerializeConst};
+///
+/// #[derive(Clone, Copy, SerializeConst)]
+/// #[const_serialize(view)]
+/// struct Config {
+///     port: u16,
+///     #[const_serialize(view)]
+///     mode: Mode,
+/// }
+///
+/// #[derive(Clone, Copy, SerializeConst)]
+/// #[repr(C, u8)]
+/// #[const_serialize(view)]
+/// enum Mode {
+///     Off,
+///     On { level: u16 },
+/// }
+///
+/// const DATA: ConstWriteBuffer = serialize_const(
+///     &Config { port: 8080, mode: Mode::On { level: 3 } },
+///     ConstWriteBuffer::new(),
+/// );
+/// const LEVEL: u16 = match ConfigView::new(DATA.as_ref()) {
+///     Some(config) => match config.mode() {
+///         ModeView::On(on) => on.level(),
+///         ModeView::Off => 0,
+///     },
+///     None => panic!("invalid config"),
+/// };
+/// assert_eq!(LEVEL, 3);
+/// ```
+pub trait SerializeConstView<'a>: SerializeConst {
+    /// The view over the serialized data of the type
+    type View;
+}
+
+/// The length of the serialized value of the layout at the start of the data, or None if the data is not a
+/// valid value of the layout.
+#[doc(hidden)]
+pub const fn view_len(bytes: &[u8], layout: &Layout) -> Option<usize> {
+    match check_value::<NativeFormat>(bytes, 0, layout) {
+        Ok(len) => Some(len),
+        Err(_) => None,
+    }
+}
+
+/// The offsets of the fields of the serialized struct, followed by the offset after the last field. N must be one
+/// more than the number of fields.
+#[doc(hidden)]
+pub const fn view_offsets<const N: usize>(bytes: &[u8], layout: &Layout) -> [usize; N] {
+    match layout {
+        Layout::Struct(encoding) => field_offsets(bytes, 0, encoding),
+        _ => panic!("view_offsets expects a struct layout"),
+    }
+}
+
+/// The discriminant of the serialized enum
+#[doc(hidden)]
+pub const fn view_tag(bytes: &[u8], layout: &Layout) -> u32 {
+    let Layout::Enum(encoding) = layout else {
+        panic!("view_tag expects an enum layout");
+    };
+    // Discriminants are always little endian. The data is already checked, so any bytes after the first four are zero.
+    let mut tag = 0;
+    let mut i = 0;
+    while i < encoding.discriminant.size && i < 4 {
+        tag |= (bytes[i] as u32) << (i * 8);
+        i += 1;
+    }
+    tag
+}
+
+/// The offsets of the fields of the variant the serialized enum holds, followed by the offset after the last
+/// field. N must be one more than the number of fields of the variant.
+#[doc(hidden)]
+pub const fn view_variant_offsets<const N: usize>(bytes: &[u8], layout: &Layout) -> [usize; N] {
+    let Layout::Enum(encoding) = layout else {
+        panic!("view_variant_offsets expects an enum layout");
+    };
+    let tag = view_tag(bytes, layout);
+    let mut i = 0;
+    while i < encoding.variants.len() {
+        let variant = &encoding.variants[i];
+        if variant.tag == tag {
+            return field_offsets(bytes, encoding.discriminant.size, &variant.data);
+        }
+        i += 1;
+    }
+    panic!("the enum data holds an unknown variant")
+}
+
+/// The serialized data of a field from the offsets of the fields
+#[doc(hidden)]
+pub const fn view_field<'a>(bytes: &'a [u8], offsets: &[usize], index: usize) -> &'a [u8] {
+    let (field, _) = bytes.split_at(offsets[index + 1]);
+    field.split_at(offsets[index]).1
+}
+
+const fn field_offsets<const N: usize>(
+    bytes: &[u8],
+    mut offset: usize,
+    encoding: &StructEncoding,
+) -> [usize; N] {
+    if N != encoding.data.len() + 1 {
+        panic!("the view needs one more offset than the number of fields");
+    }
+    let mut offsets = [0; N];
+    offsets[0] = offset;
+    let mut i = 0;
+    while i < encoding.data.len() {
+        offset = match check_value::<NativeFormat>(bytes, offset, &encoding.data[i].encoding) {
+            Ok(end) => end,
+            Err(_) => panic!("the view data is not valid"),
+        };
+        offsets[i + 1] = offset;
+        i += 1;
+    }
+    offsets
+}
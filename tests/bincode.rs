@@ -0,0 +1,56 @@
+use const_serialize::{
+    deserialize_bincode, serialize_bincode, ConstReadBuffer, ConstWriteBuffer, SerializeConst,
+};
+use proptest::prelude::*;
+
+mod common;
+use common::{record, Kind, Record};
+
+fn encode<T: SerializeConst>(value: &T) -> Vec<u8> {
+    serialize_bincode(value, ConstWriteBuffer::new())
+        .as_ref()
+        .to_vec()
+}
+
+fn decode<T: SerializeConst>(bytes: &[u8]) -> Option<T> {
+    let (rest, value) = deserialize_bincode::<T>(ConstReadBuffer::new(bytes))?;
+    assert!(rest.get().is_none());
+    Some(value)
+}
+
+proptest! {
+    #[test]
+    fn test_matches_bincode(value in record().prop_filter("NaN is not equal to itself", |value| !value.has_nan())) {
+        let bytes = encode(&value);
+        prop_assert_eq!(&bytes, &bincode::serialize(&value).unwrap());
+        prop_assert_eq!(
+            &bytes,
+            &bincode2::serde::encode_to_vec(value, bincode2::config::legacy()).unwrap()
+        );
+        prop_assert_eq!(decode::<Record>(&bytes), Some(value));
+        prop_assert_eq!(bincode::deserialize::<Record>(&bytes).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_in_const() {
+    const KIND: Kind = Kind::Far {
+        value: -2,
+        flag: true,
+    };
+    const BUF: ConstWriteBuffer = serialize_bincode(&KIND, ConstWriteBuffer::new());
+    const DECODED: Option<(ConstReadBuffer, Kind)> = deserialize_bincode(BUF.read());
+    assert_eq!(
+        BUF.as_ref(),
+        [2, 0, 0, 0, 0xfe, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 1]
+    );
+    assert_eq!(DECODED.unwrap().1, KIND);
+}
+
+#[test]
+fn test_invalid_data() {
+    assert_eq!(decode::<Kind>(&[3, 0, 0, 0]), None);
+    assert!(bincode::deserialize::<Kind>(&[3, 0, 0, 0]).is_err());
+    assert_eq!(decode::<Kind>(&[1, 0, 0, 0, 5]), None);
+    assert_eq!(decode::<bool>(&[2]), None);
+}
@@ -0,0 +1,139 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use const_serialize::{embed_const, serialize_const, ConstWriteBuffer, DynLayout, SerializeConst};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Struct {
+    a: u32,
+    b: [u8; 2],
+    c: Enum,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Enum {
+    A { one: u32, two: u16 },
+}
+
+/// A temporary directory for the files of one test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir =
+        std::env::temp_dir().join(format!("const-serialize-cli-{}-{name}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn cli(args: &[&PathBuf]) -> (bool, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_const-serialize"))
+        .args(args)
+        .output()
+        .unwrap();
+    (
+        output.status.success(),
+        String::from_utf8(output.stdout).unwrap(),
+    )
+}
+
+fn cli_with(command: &str, args: &[&PathBuf]) -> (bool, String) {
+    let command = PathBuf::from(command);
+    let mut all = vec![&command];
+    all.extend_from_slice(args);
+    cli(&all)
+}
+
+#[test]
+#[cfg_attr(feature = "test-big-endian", ignore)]
+fn test_cli() {
+    let dir = temp_dir("roundtrip");
+    let schema = dir.join("schema.json");
+    let layout = DynLayout::from(Struct::MEMORY_LAYOUT);
+    std::fs::write(&schema, serde_json::to_string(&layout).unwrap()).unwrap();
+
+    let data = dir.join("data.bin");
+    let mut buf = ConstWriteBuffer::new();
+    buf = serialize_const(
+        &Struct {
+            a: 1,
+            b: [2, 3],
+            c: Enum::A { one: 4, two: 5 },
+        },
+        buf,
+    );
+    std::fs::write(&data, buf.as_ref()).unwrap();
+
+    let (success, dump) = cli_with("dump", &[&schema, &data]);
+    assert!(success);
+    assert!(dump.contains("c.A.two  u16 5"), "{dump}");
+
+    let (success, json) = cli_with("to-json", &[&schema, &data]);
+    assert!(success);
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({ "a": 1, "b": [2, 3], "c": { "A": { "one": 4, "two": 5 } } })
+    );
+
+    let edited_json = dir.join("edited.json");
+    std::fs::write(
+        &edited_json,
+        r#"{ "a": 1, "b": [2, 5], "c": { "A": { "one": 4, "two": 5 } } }"#,
+    )
+    .unwrap();
+    let edited = dir.join("edited.bin");
+    let (success, _) = cli_with(
+        "from-json",
+        &[&schema, &edited_json, &PathBuf::from("-o"), &edited],
+    );
+    assert!(success);
+
+    let (success, diff) = cli_with(
+        "diff",
+        &[&data, &edited, &PathBuf::from("--schema"), &schema],
+    );
+    assert!(!success);
+    assert_eq!(diff, "b[1]: 3 -> 5\n");
+
+    let (success, hash) = cli_with("hash", &[&schema]);
+    assert!(success);
+    assert_eq!(
+        hash.trim(),
+        format!("{:016x}", Struct::MEMORY_LAYOUT.fingerprint())
+    );
+
+    let (success, diff) = cli_with("diff", &[&schema, &schema]);
+    assert!(success);
+    assert_eq!(diff, "");
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+embed_const!(
+    static EMBEDDED: Struct = Struct {
+        a: 1,
+        b: [2, 3],
+        c: Enum::A { one: 4, two: 5 },
+    },
+    section = "cser",
+    self_describing,
+);
+
+#[test]
+#[cfg(target_os = "linux")]
+#[cfg_attr(feature = "test-big-endian", ignore)]
+fn test_cli_records() {
+    let exe = std::env::current_exe().unwrap();
+    let (success, records) = cli_with("records", &[&exe]);
+    assert!(success);
+    let [record] = records.lines().collect::<Vec<_>>()[..] else {
+        panic!("expected one record: {records}");
+    };
+    let fingerprint = format!("{:016x}", Struct::MEMORY_LAYOUT.fingerprint());
+    assert!(record.starts_with("cser 0x"), "{record}");
+    assert!(
+        record.ends_with(&format!(
+            "{fingerprint} {{\"a\":1,\"b\":[2,3],\"c\":{{\"A\":{{\"one\":4,\"two\":5}}}}}}"
+        )),
+        "{record}"
+    );
+}
@@ -0,0 +1,82 @@
+//! The record the wire format tests round trip and compare against other implementations of the formats
+
+use const_serialize::SerializeConst;
+use proptest::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, PartialEq, PartialOrd, SerializeConst, Serialize, Deserialize)]
+pub struct Record {
+    pub byte: u8,
+    pub signed_byte: i8,
+    pub short: u16,
+    pub signed_short: i16,
+    pub int: u32,
+    pub signed_int: i32,
+    pub long: u64,
+    pub signed_long: i64,
+    pub flag: bool,
+    pub single: f32,
+    pub double: f64,
+    pub list: [u16; 3],
+    pub pair: (i32, u8),
+    pub kinds: [Kind; 2],
+}
+
+impl Record {
+    /// NaN is not equal to itself, so records with NaN can't be compared after a round trip
+    pub fn has_nan(&self) -> bool {
+        self.single.is_nan() || self.double.is_nan()
+    }
+}
+
+// The discriminant of Far doesn't fit in a byte, so formats that write the index of the variant instead of
+// the discriminant write a different tag
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, SerializeConst, Serialize, Deserialize,
+)]
+#[repr(C, u16)]
+pub enum Kind {
+    Empty,
+    Small { value: u16 },
+    Far { value: i64, flag: bool } = 300,
+}
+
+pub fn kind() -> impl Strategy<Value = Kind> {
+    prop_oneof![
+        Just(Kind::Empty),
+        any::<u16>().prop_map(|value| Kind::Small { value }),
+        (any::<i64>(), any::<bool>()).prop_map(|(value, flag)| Kind::Far { value, flag }),
+    ]
+}
+
+pub fn record() -> impl Strategy<Value = Record> {
+    (
+        (any::<u8>(), any::<i8>(), any::<u16>(), any::<i16>()),
+        (any::<u32>(), any::<i32>(), any::<u64>(), any::<i64>()),
+        (any::<bool>(), any::<f32>(), any::<f64>()),
+        (any::<[u16; 3]>(), any::<(i32, u8)>(), [kind(), kind()]),
+    )
+        .prop_map(
+            |(
+                (byte, signed_byte, short, signed_short),
+                (int, signed_int, long, signed_long),
+                (flag, single, double),
+                (list, pair, kinds),
+            )| Record {
+                byte,
+                signed_byte,
+                short,
+                signed_short,
+                int,
+                signed_int,
+                long,
+                signed_long,
+                flag,
+                single,
+                double,
+                list,
+                pair,
+                kinds,
+            },
+        )
+}
@@ -0,0 +1,73 @@
+use const_serialize::{
+    deserialize_compact, serialize_compact, ConstReadBuffer, ConstWriteBuffer, SerializeConst,
+};
+use proptest::prelude::*;
+
+mod common;
+use common::{record, Kind, Record};
+
+fn encode<T: SerializeConst>(value: &T) -> Vec<u8> {
+    serialize_compact(value, ConstWriteBuffer::new())
+        .as_ref()
+        .to_vec()
+}
+
+fn decode<T: SerializeConst>(bytes: &[u8]) -> Option<T> {
+    let (rest, value) = deserialize_compact::<T>(ConstReadBuffer::new(bytes))?;
+    assert!(rest.get().is_none());
+    Some(value)
+}
+
+proptest! {
+    #[test]
+    fn test_matches_postcard(value in record().prop_filter("NaN is not equal to itself", |value| !value.has_nan())) {
+        let bytes = encode(&value);
+        prop_assert_eq!(&bytes, &postcard::to_allocvec(&value).unwrap());
+        prop_assert_eq!(decode::<Record>(&bytes), Some(value));
+        prop_assert_eq!(postcard::from_bytes::<Record>(&bytes).unwrap(), value);
+    }
+}
+
+#[test]
+fn test_edge_values() {
+    for value in [0, 1, 127, 128, 16383, 16384, u64::MAX >> 1, u64::MAX] {
+        assert_eq!(encode(&value), postcard::to_allocvec(&value).unwrap());
+        assert_eq!(decode::<u64>(&encode(&value)), Some(value));
+    }
+    for value in [0, -1, 1, -64, 64, i32::MIN, i32::MAX] {
+        assert_eq!(encode(&value), postcard::to_allocvec(&value).unwrap());
+        assert_eq!(decode::<i32>(&encode(&value)), Some(value));
+    }
+}
+
+#[test]
+fn test_in_const() {
+    const KIND: Kind = Kind::Far {
+        value: -3,
+        flag: true,
+    };
+    const BUF: ConstWriteBuffer = serialize_compact(&KIND, ConstWriteBuffer::new());
+    const DECODED: Option<(ConstReadBuffer, Kind)> = deserialize_compact(BUF.read());
+    assert_eq!(BUF.as_ref(), [2, 5, 1]);
+    assert_eq!(DECODED.unwrap().1, KIND);
+}
+
+#[test]
+fn test_invalid_data() {
+    // Too many bytes for a u16
+    assert_eq!(decode::<u16>(&[0x80, 0x80, 0x80, 0x01]), None);
+    // Too large for a u16
+    assert_eq!(decode::<u16>(&[0xff, 0xff, 0x04]), None);
+    assert_eq!(decode::<u16>(&[0xff, 0xff, 0x03]), Some(u16::MAX));
+    // Too large for a u64
+    assert_eq!(
+        decode::<u64>(&[0xff; 9].iter().chain(&[0x02]).copied().collect::<Vec<_>>()),
+        None
+    );
+    // Unterminated varint
+    assert_eq!(decode::<u32>(&[0x80]), None);
+    // Unknown variant and invalid bool
+    assert_eq!(decode::<Kind>(&[3]), None);
+    assert_eq!(decode::<bool>(&[2]), None);
+    assert!(postcard::from_bytes::<Kind>(&[3]).is_err());
+}
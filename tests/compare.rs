@@ -0,0 +1,173 @@
+use std::cmp::Ordering;
+
+use const_serialize::{const_cmp, const_eq, const_hash, SerializeConst};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, SerializeConst)]
+#[const_serialize(eq, ord, hash)]
+struct Key {
+    group: i16,
+    id: u32,
+    kind: Kind,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, SerializeConst)]
+#[repr(C, u8)]
+enum Kind {
+    Plain,
+    Tagged { tag: u16 },
+    Nested { inner: [i8; 2] } = 5,
+}
+
+#[derive(Clone, Copy, Debug, SerializeConst)]
+#[repr(C)]
+struct Padded {
+    small: u8,
+    large: u32,
+}
+
+const fn keys() -> [Key; 8] {
+    [
+        Key {
+            group: 2,
+            id: 1,
+            kind: Kind::Plain,
+        },
+        Key {
+            group: -3,
+            id: 7,
+            kind: Kind::Tagged { tag: 2 },
+        },
+        Key {
+            group: 2,
+            id: 1,
+            kind: Kind::Nested { inner: [-1, 0] },
+        },
+        Key {
+            group: -3,
+            id: 7,
+            kind: Kind::Tagged { tag: 1 },
+        },
+        Key {
+            group: 0,
+            id: u32::MAX,
+            kind: Kind::Plain,
+        },
+        Key {
+            group: 2,
+            id: 1,
+            kind: Kind::Nested { inner: [-1, -2] },
+        },
+        Key {
+            group: i16::MIN,
+            id: 0,
+            kind: Kind::Plain,
+        },
+        Key {
+            group: 0,
+            id: 256,
+            kind: Kind::Plain,
+        },
+    ]
+}
+
+/// Sort the keys in const with the generated const_cmp method
+const fn sorted_keys() -> [Key; 8] {
+    let mut keys = keys();
+    let mut i = 1;
+    while i < keys.len() {
+        let mut j = i;
+        while j > 0 && keys[j - 1].const_cmp(&keys[j]).is_gt() {
+            let swap = keys[j - 1];
+            keys[j - 1] = keys[j];
+            keys[j] = swap;
+            j -= 1;
+        }
+        i += 1;
+    }
+    keys
+}
+
+const SORTED: [Key; 8] = sorted_keys();
+
+#[test]
+fn test_const_cmp_matches_ord() {
+    let mut expected = keys();
+    expected.sort();
+    assert_eq!(SORTED, expected);
+
+    for first in keys() {
+        for second in keys() {
+            assert_eq!(const_cmp(&first, &second), first.cmp(&second));
+            assert_eq!(first.const_eq(&second), first == second);
+        }
+    }
+}
+
+#[test]
+fn test_const_cmp_floats() {
+    let floats = [
+        -f64::INFINITY,
+        -1.5,
+        -0.0,
+        0.0,
+        f64::MIN_POSITIVE,
+        2.0,
+        f64::INFINITY,
+        f64::NAN,
+    ];
+    for first in floats {
+        for second in floats {
+            assert_eq!(const_cmp(&first, &second), first.total_cmp(&second));
+            let (first, second) = (first as f32, second as f32);
+            assert_eq!(const_cmp(&first, &second), first.total_cmp(&second));
+        }
+    }
+    assert!(const_eq(&f64::NAN, &f64::NAN));
+    assert!(!const_eq(&-0.0, &0.0));
+}
+
+#[test]
+fn test_padding_is_ignored() {
+    let first: Padded = unsafe { std::mem::transmute([1u8, 0, 0, 0, 2, 0, 0, 0]) };
+    let second: Padded = unsafe { std::mem::transmute([1u8, 0xaa, 0xbb, 0xcc, 2, 0, 0, 0]) };
+    assert!(const_eq(&first, &second));
+    assert_eq!(const_cmp(&first, &second), Ordering::Equal);
+    assert_eq!(const_hash(&first), const_hash(&second));
+
+    let third = Padded { small: 1, large: 3 };
+    assert_eq!(const_cmp(&first, &third), Ordering::Less);
+    assert_ne!(const_hash(&first), const_hash(&third));
+}
+
+#[test]
+fn test_const_hash() {
+    const HASHES: [u64; 8] = {
+        let keys = keys();
+        let mut hashes = [0; 8];
+        let mut i = 0;
+        while i < keys.len() {
+            hashes[i] = keys[i].const_hash();
+            i += 1;
+        }
+        hashes
+    };
+    for (i, first) in keys().iter().enumerate() {
+        for (j, second) in keys().iter().enumerate() {
+            assert_eq!(HASHES[i] == HASHES[j], first == second);
+        }
+    }
+}
+
+#[test]
+fn test_large_values() {
+    // Larger than the buffers serialize_eq uses
+    const FIRST: [u64; 100] = [7; 100];
+    const SECOND: [u64; 100] = {
+        let mut values = [7; 100];
+        values[99] = 8;
+        values
+    };
+    const ORDERING: Ordering = const_cmp(&FIRST, &SECOND);
+    assert_eq!(ORDERING, Ordering::Less);
+    assert!(const_eq(&SECOND, &SECOND));
+}
@@ -0,0 +1,100 @@
+use const_serialize::{
+    decode_value, diff_values, serialize_const, ConstWriteBuffer, Difference, DynLayout,
+    SerializeConst,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Struct {
+    a: u32,
+    b: [u8; 2],
+    c: Enum,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Enum {
+    A { one: u32, two: u16 },
+    B { one: u8 } = 15,
+}
+
+mod changed {
+    use const_serialize::SerializeConst;
+
+    #[derive(SerializeConst)]
+    pub struct Struct {
+        pub a: u64,
+        pub b: [u8; 2],
+        pub c: Enum,
+        pub d: bool,
+    }
+
+    #[derive(SerializeConst)]
+    #[repr(C, u8)]
+    #[allow(dead_code)]
+    pub enum Enum {
+        A { one: u32 },
+        C { one: u8 } = 15,
+    }
+}
+
+fn difference(path: &str, left: &str, right: &str) -> Difference {
+    Difference {
+        path: path.to_string(),
+        left: left.to_string(),
+        right: right.to_string(),
+    }
+}
+
+#[test]
+fn test_diff_values() {
+    let decode = |data: &Struct| {
+        let mut buf = ConstWriteBuffer::new();
+        buf = serialize_const(data, buf);
+        decode_value(buf.as_ref(), &Struct::MEMORY_LAYOUT).unwrap()
+    };
+    let left = decode(&Struct {
+        a: 1,
+        b: [2, 3],
+        c: Enum::A { one: 4, two: 5 },
+    });
+    let right = decode(&Struct {
+        a: 1,
+        b: [2, 4],
+        c: Enum::A { one: 4, two: 6 },
+    });
+    assert_eq!(diff_values(&left, &left), Vec::new());
+    assert_eq!(
+        diff_values(&left, &right),
+        vec![
+            difference("b[1]", "3", "4"),
+            difference("c.A.two", "5", "6"),
+        ]
+    );
+
+    let right = decode(&Struct {
+        a: 1,
+        b: [2, 3],
+        c: Enum::B { one: 4 },
+    });
+    assert_eq!(
+        diff_values(&left, &right),
+        vec![difference("c", "Enum::A", "Enum::B")]
+    );
+}
+
+#[test]
+fn test_diff_layouts() {
+    let left = DynLayout::from(Struct::MEMORY_LAYOUT);
+    let right = DynLayout::from(changed::Struct::MEMORY_LAYOUT);
+    assert_eq!(left.diff(&left), Vec::new());
+    let differences = left.diff(&right);
+    assert_eq!(
+        differences,
+        vec![
+            difference("a", "u32", "u64"),
+            difference("c.A.two", "u16", "(missing)"),
+            difference("c.B", "B", "C"),
+            difference("d", "(missing)", "bool"),
+        ]
+    );
+}
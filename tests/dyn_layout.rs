@@ -0,0 +1,312 @@
+use const_serialize::{
+    deserialize_const, serialize_const, ConstReadBuffer, ConstWriteBuffer, DecodeError,
+    DecodeErrorKind, DynEnumEncoding, DynEnumVariant, DynLayout, DynListEncoding, DynPlainOldData,
+    DynStructEncoding, LayoutError, PrimitiveEncoding, PrimitiveKind, SerializeConst, Value,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C)]
+struct Struct {
+    a: u32,
+    b: u32,
+    c: [u16; 2],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Enum {
+    A { one: u32, two: u16 },
+    B { one: u8, two: u16 } = 15,
+}
+
+fn memory_of<T>(value: &T) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(value as *const T as *const u8, std::mem::size_of::<T>()) }
+}
+
+#[test]
+fn test_dyn_layout_roundtrip() {
+    let dyn_layout = DynLayout::from(Struct::MEMORY_LAYOUT);
+    assert_eq!(dyn_layout.size(), std::mem::size_of::<Struct>());
+    let layout = dyn_layout.clone().into_layout();
+    assert_eq!(DynLayout::from(layout), dyn_layout);
+
+    let dyn_layout = DynLayout::from(Enum::MEMORY_LAYOUT);
+    let layout = dyn_layout.clone().into_layout();
+    assert_eq!(DynLayout::from(layout), dyn_layout);
+}
+
+#[test]
+fn test_dyn_layout_matches_const_serialize() {
+    let data = Struct {
+        a: 0x11111111,
+        b: 0x22222222,
+        c: [0x3333, 0x4444],
+    };
+    let mut buf = ConstWriteBuffer::new();
+    buf = serialize_const(&data, buf);
+
+    let layout = DynLayout::from(Struct::MEMORY_LAYOUT);
+    let serialized = layout.serialize(memory_of(&data));
+    assert_eq!(serialized, buf.as_ref());
+
+    let memory = layout.deserialize(&serialized).unwrap();
+    assert_eq!(memory, memory_of(&data));
+}
+
+#[test]
+fn test_dyn_layout_enum() {
+    let layout = DynLayout::from(Enum::MEMORY_LAYOUT);
+    for data in [
+        Enum::A {
+            one: 0x11111111,
+            two: 0x22,
+        },
+        Enum::B {
+            one: 0x11,
+            two: 0x2233,
+        },
+    ] {
+        let mut buf = ConstWriteBuffer::new();
+        buf = serialize_const(&data, buf);
+        let memory = layout.deserialize(buf.as_ref()).unwrap();
+        let reserialized = layout.serialize(&memory);
+        assert_eq!(reserialized, buf.as_ref());
+        let buf = ConstReadBuffer::new(&reserialized);
+        assert_eq!(deserialize_const!(Enum, buf), Some(data));
+    }
+
+    let mut buf = ConstWriteBuffer::new();
+    buf = serialize_const(&Enum::A { one: 1, two: 2 }, buf);
+    let mut corrupted = buf.as_ref().to_vec();
+    corrupted[0] = 2;
+    assert_eq!(layout.deserialize(&corrupted), None);
+}
+
+#[test]
+fn test_dyn_layout_without_rust_type() {
+    // struct { a: u16, b: [u8; 3], c: enum { A { x: u16 } = 1, B {} = 7 } } with hand picked offsets
+    let layout = DynLayout::Struct(DynStructEncoding::new(
+        12,
+        vec![
+            DynPlainOldData::new(0, DynLayout::Primitive(PrimitiveEncoding::new(2))),
+            DynPlainOldData::new(
+                2,
+                DynLayout::List(DynListEncoding::new(
+                    3,
+                    DynLayout::Primitive(PrimitiveEncoding::new(1)),
+                )),
+            ),
+            DynPlainOldData::new(
+                8,
+                DynLayout::Enum(DynEnumEncoding::new(
+                    4,
+                    PrimitiveEncoding::new(1),
+                    vec![
+                        DynEnumVariant::new(
+                            1,
+                            DynStructEncoding::new(
+                                2,
+                                vec![DynPlainOldData::new(
+                                    0,
+                                    DynLayout::Primitive(PrimitiveEncoding::new(2)),
+                                )],
+                            ),
+                            2,
+                        ),
+                        DynEnumVariant::new(7, DynStructEncoding::new(0, vec![]), 1),
+                    ],
+                )),
+            ),
+        ],
+    ));
+
+    let a = 0x1234u16.to_le_bytes();
+    let x = 0x5678u16.to_le_bytes();
+    let (wire_a, wire_x) = if cfg!(feature = "test-big-endian") {
+        (0x1234u16.to_be_bytes(), 0x5678u16.to_be_bytes())
+    } else {
+        (a, x)
+    };
+
+    let serialized = [wire_a[0], wire_a[1], 1, 2, 3, 1, wire_x[0], wire_x[1]];
+    let memory = layout.deserialize(&serialized).unwrap();
+    assert_eq!(memory, [a[0], a[1], 1, 2, 3, 0, 0, 0, 1, 0, x[0], x[1]]);
+    assert_eq!(layout.serialize(&memory), serialized);
+
+    let serialized = [wire_a[0], wire_a[1], 1, 2, 3, 7];
+    let memory = layout.deserialize(&serialized).unwrap();
+    assert_eq!(layout.serialize(&memory), serialized);
+
+    assert_eq!(layout.deserialize(&serialized[..5]), None);
+}
+
+#[test]
+fn test_dyn_layout_wide_discriminant() {
+    // enum { A { x: u32 } = 7, B {} = 0 } with a u64 discriminant and the variants aligned to 16 bytes
+    let layout = DynLayout::Enum(DynEnumEncoding::new(
+        32,
+        PrimitiveEncoding::new(8),
+        vec![
+            DynEnumVariant::new(
+                7,
+                DynStructEncoding::new(
+                    4,
+                    vec![DynPlainOldData::new(
+                        0,
+                        DynLayout::Primitive(PrimitiveEncoding::new(4)),
+                    )],
+                )
+                .with_name("A"),
+                16,
+            ),
+            DynEnumVariant::new(0, DynStructEncoding::new(0, vec![]).with_name("B"), 1),
+        ],
+    ));
+
+    let serialized = [7, 0, 0, 0, 0, 0, 0, 0, 1, 2, 3, 4];
+    let value = layout.decode_value(&serialized).unwrap();
+    assert!(matches!(&value, Value::Enum(value) if value.tag == 7));
+    assert_eq!(layout.encode_value(&value).unwrap(), serialized);
+    let memory = layout.deserialize(&serialized).unwrap();
+    assert_eq!(layout.serialize(&memory), serialized);
+
+    // A discriminant that only differs from a variant in the high bytes doesn't match it
+    let unknown = [7, 0, 0, 0, 0, 1, 0, 0, 1, 2, 3, 4];
+    assert_eq!(
+        layout.decode_value(&unknown),
+        Err(DecodeError {
+            offset: 0,
+            kind: DecodeErrorKind::UnknownVariant { tag: 1 << 40 | 7 },
+        })
+    );
+    assert_eq!(layout.deserialize(&unknown), None);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C)]
+struct Tagged {
+    #[const_serialize(id = 3)]
+    a: u16,
+    #[const_serialize(id = 1, default = 7)]
+    b: u32,
+}
+
+#[test]
+fn test_dyn_layout_field_ids() {
+    let dyn_layout = DynLayout::from(Tagged::MEMORY_LAYOUT);
+    let default = serialize_const(&7u32, ConstWriteBuffer::new());
+    let expected = DynLayout::Struct(
+        DynStructEncoding::new(
+            8,
+            vec![
+                DynPlainOldData::new(0, DynLayout::Primitive(PrimitiveEncoding::new(2)))
+                    .with_name("a")
+                    .with_id(3),
+                DynPlainOldData::new(4, DynLayout::Primitive(PrimitiveEncoding::new(4)))
+                    .with_name("b")
+                    .with_id(1)
+                    .with_default(default.as_ref().to_vec()),
+            ],
+        )
+        .with_name("Tagged"),
+    );
+    assert_eq!(dyn_layout, expected);
+
+    // Converting back to a layout keeps the ids and defaults
+    assert_eq!(
+        DynLayout::from(dyn_layout.clone().into_layout()),
+        dyn_layout
+    );
+}
+
+#[test]
+fn test_dyn_layout_invalid_bool() {
+    let layout = DynLayout::from(<[bool; 2]>::MEMORY_LAYOUT);
+    assert_eq!(layout.deserialize(&[1, 0]), Some(vec![1, 0]));
+    assert_eq!(layout.deserialize(&[1, 2]), None);
+}
+
+#[test]
+fn test_dyn_layout_validate() {
+    let number = || DynLayout::Primitive(PrimitiveEncoding::new(4));
+    let field = |offset| DynPlainOldData::new(offset, number());
+    assert_eq!(DynLayout::from(Struct::MEMORY_LAYOUT).validate(), Ok(()));
+    assert_eq!(DynLayout::from(Enum::MEMORY_LAYOUT).validate(), Ok(()));
+
+    let bool_field = DynPlainOldData::new(
+        0,
+        DynLayout::Primitive(PrimitiveEncoding::new(1).with_kind(PrimitiveKind::Bool)),
+    );
+    let invalid = [
+        // A field that ends after the end of the struct
+        (
+            DynLayout::Struct(DynStructEncoding::new(4, vec![field(2)])),
+            LayoutError::OutOfBounds { end: 6, size: 4 },
+        ),
+        (
+            DynLayout::Struct(DynStructEncoding::new(4, vec![field(usize::MAX)])),
+            LayoutError::OutOfBounds {
+                end: usize::MAX,
+                size: 4,
+            },
+        ),
+        // A list that is too large to have a size
+        (
+            DynLayout::List(DynListEncoding::new(usize::MAX, number())),
+            LayoutError::SizeOverflow,
+        ),
+        // Variants that don't fit after the discriminant
+        (
+            DynLayout::Enum(DynEnumEncoding::new(
+                4,
+                PrimitiveEncoding::new(1),
+                vec![DynEnumVariant::new(
+                    0,
+                    DynStructEncoding::new(4, vec![field(0)]),
+                    4,
+                )],
+            )),
+            LayoutError::OutOfBounds { end: 8, size: 4 },
+        ),
+        // Defaults that are not one value of the field
+        (
+            DynLayout::Struct(DynStructEncoding::new(
+                4,
+                vec![field(0).with_default(vec![1, 2])],
+            )),
+            LayoutError::InvalidDefault,
+        ),
+        (
+            DynLayout::Struct(DynStructEncoding::new(
+                4,
+                vec![field(0).with_default(vec![1, 2, 3, 4, 5])],
+            )),
+            LayoutError::InvalidDefault,
+        ),
+        (
+            DynLayout::Struct(DynStructEncoding::new(
+                1,
+                vec![bool_field.with_default(vec![2])],
+            )),
+            LayoutError::InvalidDefault,
+        ),
+    ];
+    for (layout, error) in invalid {
+        assert_eq!(layout.validate(), Err(error.clone()), "{layout:?}");
+        // Layouts loaded with serde are validated
+        let json = serde_json::to_string(&layout).unwrap();
+        let loaded = serde_json::from_str::<DynLayout>(&json).unwrap_err();
+        assert!(loaded.to_string().contains(&error.to_string()), "{loaded}");
+    }
+
+    // Large lists of items without any data are checked without walking every item
+    let empty = DynLayout::Struct(DynStructEncoding::new(0, Vec::new()));
+    let layout = DynLayout::Struct(DynStructEncoding::new(
+        0,
+        vec![
+            DynPlainOldData::new(0, DynLayout::List(DynListEncoding::new(usize::MAX, empty)))
+                .with_default(Vec::new()),
+        ],
+    ));
+    assert_eq!(layout.validate(), Ok(()));
+}
@@ -0,0 +1,285 @@
+use const_serialize::{
+    embed_const, embed_schema, read_records, serialize_described_record, serialize_record,
+    serialize_schema_record, DynLayout, Primitive, RecordError, SerializeConst, Value,
+    RECORD_HEADER_SIZE, RECORD_MAGIC,
+};
+use object::write::{Object, StandardSegment};
+use object::{Architecture, BinaryFormat, Endianness, SectionKind};
+use proptest::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Version {
+    major: u16,
+    minor: u16,
+    tag: Tag,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Tag {
+    Release,
+    Beta { number: u32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct FieldIds {
+    #[const_serialize(id = 2)]
+    first: u16,
+    #[const_serialize(id = 1, default = 5)]
+    second: u16,
+}
+
+const VERSION: Version = Version {
+    major: 1,
+    minor: 2,
+    tag: Tag::Beta { number: 3 },
+};
+
+embed_const!(static EMBEDDED_VERSION: Version = VERSION, section = "cser");
+embed_const!(
+    static EMBEDDED_RELEASE: Version = Version {
+        major: 4,
+        minor: 0,
+        tag: Tag::Release,
+    },
+    section = "cser",
+);
+embed_const!(static EMBEDDED_NUMBER: u64 = 0x1122334455667788, section = "cser");
+
+embed_const!(static DESCRIBED_VERSION: Version = VERSION, section = "cserdesc", self_describing);
+embed_schema!(static NUMBER_SCHEMA: u64, section = "cserdesc");
+embed_const!(
+    static DESCRIBED_IDS: FieldIds = FieldIds { first: 7, second: 8 },
+    section = "cserdesc",
+    self_describing,
+);
+embed_const!(static TABLE_NUMBER: u64 = 5, section = "cserdesc");
+embed_const!(static UNKNOWN_NUMBER: u32 = 6, section = "cserdesc");
+
+#[test]
+fn test_record_header() {
+    let record = serialize_record(&0x1234u16);
+    let record = record.as_ref();
+    assert_eq!(record.len(), RECORD_HEADER_SIZE + 2);
+    assert_eq!(record[..4], RECORD_MAGIC);
+    assert_eq!(
+        record[4..12],
+        u16::MEMORY_LAYOUT.fingerprint().to_le_bytes()
+    );
+    assert_eq!(record[12..16], 2u32.to_le_bytes());
+    assert_eq!(
+        EMBEDDED_NUMBER[..],
+        serialize_record(&0x1122334455667788u64).as_ref()[..]
+    );
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_read_records_from_current_exe() {
+    let file = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    let records = read_records(&file, "cser").unwrap();
+    assert_eq!(records.len(), 3);
+
+    let versions: Vec<Version> = records
+        .iter()
+        .filter_map(|record| record.deserialize::<Version>().ok())
+        .collect();
+    assert!(versions.contains(&VERSION));
+    assert!(versions.contains(&Version {
+        major: 4,
+        minor: 0,
+        tag: Tag::Release,
+    }));
+
+    let number = records
+        .iter()
+        .find(|record| record.fingerprint == u64::MEMORY_LAYOUT.fingerprint())
+        .unwrap();
+    assert_eq!(number.deserialize::<u64>(), Ok(0x1122334455667788));
+    assert_eq!(
+        number.decode(&DynLayout::from(u64::MEMORY_LAYOUT)),
+        Ok(Value::Primitive(Primitive::Unsigned(0x1122334455667788)))
+    );
+    assert_eq!(
+        number.deserialize::<u32>(),
+        Err(RecordError::FingerprintMismatch {
+            expected: u32::MEMORY_LAYOUT.fingerprint(),
+            found: u64::MEMORY_LAYOUT.fingerprint(),
+        })
+    );
+}
+
+/// Write an object file with a `cser` section that contains the data
+fn object_file(format: BinaryFormat, architecture: Architecture, data: &[u8]) -> Vec<u8> {
+    let mut object = Object::new(format, architecture, Endianness::Little);
+    let segment = object.segment_name(StandardSegment::Data).to_vec();
+    let section = object.add_section(segment, b"cser".to_vec(), SectionKind::Data);
+    object.append_section_data(section, data, 1);
+    object.write().unwrap()
+}
+
+#[test]
+fn test_read_records_from_object_files() {
+    let mut section_data = Vec::new();
+    section_data.extend_from_slice(serialize_record(&VERSION).as_ref());
+    // Padding between records is skipped
+    section_data.extend_from_slice(&[0; 3]);
+    section_data.extend_from_slice(serialize_record(&7u8).as_ref());
+
+    for (format, architecture) in [
+        (BinaryFormat::Elf, Architecture::X86_64),
+        (BinaryFormat::MachO, Architecture::Aarch64),
+        (BinaryFormat::Coff, Architecture::X86_64),
+    ] {
+        let file = object_file(format, architecture, &section_data);
+        let records = read_records(&file, "cser").unwrap();
+        assert_eq!(records.len(), 2, "{format:?}");
+        assert_eq!(records[0].deserialize::<Version>(), Ok(VERSION));
+        assert_eq!(records[1].deserialize::<u8>(), Ok(7));
+        assert_eq!(
+            records[1].address - records[0].address,
+            (RECORD_HEADER_SIZE + records[0].data.len() + 3) as u64
+        );
+    }
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_read_self_describing_records_from_current_exe() {
+    let file = std::fs::read(std::env::current_exe().unwrap()).unwrap();
+    let records = read_records(&file, "cserdesc").unwrap();
+    assert_eq!(records.len(), 4);
+
+    let described = records
+        .iter()
+        .find(|record| record.fingerprint == Version::MEMORY_LAYOUT.fingerprint())
+        .unwrap();
+    assert_eq!(
+        described.schema,
+        Some(DynLayout::from(Version::MEMORY_LAYOUT))
+    );
+    assert_eq!(described.deserialize::<Version>(), Ok(VERSION));
+    let value = serde_json::to_value(described.value().unwrap()).unwrap();
+    assert_eq!(
+        value,
+        serde_json::json!({ "major": 1, "minor": 2, "tag": { "Beta": { "number": 3 } } })
+    );
+
+    // The schema keeps the field ids and defaults, so the fingerprint still matches
+    let ids = records
+        .iter()
+        .find(|record| record.fingerprint == FieldIds::MEMORY_LAYOUT.fingerprint())
+        .unwrap();
+    assert_eq!(ids.schema, Some(DynLayout::from(FieldIds::MEMORY_LAYOUT)));
+    assert_eq!(
+        ids.deserialize::<FieldIds>(),
+        Ok(FieldIds {
+            first: 7,
+            second: 8
+        })
+    );
+
+    let table = records
+        .iter()
+        .find(|record| record.fingerprint == u64::MEMORY_LAYOUT.fingerprint())
+        .unwrap();
+    assert_eq!(table.value(), Ok(Value::Primitive(Primitive::Unsigned(5))));
+
+    let unknown = records
+        .iter()
+        .find(|record| record.fingerprint == u32::MEMORY_LAYOUT.fingerprint())
+        .unwrap();
+    assert_eq!(unknown.value(), Err(RecordError::MissingSchema));
+    assert_eq!(unknown.deserialize::<u32>(), Ok(6));
+}
+
+#[test]
+fn test_read_self_describing_records_from_object_files() {
+    let mut section_data = Vec::new();
+    section_data.extend_from_slice(serialize_described_record(&VERSION).as_ref());
+    section_data.extend_from_slice(serialize_record(&7u8).as_ref());
+    section_data.extend_from_slice(serialize_schema_record::<u8>().as_ref());
+
+    for (format, architecture) in [
+        (BinaryFormat::Elf, Architecture::X86_64),
+        (BinaryFormat::MachO, Architecture::Aarch64),
+        (BinaryFormat::Coff, Architecture::X86_64),
+    ] {
+        let file = object_file(format, architecture, &section_data);
+        let records = read_records(&file, "cser").unwrap();
+        assert_eq!(records.len(), 2, "{format:?}");
+        assert_eq!(records[0].deserialize::<Version>(), Ok(VERSION));
+        assert_eq!(
+            records[1].value(),
+            Ok(Value::Primitive(Primitive::Unsigned(7)))
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "records can be at most 512 bytes")]
+fn test_described_record_too_large() {
+    let _ = serialize_described_record(&[0u64; 62]);
+}
+
+/// Read the records in a section and decode every record that was found. Corrupt data may fail to read or decode,
+/// but must never panic.
+fn read_corrupt_section(data: &[u8]) {
+    let file = object_file(BinaryFormat::Elf, Architecture::X86_64, data);
+    let Ok(records) = read_records(&file, "cser") else {
+        return;
+    };
+    for record in records {
+        let _ = record.value();
+        let _ = record.deserialize::<Version>();
+        let _ = record.deserialize::<FieldIds>();
+        let _ = record.deserialize::<u64>();
+        if let Some(schema) = &record.schema {
+            let _ = record.decode(schema);
+        }
+    }
+}
+
+/// A section with one record of every kind
+fn valid_section() -> Vec<u8> {
+    let mut data = Vec::new();
+    data.extend_from_slice(serialize_record(&VERSION).as_ref());
+    data.extend_from_slice(serialize_described_record(&VERSION).as_ref());
+    data.extend_from_slice(
+        serialize_described_record(&FieldIds {
+            first: 1,
+            second: 2,
+        })
+        .as_ref(),
+    );
+    data.extend_from_slice(serialize_schema_record::<u64>().as_ref());
+    data.extend_from_slice(serialize_record(&5u64).as_ref());
+    data
+}
+
+proptest! {
+    #[test]
+    fn test_read_truncated_section(len in 0..valid_section().len()) {
+        read_corrupt_section(&valid_section()[..len]);
+    }
+
+    #[test]
+    fn test_read_corrupt_section(changes in prop::collection::vec((any::<prop::sample::Index>(), any::<u8>()), 1..8)) {
+        let mut data = valid_section();
+        for (index, byte) in changes {
+            let index = index.index(data.len());
+            data[index] = byte;
+        }
+        read_corrupt_section(&data);
+    }
+
+    #[test]
+    fn test_read_random_section(
+        magic in prop::sample::select(vec![*b"CSER", *b"CSED", *b"CSLY"]),
+        rest in prop::collection::vec(any::<u8>(), 0..128),
+    ) {
+        let mut data = magic.to_vec();
+        data.extend_from_slice(&rest);
+        read_corrupt_section(&data);
+    }
+}
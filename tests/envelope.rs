@@ -0,0 +1,175 @@
+use const_serialize::{
+    crc32, deserialize_enveloped, serialize_const, serialize_enveloped, ConstReadBuffer,
+    ConstWriteBuffer, EnvelopeError, SerializeConst, ENVELOPE_HEADER_SIZE, ENVELOPE_MAGIC,
+    ENVELOPE_VERSION,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Struct {
+    a: u32,
+    b: [u8; 3],
+    c: Enum,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+#[allow(dead_code)]
+enum Enum {
+    A { one: u32, two: u16 },
+    B { one: u8, two: u16 } = 15,
+}
+
+const DATA: Struct = Struct {
+    a: 0x11111111,
+    b: [1, 2, 3],
+    c: Enum::B { one: 4, two: 5 },
+};
+
+const ENVELOPE: ConstWriteBuffer = serialize_enveloped(&DATA, ConstWriteBuffer::new());
+
+#[test]
+fn test_crc32() {
+    assert_eq!(crc32(b""), 0);
+    assert_eq!(crc32(b"123456789"), 0xcbf43926);
+    assert_eq!(
+        crc32(b"The quick brown fox jumps over the lazy dog"),
+        0x414fa339
+    );
+}
+
+#[test]
+fn test_envelope_roundtrip() {
+    const DESERIALIZED: Result<Struct, EnvelopeError> =
+        deserialize_enveloped!(Struct, ENVELOPE.read());
+    assert_eq!(DESERIALIZED, Ok(DATA));
+
+    let payload = serialize_const(&DATA, ConstWriteBuffer::new());
+    let envelope = ENVELOPE.as_ref();
+    assert_eq!(
+        envelope.len(),
+        ENVELOPE_HEADER_SIZE + payload.as_ref().len()
+    );
+    assert_eq!(envelope[..4], ENVELOPE_MAGIC);
+    assert_eq!(envelope[4], ENVELOPE_VERSION);
+    assert_eq!(envelope[6] as usize, std::mem::size_of::<usize>());
+    assert_eq!(
+        envelope[8..16],
+        Struct::MEMORY_LAYOUT.fingerprint().to_le_bytes()
+    );
+    assert_eq!(
+        envelope[16..20],
+        (payload.as_ref().len() as u32).to_le_bytes()
+    );
+    assert_eq!(envelope[20..24], crc32(payload.as_ref()).to_le_bytes());
+    assert_eq!(envelope[ENVELOPE_HEADER_SIZE..], *payload.as_ref());
+
+    // Bytes after the payload are ignored
+    let mut trailing = envelope.to_vec();
+    trailing.push(0xff);
+    assert_eq!(
+        deserialize_enveloped!(Struct, ConstReadBuffer::new(&trailing)),
+        Ok(DATA)
+    );
+}
+
+#[test]
+fn test_envelope_errors() {
+    let envelope = ENVELOPE.as_ref();
+    let deserialize = |bytes: &[u8]| deserialize_enveloped!(Struct, ConstReadBuffer::new(bytes));
+    let modified = |index: usize, value: u8| {
+        let mut bytes = envelope.to_vec();
+        bytes[index] = value;
+        deserialize(&bytes)
+    };
+
+    assert_eq!(
+        deserialize(&envelope[..ENVELOPE_HEADER_SIZE - 1]),
+        Err(EnvelopeError::UnexpectedEnd)
+    );
+    assert_eq!(
+        deserialize(&envelope[..envelope.len() - 1]),
+        Err(EnvelopeError::UnexpectedEnd)
+    );
+    assert_eq!(modified(0, b'X'), Err(EnvelopeError::BadMagic));
+    assert_eq!(
+        modified(4, 2),
+        Err(EnvelopeError::UnsupportedVersion { found: 2 })
+    );
+    assert_eq!(
+        modified(5, envelope[5] ^ 1),
+        Err(EnvelopeError::TargetMismatch {
+            big_endian: envelope[5] == 0,
+            pointer_width: envelope[6],
+        })
+    );
+    assert_eq!(
+        modified(6, 2),
+        Err(EnvelopeError::TargetMismatch {
+            big_endian: envelope[5] != 0,
+            pointer_width: 2,
+        })
+    );
+    assert_eq!(
+        deserialize_enveloped!(Enum, ENVELOPE.read()),
+        Err(EnvelopeError::LayoutMismatch {
+            expected: Enum::MEMORY_LAYOUT.fingerprint(),
+            found: Struct::MEMORY_LAYOUT.fingerprint(),
+        })
+    );
+
+    let last = envelope.len() - 1;
+    let mut corrupted = envelope.to_vec();
+    corrupted[last] ^= 0xff;
+    assert_eq!(
+        deserialize(&corrupted),
+        Err(EnvelopeError::ChecksumMismatch {
+            expected: crc32(&envelope[ENVELOPE_HEADER_SIZE..]),
+            found: crc32(&corrupted[ENVELOPE_HEADER_SIZE..]),
+        })
+    );
+
+    // A payload with an unknown enum tag and a matching checksum
+    let tag = ENVELOPE_HEADER_SIZE + 7;
+    let mut corrupted = envelope.to_vec();
+    corrupted[tag] = 3;
+    let checksum = crc32(&corrupted[ENVELOPE_HEADER_SIZE..]).to_le_bytes();
+    corrupted[20..24].copy_from_slice(&checksum);
+    assert_eq!(deserialize(&corrupted), Err(EnvelopeError::InvalidPayload));
+}
+
+#[test]
+fn test_envelope_byte_order() {
+    // The envelope of DATA written by the default build, which is little endian on every target
+    const LITTLE_ENDIAN: [u8; 35] = [
+        0x43, 0x53, 0x45, 0x56, 0x01, 0x00, 0x08, 0x00, 0x50, 0x85, 0xc6, 0xc0, 0x36, 0x14, 0xd9,
+        0x5a, 0x0b, 0x00, 0x00, 0x00, 0x16, 0xbb, 0x3b, 0x49, 0x11, 0x11, 0x11, 0x11, 0x01, 0x02,
+        0x03, 0x0f, 0x04, 0x05, 0x00,
+    ];
+    // The same envelope written with the test-big-endian feature
+    const BIG_ENDIAN: [u8; 35] = [
+        0x43, 0x53, 0x45, 0x56, 0x01, 0x01, 0x08, 0x00, 0x50, 0x85, 0xc6, 0xc0, 0x36, 0x14, 0xd9,
+        0x5a, 0x0b, 0x00, 0x00, 0x00, 0xdc, 0xbb, 0x26, 0x44, 0x11, 0x11, 0x11, 0x11, 0x01, 0x02,
+        0x03, 0x0f, 0x04, 0x00, 0x05,
+    ];
+    let pointer_width = std::mem::size_of::<usize>() as u8;
+    let deserialize = |envelope: [u8; 35]| {
+        let mut bytes = envelope;
+        bytes[6] = pointer_width;
+        deserialize_enveloped!(Struct, ConstReadBuffer::new(&bytes))
+    };
+
+    let (matching, other, other_big_endian) = if cfg!(feature = "test-big-endian") {
+        (BIG_ENDIAN, LITTLE_ENDIAN, false)
+    } else {
+        (LITTLE_ENDIAN, BIG_ENDIAN, true)
+    };
+    assert_eq!(ENVELOPE.as_ref()[5], matching[5]);
+    assert_eq!(deserialize(matching), Ok(DATA));
+    assert_eq!(
+        deserialize(other),
+        Err(EnvelopeError::TargetMismatch {
+            big_endian: other_big_endian,
+            pointer_width,
+        })
+    );
+}
@@ -0,0 +1,135 @@
+use const_serialize::{DynLayout, SerializeConst};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Struct {
+    a: u32,
+    b: u8,
+    c: [Enum; 2],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+#[allow(dead_code)]
+enum Enum {
+    A { one: u32, two: u16 },
+    B { one: u8, two: u16 } = 15,
+}
+
+mod renamed {
+    use const_serialize::SerializeConst;
+
+    #[derive(SerializeConst)]
+    pub struct Struct {
+        pub a: u32,
+        pub d: u8,
+        pub c: [super::Enum; 2],
+    }
+}
+
+mod reordered {
+    use const_serialize::SerializeConst;
+
+    #[derive(SerializeConst)]
+    #[repr(C)]
+    pub struct Struct {
+        pub b: u8,
+        pub a: u32,
+        pub c: [super::Enum; 2],
+    }
+}
+
+mod tagged {
+    use const_serialize::SerializeConst;
+
+    #[derive(SerializeConst)]
+    pub struct Struct {
+        #[const_serialize(id = 1)]
+        pub a: u32,
+        #[const_serialize(id = 2)]
+        pub b: u8,
+        #[const_serialize(id = 3)]
+        pub c: [super::Enum; 2],
+    }
+
+    #[derive(SerializeConst)]
+    pub struct Renumbered {
+        #[const_serialize(id = 1)]
+        pub a: u32,
+        #[const_serialize(id = 4)]
+        pub b: u8,
+        #[const_serialize(id = 3)]
+        pub c: [super::Enum; 2],
+    }
+
+    #[derive(SerializeConst)]
+    pub struct Defaulted {
+        #[const_serialize(id = 1)]
+        pub a: u32,
+        #[const_serialize(id = 2, default = 1)]
+        pub b: u8,
+        #[const_serialize(id = 3)]
+        pub c: [super::Enum; 2],
+    }
+
+    pub mod other_default {
+        use const_serialize::SerializeConst;
+
+        #[derive(SerializeConst)]
+        pub struct Defaulted {
+            #[const_serialize(id = 1)]
+            pub a: u32,
+            #[const_serialize(id = 2, default = 2)]
+            pub b: u8,
+            #[const_serialize(id = 3)]
+            pub c: [super::super::Enum; 2],
+        }
+    }
+}
+
+#[test]
+fn test_fingerprint() {
+    const FINGERPRINT: u64 = Struct::MEMORY_LAYOUT.fingerprint();
+    assert_eq!(FINGERPRINT, Struct::MEMORY_LAYOUT.fingerprint());
+    assert_eq!(
+        DynLayout::from(Struct::MEMORY_LAYOUT).fingerprint(),
+        FINGERPRINT
+    );
+    assert_ne!(renamed::Struct::MEMORY_LAYOUT.fingerprint(), FINGERPRINT);
+    assert_ne!(reordered::Struct::MEMORY_LAYOUT.fingerprint(), FINGERPRINT);
+    assert_ne!(
+        u32::MEMORY_LAYOUT.fingerprint(),
+        i32::MEMORY_LAYOUT.fingerprint()
+    );
+    assert_ne!(
+        <[u8; 2]>::MEMORY_LAYOUT.fingerprint(),
+        <[u8; 3]>::MEMORY_LAYOUT.fingerprint()
+    );
+}
+
+#[test]
+fn test_fingerprint_after_conversion() {
+    let layout = DynLayout::from(Enum::MEMORY_LAYOUT);
+    assert_eq!(
+        layout.clone().into_layout().fingerprint(),
+        layout.fingerprint()
+    );
+    assert_eq!(layout.fingerprint(), Enum::MEMORY_LAYOUT.fingerprint());
+}
+
+#[test]
+fn test_fingerprint_field_options() {
+    let fingerprint = tagged::Struct::MEMORY_LAYOUT.fingerprint();
+    assert_ne!(fingerprint, Struct::MEMORY_LAYOUT.fingerprint());
+    assert_ne!(tagged::Renumbered::MEMORY_LAYOUT.fingerprint(), fingerprint);
+    let defaulted = tagged::Defaulted::MEMORY_LAYOUT.fingerprint();
+    assert_ne!(defaulted, fingerprint);
+    // Only whether a field has a default is part of the fingerprint, not the value
+    assert_eq!(
+        tagged::other_default::Defaulted::MEMORY_LAYOUT.fingerprint(),
+        defaulted
+    );
+    assert_eq!(
+        DynLayout::from(tagged::Defaulted::MEMORY_LAYOUT).fingerprint(),
+        defaulted
+    );
+}
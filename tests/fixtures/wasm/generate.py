@@ -0,0 +1,71 @@
+"""Regenerate the wasm fixtures used by tests/wasm.rs.
+
+The records are the output of `serialize_record` for the values in tests/wasm.rs. They depend on
+the layout fingerprints of the types in that test, so update them here if the types change.
+"""
+
+from pathlib import Path
+
+# serialize_record(&Version { major: 1, minor: 2, tag: Tag::Beta { number: 3 } })
+BETA = bytes.fromhex("43534552b9e9a61263fa7a6409000000010002000103000000")
+# serialize_record(&Version { major: 4, minor: 0, tag: Tag::Release })
+RELEASE = bytes.fromhex("43534552b9e9a61263fa7a64050000000400000000")
+# serialize_record(&0x1122334455667788u64)
+NUMBER = bytes.fromhex("43534552c5f52985f8ad1a82080000008877665544332211")
+
+
+def uleb(value):
+    out = bytearray()
+    while True:
+        byte = value & 0x7F
+        value >>= 7
+        if value:
+            out.append(byte | 0x80)
+        else:
+            out.append(byte)
+            return bytes(out)
+
+
+def sleb(value):
+    out = bytearray()
+    while True:
+        byte = value & 0x7F
+        value >>= 7
+        if (value == 0 and not byte & 0x40) or (value == -1 and byte & 0x40):
+            out.append(byte)
+            return bytes(out)
+        out.append(byte | 0x80)
+
+
+def vec(data):
+    return uleb(len(data)) + data
+
+
+def section(id, content):
+    return bytes([id]) + vec(content)
+
+
+def custom(name, content):
+    return section(0, vec(name.encode()) + content)
+
+
+HEADER = b"\0asm" + (1).to_bytes(4, "little")
+
+
+def data_module():
+    memory = section(5, uleb(1) + b"\x00" + uleb(1))
+    data_count = section(12, uleb(2))
+    # An active segment at address 1024 with other data and padding around the records
+    active = b"\x00" + b"\x41" + sleb(1024) + b"\x0b" + vec(b"hello\0" + BETA + b"\0\0" + NUMBER)
+    passive = b"\x01" + vec(RELEASE)
+    data = section(11, uleb(2) + active + passive)
+    return HEADER + memory + data_count + data
+
+
+def custom_module():
+    return HEADER + custom("producers", b"\0") + custom("cser", RELEASE + NUMBER)
+
+
+here = Path(__file__).parent
+(here / "data.wasm").write_bytes(data_module())
+(here / "custom.wasm").write_bytes(custom_module())
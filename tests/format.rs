@@ -0,0 +1,125 @@
+use const_serialize::{
+    deserialize_with, serialize_const, serialize_with, ConstReadBuffer, ConstWriteBuffer,
+    LengthFormat, NativeFormat, PrimitiveFormat, SerializeConst, TagFormat, WireFormat,
+};
+
+/// Every struct and array knows its length, so readers can skip fields they don't know about
+struct Delimited;
+
+impl WireFormat for Delimited {
+    const PRIMITIVES: PrimitiveFormat = PrimitiveFormat::BigEndian;
+    const ENUM_TAGS: TagFormat = TagFormat::IndexVarint;
+    const LIST_LENGTHS: LengthFormat = LengthFormat::U32;
+    const STRUCT_LENGTHS: LengthFormat = LengthFormat::Varint;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Old {
+    id: u16,
+    shape: Shape,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct New {
+    id: u16,
+    shape: Shape,
+    scale: [i16; 2],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Shape {
+    Point,
+    Circle { radius: u32 } = 4,
+}
+
+fn encode<F: WireFormat, T: SerializeConst>(value: &T) -> Vec<u8> {
+    serialize_with::<F, T>(value, ConstWriteBuffer::new())
+        .as_ref()
+        .to_vec()
+}
+
+fn decode<F: WireFormat, T: SerializeConst>(bytes: &[u8]) -> Option<T> {
+    let (rest, value) = deserialize_with::<F, T>(ConstReadBuffer::new(bytes))?;
+    assert!(rest.get().is_none());
+    Some(value)
+}
+
+#[test]
+fn test_native_format() {
+    let value = New {
+        id: 7,
+        shape: Shape::Circle { radius: 9 },
+        scale: [-1, 2],
+    };
+    assert_eq!(
+        encode::<NativeFormat, _>(&value),
+        serialize_const(&value, ConstWriteBuffer::new()).as_ref()
+    );
+    assert_eq!(
+        decode::<NativeFormat, New>(&encode::<NativeFormat, _>(&value)),
+        Some(value)
+    );
+}
+
+#[test]
+fn test_lengths() {
+    const VALUE: New = New {
+        id: 0x0102,
+        shape: Shape::Circle { radius: 3 },
+        scale: [-1, 2],
+    };
+    const BUF: ConstWriteBuffer = serialize_with::<Delimited, _>(&VALUE, ConstWriteBuffer::new());
+    let expected = [
+        // The length of New, then id
+        &[16, 1, 2][..],
+        // The variant index, the length of Circle and radius
+        &[1, 4, 0, 0, 0, 3],
+        // The length of scale and its items
+        &[2, 0, 0, 0, 0xff, 0xff, 0, 2],
+    ]
+    .concat();
+    assert_eq!(BUF.as_ref(), expected);
+    assert_eq!(decode::<Delimited, New>(BUF.as_ref()), Some(VALUE));
+}
+
+#[test]
+fn test_unknown_fields_are_skipped() {
+    let new = New {
+        id: 5,
+        shape: Shape::Point,
+        scale: [3, 4],
+    };
+    let bytes = encode::<Delimited, _>(&new);
+    assert_eq!(
+        decode::<Delimited, Old>(&bytes),
+        Some(Old {
+            id: 5,
+            shape: Shape::Point
+        })
+    );
+    // Without struct lengths, the extra fields are left in the buffer
+    let bytes = encode::<NativeFormat, _>(&new);
+    let (rest, _) = deserialize_with::<NativeFormat, Old>(ConstReadBuffer::new(&bytes)).unwrap();
+    let (_, scale) = deserialize_with::<NativeFormat, [i16; 2]>(rest).unwrap();
+    assert_eq!(scale, [3, 4]);
+}
+
+#[test]
+fn test_invalid_lengths() {
+    let mut bytes = encode::<Delimited, _>(&[1u8, 2, 3]);
+    assert_eq!(decode::<Delimited, [u8; 3]>(&bytes), Some([1, 2, 3]));
+    // The array length doesn't match the type
+    bytes[0] = 2;
+    assert_eq!(decode::<Delimited, [u8; 3]>(&bytes), None);
+    // The struct is longer than the buffer
+    let mut bytes = encode::<Delimited, _>(&Old {
+        id: 1,
+        shape: Shape::Point,
+    });
+    bytes[0] += 1;
+    assert_eq!(decode::<Delimited, Old>(&bytes), None);
+    // The struct is shorter than its fields
+    bytes[0] -= 2;
+    assert_eq!(decode::<Delimited, Old>(&bytes[..bytes.len() - 1]), None);
+}
@@ -0,0 +1,93 @@
+use const_serialize::{
+    serialize_const, serialize_hash, serialize_sha256, sha256, xxhash64, ConstWriteBuffer,
+    SerializeConst,
+};
+use sha2::Digest;
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Struct {
+    a: u32,
+    b: [u8; 3],
+    c: (i16, bool),
+}
+
+const DATA: Struct = Struct {
+    a: 0x11111111,
+    b: [1, 2, 3],
+    c: (-2, true),
+};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Deterministic bytes of every length up to the limit to cover all block boundaries
+fn inputs() -> impl Iterator<Item = Vec<u8>> {
+    (0..300).map(|len| (0..len).map(|i| (i * 31 + len) as u8).collect())
+}
+
+#[test]
+fn test_sha256_vectors() {
+    const EMPTY: [u8; 32] = sha256(b"");
+    assert_eq!(
+        hex(&EMPTY),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        hex(&sha256(b"abc")),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+    assert_eq!(
+        hex(&sha256(
+            b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq"
+        )),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+    assert_eq!(
+        hex(&sha256(b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu")),
+        "cf5b16a778af8380036ce59e7b0492370b249b11e8f07a51afac45037afee9d1"
+    );
+    assert_eq!(
+        hex(&sha256(&vec![b'a'; 1_000_000])),
+        "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0"
+    );
+
+    for input in inputs() {
+        assert_eq!(sha256(&input)[..], sha2::Sha256::digest(&input)[..]);
+    }
+}
+
+#[test]
+fn test_xxhash64_vectors() {
+    const EMPTY: u64 = xxhash64(b"", 0);
+    assert_eq!(EMPTY, 0xef46db3751d8e999);
+    assert_eq!(xxhash64(b"a", 0), 0xd24ec4f1a98c6e5b);
+    assert_eq!(xxhash64(b"abc", 0), 0x44bc2cf5ad770999);
+    assert_eq!(
+        xxhash64(b"Nobody inspects the spammish repetition", 0),
+        0xfbcea83c8a378bf1
+    );
+
+    for input in inputs() {
+        for seed in [0, 1, 0x9e3779b97f4a7c15] {
+            assert_eq!(
+                xxhash64(&input, seed),
+                xxhash_rust::xxh64::xxh64(&input, seed)
+            );
+        }
+    }
+}
+
+#[test]
+fn test_serialize_hash() {
+    const HASH: u64 = serialize_hash(&DATA);
+    const DIGEST: [u8; 32] = serialize_sha256(&DATA);
+
+    let buffer = serialize_const(&DATA, ConstWriteBuffer::new());
+    assert_eq!(HASH, xxhash_rust::xxh64::xxh64(buffer.as_ref(), 0));
+    assert_eq!(DIGEST[..], sha2::Sha256::digest(buffer.as_ref())[..]);
+
+    let other = Struct { a: 0, ..DATA };
+    assert_ne!(serialize_hash(&other), HASH);
+    assert_ne!(serialize_sha256(&other), DIGEST);
+}
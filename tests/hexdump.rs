@@ -0,0 +1,91 @@
+use const_serialize::{hexdump, serialize_const, ConstWriteBuffer, SerializeConst};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Struct {
+    a: u32,
+    b: i8,
+    c: [u16; 2],
+    d: Enum,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Enum {
+    A { one: bool, two: f64 },
+    B { one: u8 } = 15,
+}
+
+// The expected dumps are written for little endian primitives
+#[test]
+#[cfg_attr(feature = "test-big-endian", ignore)]
+fn test_hexdump() {
+    let data = Struct {
+        a: 0x11111111,
+        b: -2,
+        c: [1, 2],
+        d: Enum::A {
+            one: true,
+            two: 0.5,
+        },
+    };
+    let mut buf = ConstWriteBuffer::new();
+    buf = serialize_const(&data, buf);
+    let mut bytes = buf.as_ref().to_vec();
+    bytes.push(0xff);
+    let dump = hexdump(&bytes, &Struct::MEMORY_LAYOUT);
+    assert!(dump.error().is_none());
+    assert_eq!(dump.trailing(), &[0xff]);
+    assert_eq!(
+        dump.to_string(),
+        "\
+00000000  11 11 11 11              a        u32 286331153
+00000004  fe                       b        i8 -2
+00000005  01 00                    c[0]     u16 1
+00000007  02 00                    c[1]     u16 2
+00000009  00                       d        tag 0 (A)
+0000000a  01                       d.A.one  bool true
+0000000b  00 00 00 00 00 00 e0 3f  d.A.two  f64 0.5
+00000013  ff                       (trailing)
+"
+    );
+}
+
+// The expected dumps are written for little endian primitives
+#[test]
+#[cfg_attr(feature = "test-big-endian", ignore)]
+fn test_hexdump_marks_mismatch() {
+    let data = Struct {
+        a: 0x11111111,
+        b: -2,
+        c: [1, 2],
+        d: Enum::B { one: 3 },
+    };
+    let mut buf = ConstWriteBuffer::new();
+    buf = serialize_const(&data, buf);
+    let mut bytes = buf.as_ref().to_vec();
+    bytes[9] = 7;
+    let dump = hexdump(&bytes, &Struct::MEMORY_LAYOUT);
+    assert_eq!(dump.error().unwrap().offset, 9);
+    assert_eq!(
+        dump.to_string(),
+        "\
+00000000  11 11 11 11              a     u32 286331153
+00000004  fe                       b     i8 -2
+00000005  01 00                    c[0]  u16 1
+00000007  02 00                    c[1]  u16 2
+00000009  ^^ unknown enum variant 7 at offset 9
+00000009  07 03                    (unmatched)
+"
+    );
+
+    let dump = hexdump(&bytes[..6], &Struct::MEMORY_LAYOUT);
+    assert_eq!(
+        dump.to_string(),
+        "\
+00000000  11 11 11 11              a  u32 286331153
+00000004  fe                       b  i8 -2
+00000005  ^^ unexpected end of data at offset 5
+00000005  01                       (unmatched)
+"
+    );
+}
@@ -0,0 +1,70 @@
+use const_serialize::{include_const, include_const_raw, SerializeConst};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Config {
+    port: u16,
+    verbose: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Mode {
+    Off,
+    On { level: u16 },
+}
+
+const CONFIG: Config = include_const!(Config, "fixtures/include/config.bin");
+const BYTES: [u8; 3] = include_const!([u8; 3], "fixtures/include/config.bin");
+
+#[test]
+#[cfg_attr(feature = "test-big-endian", ignore)]
+fn test_include_const() {
+    assert_eq!(
+        CONFIG,
+        Config {
+            port: 8080,
+            verbose: true
+        }
+    );
+}
+
+#[test]
+fn test_include_const_bytes() {
+    assert_eq!(BYTES, [0x90, 0x1f, 0x01]);
+}
+
+fn include_mode(bytes: &[u8]) -> Mode {
+    include_const_raw::<Mode>(bytes, "mode.bin")
+}
+
+#[test]
+fn test_include_const_valid() {
+    assert_eq!(include_mode(&[1, 3, 3]), Mode::On { level: 0x0303 });
+    assert_eq!(include_mode(&[0]), Mode::Off);
+}
+
+#[test]
+#[should_panic(expected = "failed to deserialize \"mode.bin\" at offset 2: unexpected end of data")]
+fn test_include_const_unexpected_end() {
+    include_mode(&[1, 3]);
+}
+
+#[test]
+#[should_panic(expected = "failed to deserialize \"mode.bin\" at offset 0: unknown enum variant 7")]
+fn test_include_const_unknown_variant() {
+    include_mode(&[7, 3, 0]);
+}
+
+#[test]
+#[should_panic(
+    expected = "failed to deserialize \"mode.bin\" at offset 1: unexpected bytes after the value"
+)]
+fn test_include_const_trailing_bytes() {
+    include_mode(&[0, 1, 2]);
+}
+
+#[test]
+#[should_panic(expected = "failed to deserialize \"config.bin\" at offset 2: invalid bool 0x02")]
+fn test_include_const_invalid_bool() {
+    include_const_raw::<Config>(&[0x90, 0x1f, 0x02], "config.bin");
+}
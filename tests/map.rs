@@ -0,0 +1,90 @@
+use const_serialize::{
+    deserialize_const, serialize_const, ConstMap, ConstReadBuffer, ConstWriteBuffer, SerializeConst,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Key {
+    Name { id: u16 },
+    Index { group: i16, index: u32 },
+}
+
+const MAP: ConstMap<Key, u32, 4> = ConstMap::new([
+    (Key::Index { group: 1, index: 3 }, 30),
+    (Key::Name { id: 9 }, 90),
+    (
+        Key::Index {
+            group: -1,
+            index: 0,
+        },
+        10,
+    ),
+    (Key::Name { id: 2 }, 20),
+]);
+
+#[test]
+fn test_entries_are_sorted() {
+    let keys: Vec<_> = MAP.entries().iter().map(|(key, _)| *key).collect();
+    assert_eq!(
+        keys,
+        [
+            Key::Name { id: 2 },
+            Key::Name { id: 9 },
+            Key::Index {
+                group: -1,
+                index: 0
+            },
+            Key::Index { group: 1, index: 3 },
+        ]
+    );
+}
+
+#[test]
+fn test_get_in_const() {
+    const FOUND: Option<&u32> = MAP.get(&Key::Index {
+        group: -1,
+        index: 0,
+    });
+    const MISSING: Option<&u32> = MAP.get(&Key::Name { id: 3 });
+    assert_eq!(FOUND, Some(&10));
+    assert_eq!(MISSING, None);
+    assert!(MAP.contains_key(&Key::Name { id: 2 }));
+    for (key, value) in MAP.entries() {
+        assert_eq!(MAP.get(key), Some(value));
+    }
+    assert_eq!(MAP.len(), 4);
+}
+
+#[test]
+fn test_empty_map() {
+    const EMPTY: ConstMap<u32, u32, 0> = ConstMap::new([]);
+    assert!(EMPTY.is_empty());
+    assert_eq!(EMPTY.get(&1), None);
+}
+
+#[test]
+#[should_panic(expected = "duplicate key in ConstMap")]
+fn test_duplicate_keys_panic() {
+    ConstMap::new([(1u32, 1u8), (2, 2), (1, 3)]);
+}
+
+#[test]
+fn test_serialize_roundtrip() {
+    const BUF: ConstWriteBuffer = serialize_const(&MAP, ConstWriteBuffer::new());
+    const LOADED: ConstMap<Key, u32, 4> =
+        deserialize_const!(ConstMap<Key, u32, 4>, BUF.read()).unwrap();
+    assert_eq!(LOADED, MAP);
+    assert!(LOADED.is_sorted());
+    const FOUND: Option<&u32> = LOADED.get(&Key::Name { id: 9 });
+    assert_eq!(FOUND, Some(&90));
+}
+
+#[test]
+fn test_unsorted_data() {
+    // The keys are out of order, then duplicated
+    for data in [[2u16, 20, 1, 10], [1, 10, 1, 20]] {
+        let bytes: Vec<u8> = data.iter().flat_map(|value| value.to_le_bytes()).collect();
+        let map = deserialize_const!(ConstMap<u16, u16, 2>, ConstReadBuffer::new(&bytes)).unwrap();
+        assert!(!map.is_sorted());
+    }
+}
@@ -0,0 +1,106 @@
+use const_serialize::{
+    deserialize_memcomparable, serialize_memcomparable, ConstReadBuffer, ConstWriteBuffer,
+    SerializeConst,
+};
+use proptest::prelude::*;
+
+mod common;
+use common::{record, Kind, Record};
+
+#[derive(Clone, Copy, Debug, SerializeConst)]
+struct Floats {
+    single: f32,
+    double: f64,
+}
+
+/// Records that order the same way with `PartialOrd` as with the bits of their floats. NaN is not ordered and
+/// -0.0 is equal to 0.0, but memcomparable orders both by their bits.
+fn ordered_record() -> impl Strategy<Value = Record> {
+    record().prop_filter("floats must be ordered", |value| {
+        !value.has_nan() && value.single != 0.0 && value.double != 0.0
+    })
+}
+
+fn encode<T: SerializeConst>(value: &T) -> Vec<u8> {
+    serialize_memcomparable(value, ConstWriteBuffer::new())
+        .as_ref()
+        .to_vec()
+}
+
+fn decode<T: SerializeConst>(bytes: &[u8]) -> Option<T> {
+    let (rest, value) = deserialize_memcomparable::<T>(ConstReadBuffer::new(bytes))?;
+    assert!(rest.get().is_none());
+    Some(value)
+}
+
+proptest! {
+    #[test]
+    fn test_order_matches_ord(first in ordered_record(), second in ordered_record()) {
+        prop_assert_eq!(
+            Some(encode(&first).cmp(&encode(&second))),
+            first.partial_cmp(&second)
+        );
+    }
+
+    #[test]
+    fn test_roundtrip(value in ordered_record()) {
+        prop_assert_eq!(decode::<Record>(&encode(&value)), Some(value));
+    }
+
+    #[test]
+    fn test_float_order(first in any::<(f32, f64)>(), second in any::<(f32, f64)>()) {
+        let first = Floats { single: first.0, double: first.1 };
+        let second = Floats { single: second.0, double: second.1 };
+        let expected = first
+            .single
+            .total_cmp(&second.single)
+            .then(first.double.total_cmp(&second.double));
+        prop_assert_eq!(encode(&first).cmp(&encode(&second)), expected);
+
+        let decoded = decode::<Floats>(&encode(&first)).unwrap();
+        prop_assert_eq!(decoded.single.to_bits(), first.single.to_bits());
+        prop_assert_eq!(decoded.double.to_bits(), first.double.to_bits());
+    }
+}
+
+#[test]
+fn test_encoding() {
+    const RECORD: Record = Record {
+        byte: 1,
+        signed_byte: -1,
+        short: 0x0102,
+        signed_short: -2,
+        int: 3,
+        signed_int: -3,
+        long: 4,
+        signed_long: -4,
+        flag: true,
+        single: 1.0,
+        double: -1.0,
+        list: [5, 6, 7],
+        pair: (-5, 8),
+        kinds: [Kind::Empty, Kind::Small { value: 9 }],
+    };
+    const BUF: ConstWriteBuffer = serialize_memcomparable(&RECORD, ConstWriteBuffer::new());
+    assert_eq!(
+        BUF.as_ref(),
+        [
+            1, 0x7f, 1, 2, 0x7f, 0xfe, 0, 0, 0, 3, 0x7f, 0xff, 0xff, 0xfd, 0, 0, 0, 0, 0, 0, 0, 4,
+            0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfc, 1, 0xbf, 0x80, 0, 0, 0x40, 0x0f, 0xff,
+            0xff, 0xff, 0xff, 0xff, 0xff, 0, 5, 0, 6, 0, 7, 0x7f, 0xff, 0xff, 0xfb, 8, 0, 0, 0, 1,
+            0, 9
+        ]
+    );
+    const DECODED: Option<(ConstReadBuffer, Record)> = deserialize_memcomparable(BUF.read());
+    assert_eq!(DECODED.unwrap().1, RECORD);
+}
+
+#[test]
+fn test_invalid_data() {
+    let mut bytes = encode(&(true, 5u16));
+    assert_eq!(decode::<(bool, u16)>(&bytes), Some((true, 5)));
+    assert_eq!(decode::<(bool, u16)>(&bytes[..2]), None);
+    bytes[0] = 2;
+    assert_eq!(decode::<(bool, u16)>(&bytes), None);
+    assert_eq!(decode::<Kind>(&[0, 3]), None);
+}
@@ -0,0 +1,255 @@
+use const_serialize::{
+    deserialize_const, deserialize_field, deserialize_field_raw, field_offset, patch_field,
+    patch_field_raw, serialize_const, serialized_offset, ConstReadBuffer, ConstWriteBuffer,
+    EnumEncoding, EnumVariant, Layout, PatchError, PathSegment, PlainOldData, PrimitiveEncoding,
+    SerializeConst, StructEncoding,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Config {
+    id: u32,
+    limits: [Limits; 3],
+    mode: Mode,
+    modes: [Mode; 2],
+    last: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Limits {
+    min: u16,
+    max: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Mode {
+    Off,
+    On { level: u16, limits: Limits },
+}
+
+const CONFIG: Config = Config {
+    id: 7,
+    limits: [
+        Limits { min: 1, max: 2 },
+        Limits { min: 3, max: 4 },
+        Limits { min: 5, max: 6 },
+    ],
+    mode: Mode::On {
+        level: 9,
+        limits: Limits { min: 10, max: 11 },
+    },
+    modes: [
+        Mode::Off,
+        Mode::On {
+            level: 12,
+            limits: Limits { min: 13, max: 14 },
+        },
+    ],
+    last: 15,
+};
+
+const DATA: ConstWriteBuffer = serialize_const(&CONFIG, ConstWriteBuffer::new());
+
+#[test]
+fn test_serialized_offset() {
+    assert_eq!(serialized_offset!(Config, id), 0);
+    assert_eq!(serialized_offset!(Config, limits), 4);
+    assert_eq!(serialized_offset!(Config, limits[2]), 4 + 2 * 4);
+    assert_eq!(serialized_offset!(Config, limits[1].max), 4 + 4 + 2);
+    assert_eq!(serialized_offset!(Config, mode), 16);
+    assert_eq!(serialized_offset!(Config, mode.On.level), 17);
+    assert_eq!(serialized_offset!(Config, mode.On.limits.max), 21);
+
+    if !cfg!(feature = "test-big-endian") {
+        let data = DATA.as_ref();
+        let offset = serialized_offset!(Config, mode.On.limits.max);
+        assert_eq!(data[offset..offset + 2], 11u16.to_le_bytes());
+    }
+}
+
+#[test]
+fn test_deserialize_field() {
+    let id: Option<u32> = deserialize_field!(Config, id, DATA.read());
+    assert_eq!(id, Some(7));
+    let limits: Option<Limits> = deserialize_field!(Config, limits[2], DATA.read());
+    assert_eq!(limits, Some(Limits { min: 5, max: 6 }));
+    let mode: Option<Mode> = deserialize_field!(Config, mode, DATA.read());
+    assert_eq!(mode, Some(CONFIG.mode));
+    let level: Option<u16> = deserialize_field!(Config, mode.On.level, DATA.read());
+    assert_eq!(level, Some(9));
+    // Items before the index are skipped one by one if they can have different lengths
+    let max: Option<u16> = deserialize_field!(Config, modes[1].On.limits.max, DATA.read());
+    assert_eq!(max, Some(14));
+    // Fields after enums are found in the data
+    let last: Option<u16> = deserialize_field!(Config, last, DATA.read());
+    assert_eq!(last, Some(15));
+}
+
+#[test]
+fn test_deserialize_field_in_const() {
+    const LEVEL: Option<u16> = deserialize_field!(Config, mode.On.level, DATA.read());
+    assert_eq!(LEVEL, Some(9));
+}
+
+#[test]
+fn test_deserialize_field_errors() {
+    // The path names a different variant than the data holds
+    let level: Option<u16> = deserialize_field!(Config, modes[0].On.level, DATA.read());
+    assert_eq!(level, None);
+
+    let data = DATA.as_ref();
+    let truncated = ConstReadBuffer::new(&data[..data.len() - 1]);
+    let last: Option<u16> = deserialize_field!(Config, last, truncated);
+    assert_eq!(last, None);
+    // Fields before the end of the data can still be read
+    let truncated = ConstReadBuffer::new(&data[..data.len() - 1]);
+    let id: Option<u32> = deserialize_field!(Config, id, truncated);
+    assert_eq!(id, Some(7));
+}
+
+#[test]
+#[should_panic(expected = "no field named `nope`")]
+fn test_unknown_field() {
+    let _ = field_offset(&Config::MEMORY_LAYOUT, &[PathSegment::Name("nope")]);
+}
+
+#[test]
+#[should_panic(expected = "the offset depends on the variant of an enum before `last`")]
+fn test_offset_after_enum() {
+    let _ = field_offset(&Config::MEMORY_LAYOUT, &[PathSegment::Name("last")]);
+}
+
+#[test]
+#[should_panic(expected = "the type doesn't match the layout of the field")]
+fn test_field_type_mismatch() {
+    let _: Option<u32> = deserialize_field!(Config, last, DATA.read());
+}
+
+#[test]
+fn test_patch_field() {
+    let mut data = DATA.as_ref().to_vec();
+    assert_eq!(patch_field!(Config, id, &mut data[..], 8u32), Ok(()));
+    assert_eq!(
+        patch_field!(
+            Config,
+            limits[1],
+            &mut data[..],
+            Limits { min: 30, max: 40 }
+        ),
+        Ok(())
+    );
+    assert_eq!(
+        patch_field!(Config, modes[1].On.level, &mut data[..], 20u16),
+        Ok(())
+    );
+    // A variant with the same length can replace the old variant
+    let mode = Mode::On {
+        level: 1,
+        limits: Limits { min: 2, max: 3 },
+    };
+    assert_eq!(patch_field!(Config, mode, &mut data[..], mode), Ok(()));
+
+    let expected = Config {
+        id: 8,
+        limits: [
+            Limits { min: 1, max: 2 },
+            Limits { min: 30, max: 40 },
+            Limits { min: 5, max: 6 },
+        ],
+        mode,
+        modes: [
+            Mode::Off,
+            Mode::On {
+                level: 20,
+                limits: Limits { min: 13, max: 14 },
+            },
+        ],
+        last: 15,
+    };
+    assert_eq!(
+        deserialize_const!(Config, ConstReadBuffer::new(&data)),
+        Some(expected)
+    );
+}
+
+#[test]
+fn test_patch_field_in_const() {
+    const PATCHED: ConstWriteBuffer = {
+        let mut data = serialize_const(&CONFIG, ConstWriteBuffer::new());
+        match patch_field!(Config, last, &mut data, 16u16) {
+            Ok(()) => data,
+            Err(_) => panic!("failed to patch the data"),
+        }
+    };
+    let last: Option<u16> = deserialize_field!(Config, last, PATCHED.read());
+    assert_eq!(last, Some(16));
+
+    let mut array = [0; 4];
+    assert_eq!(patch_field!([u16; 2], [1], &mut array, 7u16), Ok(()));
+    assert_eq!(
+        deserialize_const!([u16; 2], ConstReadBuffer::new(&array)),
+        Some([0, 7])
+    );
+}
+
+#[test]
+fn test_patch_field_errors() {
+    let mut data = DATA.as_ref().to_vec();
+    // Off has no payload, so it would shrink the data
+    assert_eq!(
+        patch_field!(Config, mode, &mut data[..], Mode::Off),
+        Err(PatchError::LengthChanged {
+            expected: 7,
+            found: 1
+        })
+    );
+    assert_eq!(
+        patch_field!(Config, modes[0].On.level, &mut data[..], 1u16),
+        Err(PatchError::VariantMismatch)
+    );
+    assert_eq!(data, DATA.as_ref());
+
+    let len = data.len();
+    assert_eq!(
+        patch_field!(Config, last, &mut data[..len - 1], 1u16),
+        Err(PatchError::InvalidData)
+    );
+}
+
+#[test]
+fn test_wide_discriminant() {
+    // An enum with an 8 byte discriminant and one variant that holds a u16
+    const WIDE: Layout = Layout::Enum(EnumEncoding::new(
+        16,
+        PrimitiveEncoding::new(8),
+        &[EnumVariant::new(
+            1,
+            StructEncoding::new(
+                2,
+                &[PlainOldData::new(0, u16::MEMORY_LAYOUT).with_name("value")],
+            )
+            .with_name("Some"),
+            2,
+        )],
+    ));
+    let path = [PathSegment::Name("Some"), PathSegment::Name("value")];
+
+    let mut data = [1, 0, 0, 0, 0, 0, 0, 0, 0x12, 0x12];
+    assert_eq!(
+        deserialize_field_raw::<u16>(ConstReadBuffer::new(&data), &WIDE, &path),
+        Some(0x1212)
+    );
+    assert_eq!(patch_field_raw(&mut data, &WIDE, &path, &0x3434u16), Ok(()));
+    assert_eq!(data[8..], [0x34, 0x34]);
+
+    // The high bytes of the discriminant are part of the tag
+    let mut data = [1, 0, 0, 0, 0, 0, 0, 1, 0x12, 0x12];
+    assert_eq!(
+        deserialize_field_raw::<u16>(ConstReadBuffer::new(&data), &WIDE, &path),
+        None
+    );
+    assert_eq!(
+        patch_field_raw(&mut data, &WIDE, &path, &0x3434u16),
+        Err(PatchError::VariantMismatch)
+    );
+}
@@ -0,0 +1,168 @@
+use const_serialize::{
+    serialize_layout, ConstWriteBuffer, DynLayout, Layout, SerializeConst, SCHEMA_VERSION,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Struct {
+    a: u32,
+    b: [i8; 3],
+    c: Enum,
+    d: (f32, bool),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u16)]
+#[allow(dead_code)]
+enum Enum {
+    A { one: u32, two: u16 },
+    B { one: u8 } = 300,
+    C,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Tagged {
+    #[const_serialize(id = 7)]
+    a: u32,
+    #[const_serialize(id = 200, default = [1, -2, 3])]
+    b: [i8; 3],
+    #[const_serialize(id = 1, default = Enum::B { one: 9 })]
+    c: Enum,
+}
+
+fn roundtrip(layout: &Layout) -> Option<DynLayout> {
+    let schema = serialize_layout(layout, ConstWriteBuffer::new());
+    DynLayout::from_schema(schema.as_ref())
+}
+
+#[test]
+fn test_schema_roundtrip() {
+    const SCHEMA: ConstWriteBuffer =
+        serialize_layout(&Struct::MEMORY_LAYOUT, ConstWriteBuffer::new());
+    assert_eq!(SCHEMA.as_ref()[0], SCHEMA_VERSION);
+    let layout = DynLayout::from_schema(SCHEMA.as_ref()).unwrap();
+    assert_eq!(layout, DynLayout::from(Struct::MEMORY_LAYOUT));
+    assert_eq!(layout.fingerprint(), Struct::MEMORY_LAYOUT.fingerprint());
+
+    assert_eq!(
+        roundtrip(&Enum::MEMORY_LAYOUT),
+        Some(DynLayout::from(Enum::MEMORY_LAYOUT))
+    );
+    assert_eq!(
+        roundtrip(&<[u64; 200]>::MEMORY_LAYOUT),
+        Some(DynLayout::from(<[u64; 200]>::MEMORY_LAYOUT))
+    );
+}
+
+#[test]
+fn test_invalid_schema() {
+    let schema = serialize_layout(&Struct::MEMORY_LAYOUT, ConstWriteBuffer::new());
+    let schema = schema.as_ref();
+    assert_eq!(DynLayout::from_schema(&schema[..schema.len() - 1]), None);
+
+    let mut trailing = schema.to_vec();
+    trailing.push(0);
+    assert_eq!(DynLayout::from_schema(&trailing), None);
+
+    assert_eq!(DynLayout::from_schema(b"X"), None);
+    // A primitive with an unknown kind
+    assert_eq!(DynLayout::from_schema(&[b'P', 4, 9]), None);
+    // A default that isn't a value of the field
+    let field = |default: &[u8]| {
+        let mut schema = vec![SCHEMA_VERSION, b'S', 1, 0, 1, 0, 0, 0];
+        schema.push(default.len() as u8 + 1);
+        schema.extend_from_slice(default);
+        schema.extend_from_slice(&[b'P', 1, 3]);
+        DynLayout::from_schema(&schema)
+    };
+    assert!(field(&[1]).is_some());
+    assert_eq!(field(&[2]), None);
+    assert_eq!(field(&[1, 0]), None);
+}
+
+#[test]
+fn test_malformed_schema() {
+    // A field with a default that starts after the end of its struct
+    assert_eq!(
+        DynLayout::from_schema(&[
+            2, b'S', 0, 0, 1, 0, 0, 0, 2, 0, b'S', 0, 0, 1, 5, 0, 0, 0, b'P', 1, 0
+        ]),
+        None
+    );
+    // A list with a size that doesn't fit in a usize
+    let mut huge_list = vec![SCHEMA_VERSION, b'S', 0, 0, 1, 0, 0, 0, 1, b'L'];
+    huge_list.extend_from_slice(&[0xff; 9]);
+    huge_list.extend_from_slice(&[0x01, b'P', 4, 0]);
+    assert_eq!(DynLayout::from_schema(&huge_list), None);
+    // A field that doesn't fit in its struct
+    assert_eq!(
+        DynLayout::from_schema(&[SCHEMA_VERSION, b'S', 2, 0, 1, 1, 0, 0, 0, b'P', 4, 0]),
+        None
+    );
+    // Layouts nested too deeply to read without overflowing the stack
+    let mut nested = vec![SCHEMA_VERSION];
+    for _ in 0..100_000 {
+        nested.extend_from_slice(&[b'L', 1]);
+    }
+    nested.extend_from_slice(&[b'P', 1, 0]);
+    assert_eq!(DynLayout::from_schema(&nested), None);
+}
+
+#[test]
+fn test_truncated_and_corrupt_schema() {
+    for layout in [Struct::MEMORY_LAYOUT, Tagged::MEMORY_LAYOUT] {
+        let schema = serialize_layout(&layout, ConstWriteBuffer::new());
+        let schema = schema.as_ref();
+        for len in 0..schema.len() {
+            assert_eq!(DynLayout::from_schema(&schema[..len]), None);
+        }
+        // Changing any byte either gives a valid layout or none, but never panics
+        for i in 0..schema.len() {
+            for byte in [0, 1, 2, 0x7f, 0x80, 0xff] {
+                let mut corrupt = schema.to_vec();
+                corrupt[i] = byte;
+                if let Some(layout) = DynLayout::from_schema(&corrupt) {
+                    assert_eq!(layout.validate(), Ok(()));
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_schema_field_ids() {
+    const SCHEMA: ConstWriteBuffer =
+        serialize_layout(&Tagged::MEMORY_LAYOUT, ConstWriteBuffer::new());
+    let layout = DynLayout::from_schema(SCHEMA.as_ref()).unwrap();
+    assert_eq!(layout, DynLayout::from(Tagged::MEMORY_LAYOUT));
+    assert_eq!(layout.fingerprint(), Tagged::MEMORY_LAYOUT.fingerprint());
+    assert_eq!(
+        DynLayout::from(layout.into_layout()),
+        DynLayout::from(Tagged::MEMORY_LAYOUT)
+    );
+}
+
+#[test]
+fn test_schema_version_1() {
+    // Schemas written before field ids start with the tag of the layout
+    assert_eq!(
+        DynLayout::from_schema(&[b'L', 3, b'P', 2, 1]),
+        Some(DynLayout::from(<[i16; 3]>::MEMORY_LAYOUT))
+    );
+    // struct Point { x: u8 } without field ids or defaults
+    let version_1 = [
+        b'S', 1, 6, b'P', b'o', b'i', b'n', b't', 1, 0, 2, b'x', b'P', 1, 0,
+    ];
+    let layout = DynLayout::from_schema(&version_1).unwrap();
+    let version_2 = [
+        &[SCHEMA_VERSION][..],
+        &version_1[..12],
+        &[0, 0],
+        &version_1[12..],
+    ]
+    .concat();
+    assert_eq!(DynLayout::from_schema(&version_2), Some(layout));
+    assert_eq!(
+        DynLayout::from_schema(&[SCHEMA_VERSION + 1, b'P', 1, 0]),
+        None
+    );
+}
@@ -0,0 +1,79 @@
+use const_serialize::{
+    deserialize_const, serialize_const, serialized_len_at, skip_value, ConstReadBuffer,
+    ConstWriteBuffer, SerializeConst,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Shape {
+    Point,
+    Circle { radius: u32 },
+    Rect { width: u32, height: u32 },
+}
+
+const SHAPES: [Shape; 4] = [
+    Shape::Rect {
+        width: 1,
+        height: 2,
+    },
+    Shape::Point,
+    Shape::Circle { radius: 3 },
+    Shape::Point,
+];
+
+const fn serialize_shapes() -> ConstWriteBuffer {
+    let mut buffer = ConstWriteBuffer::new();
+    let mut i = 0;
+    while i < SHAPES.len() {
+        buffer = serialize_const(&SHAPES[i], buffer);
+        i += 1;
+    }
+    buffer
+}
+
+const DATA: ConstWriteBuffer = serialize_shapes();
+
+#[test]
+fn test_serialized_len_at() {
+    let mut buffer = DATA.read();
+    let mut lengths = Vec::new();
+    while let Some(len) = serialized_len_at(&buffer, &Shape::MEMORY_LAYOUT) {
+        lengths.push(len);
+        buffer = skip_value(buffer, &Shape::MEMORY_LAYOUT).unwrap();
+    }
+    assert_eq!(lengths, [9, 1, 5, 1]);
+}
+
+#[test]
+fn test_skip_value() {
+    const THIRD: Option<Shape> = {
+        let buffer = DATA.read();
+        let buffer = match skip_value(buffer, &Shape::MEMORY_LAYOUT) {
+            Some(buffer) => buffer,
+            None => panic!("invalid shape"),
+        };
+        let buffer = match skip_value(buffer, &Shape::MEMORY_LAYOUT) {
+            Some(buffer) => buffer,
+            None => panic!("invalid shape"),
+        };
+        deserialize_const!(Shape, buffer)
+    };
+    assert_eq!(THIRD, Some(Shape::Circle { radius: 3 }));
+
+    // Skipping a list skips every item
+    let buffer = skip_value(DATA.read(), &<[Shape; 3]>::MEMORY_LAYOUT).unwrap();
+    assert_eq!(deserialize_const!(Shape, buffer), Some(Shape::Point));
+}
+
+#[test]
+fn test_skip_invalid_value() {
+    let data = DATA.as_ref();
+    // The buffer ends in the middle of the first value
+    let buffer = ConstReadBuffer::new(&data[..5]);
+    assert_eq!(serialized_len_at(&buffer, &Shape::MEMORY_LAYOUT), None);
+    assert!(skip_value(buffer, &Shape::MEMORY_LAYOUT).is_none());
+
+    // Unknown discriminant
+    let buffer = ConstReadBuffer::new(&[7, 0, 0, 0, 0]);
+    assert_eq!(serialized_len_at(&buffer, &Shape::MEMORY_LAYOUT), None);
+}
@@ -0,0 +1,98 @@
+use const_serialize::{
+    serialize_const, ConstReadBuffer, ConstWriteBuffer, SerializeConst, SerializedSlice,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Record {
+    id: u16,
+    value: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Event {
+    Tick,
+    Move { x: u32, y: u32 },
+}
+
+const fn records() -> [Record; 50] {
+    let mut records = [Record { id: 0, value: 0 }; 50];
+    let mut i = 0;
+    while i < records.len() {
+        records[i] = Record {
+            id: i as u16,
+            value: i as u32 * 10,
+        };
+        i += 1;
+    }
+    records
+}
+
+const RECORDS: [Record; 50] = records();
+const RECORD_DATA: ConstWriteBuffer = serialize_const(&RECORDS, ConstWriteBuffer::new());
+
+const EVENTS: [Event; 4] = [
+    Event::Move { x: 1, y: 2 },
+    Event::Tick,
+    Event::Move { x: 3, y: 4 },
+    Event::Tick,
+];
+const EVENT_DATA: ConstWriteBuffer = serialize_const(&EVENTS, ConstWriteBuffer::new());
+
+#[test]
+fn test_next() {
+    let mut records = SerializedSlice::<Record>::new(RECORD_DATA.read(), RECORDS.len());
+    let mut read = Vec::new();
+    while let Some((rest, record)) = records.next() {
+        assert_eq!(rest.len(), RECORDS.len() - read.len() - 1);
+        read.push(record);
+        records = rest;
+    }
+    assert!(records.is_empty());
+    assert_eq!(read, RECORDS);
+
+    let mut events = SerializedSlice::<Event>::new(EVENT_DATA.read(), EVENTS.len());
+    let mut read = Vec::new();
+    while let Some((rest, event)) = events.next() {
+        read.push(event);
+        events = rest;
+    }
+    assert_eq!(read, EVENTS);
+}
+
+#[test]
+fn test_nth() {
+    const RECORD: Option<Record> =
+        match SerializedSlice::<Record>::new(RECORD_DATA.read(), 50).nth(42) {
+            Some((_, record)) => Some(record),
+            None => None,
+        };
+    assert_eq!(RECORD, Some(RECORDS[42]));
+
+    let records = SerializedSlice::<Record>::new(RECORD_DATA.read(), RECORDS.len());
+    let (records, record) = records.nth(10).unwrap();
+    assert_eq!(record, RECORDS[10]);
+    assert_eq!(records.len(), 39);
+    let (records, record) = records.nth(0).unwrap();
+    assert_eq!(record, RECORDS[11]);
+    assert!(records.nth(38).is_none());
+
+    // Items with different lengths are skipped one at a time
+    let events = SerializedSlice::<Event>::new(EVENT_DATA.read(), EVENTS.len());
+    let (events, event) = events.nth(2).unwrap();
+    assert_eq!(event, EVENTS[2]);
+    assert_eq!(events.nth(0).unwrap().1, EVENTS[3]);
+}
+
+#[test]
+fn test_invalid_items() {
+    let data = RECORD_DATA.as_ref();
+    // The last item is cut off
+    let records = SerializedSlice::<Record>::new(ConstReadBuffer::new(&data[..20]), 4);
+    assert_eq!(records.nth(2).unwrap().1, RECORDS[2]);
+    let records = SerializedSlice::<Record>::new(ConstReadBuffer::new(&data[..20]), 4);
+    assert!(records.nth(3).is_none());
+
+    let events = SerializedSlice::<Event>::new(ConstReadBuffer::new(&[5]), 1);
+    assert!(events.next().is_none());
+}
@@ -0,0 +1,189 @@
+use const_serialize::{
+    deserialize_tagged, serialize_tagged, ConstReadBuffer, ConstWriteBuffer, PlainOldData,
+    SerializeConst,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct ConfigV1 {
+    #[const_serialize(id = 1)]
+    port: u16,
+    #[const_serialize(id = 2)]
+    mode: Mode,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct ConfigV2 {
+    #[const_serialize(id = 2)]
+    mode: Mode,
+    #[const_serialize(id = 3, default = [1, 2])]
+    limits: [u32; 2],
+    #[const_serialize(id = 1)]
+    port: u16,
+    #[const_serialize(id = 4, default = Point { x: -1, y: 1 })]
+    origin: Point,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Point {
+    x: i16,
+    y: i16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Mode {
+    Off,
+    On {
+        #[const_serialize(id = 5, default = 3)]
+        level: u16,
+    },
+}
+
+fn encode<T: SerializeConst>(value: &T) -> Vec<u8> {
+    serialize_tagged(value, ConstWriteBuffer::new())
+        .as_ref()
+        .to_vec()
+}
+
+fn decode<T: SerializeConst>(bytes: &[u8]) -> Option<T> {
+    let (rest, value) = deserialize_tagged::<T>(ConstReadBuffer::new(bytes))?;
+    assert!(rest.get().is_none());
+    Some(value)
+}
+
+#[test]
+fn test_encoding() {
+    const CONFIG: ConfigV1 = ConfigV1 {
+        port: 0x1f90,
+        mode: Mode::On { level: 2 },
+    };
+    const BUF: ConstWriteBuffer = serialize_tagged(&CONFIG, ConstWriteBuffer::new());
+    let expected = [
+        // Two fields
+        &[2][..],
+        // The id, length and value of port
+        &[1, 2, 0x90, 0x1f],
+        // The id and length of mode, then the variant and its one field
+        &[2, 6, 1, 1, 5, 2, 2, 0],
+    ]
+    .concat();
+    assert_eq!(BUF.as_ref(), expected);
+    const DECODED: Option<(ConstReadBuffer, ConfigV1)> = deserialize_tagged(BUF.read());
+    assert_eq!(DECODED.unwrap().1, CONFIG);
+}
+
+#[test]
+fn test_missing_fields_use_defaults() {
+    const OLD: ConstWriteBuffer = serialize_tagged(
+        &ConfigV1 {
+            port: 80,
+            mode: Mode::Off,
+        },
+        ConstWriteBuffer::new(),
+    );
+    const NEW: Option<(ConstReadBuffer, ConfigV2)> = deserialize_tagged(OLD.read());
+    assert_eq!(
+        NEW.unwrap().1,
+        ConfigV2 {
+            mode: Mode::Off,
+            limits: [1, 2],
+            port: 80,
+            origin: Point { x: -1, y: 1 },
+        }
+    );
+
+    // Fields of enum variants can have defaults too
+    assert_eq!(decode::<Mode>(&[1, 0]), Some(Mode::On { level: 3 }));
+}
+
+#[test]
+fn test_unknown_fields_are_skipped() {
+    let new = ConfigV2 {
+        mode: Mode::On { level: 9 },
+        limits: [5, 6],
+        port: 443,
+        origin: Point { x: 3, y: 4 },
+    };
+    let bytes = encode(&new);
+    assert_eq!(decode::<ConfigV2>(&bytes), Some(new));
+    assert_eq!(
+        decode::<ConfigV1>(&bytes),
+        Some(ConfigV1 {
+            port: 443,
+            mode: Mode::On { level: 9 },
+        })
+    );
+}
+
+#[test]
+fn test_missing_field_without_default() {
+    // Only the port field
+    assert_eq!(decode::<ConfigV1>(&[1, 1, 2, 80, 0]), None);
+    assert!(decode::<ConfigV1>(&[2, 1, 2, 80, 0, 2, 2, 0, 0]).is_some());
+    // Fields without ids use their position
+    assert_eq!(decode::<Point>(&[1, 1, 2, 1, 0]), None);
+    assert_eq!(
+        decode::<Point>(&[2, 1, 2, 2, 0, 0, 2, 1, 0]),
+        Some(Point { x: 1, y: 2 })
+    );
+}
+
+#[test]
+fn test_invalid_data() {
+    let bytes = encode(&Point { x: 1, y: 2 });
+    // A field is longer than the buffer
+    assert_eq!(decode::<Point>(&bytes[..bytes.len() - 1]), None);
+    // A field is shorter than its value
+    assert_eq!(decode::<Point>(&[2, 0, 1, 1, 1, 2, 0]), None);
+}
+
+#[test]
+fn test_default_with_matching_layout() {
+    let field = PlainOldData::new(0, Point::MEMORY_LAYOUT);
+    let _ = field.with_default(&Point { x: 1, y: 2 });
+}
+
+#[test]
+#[should_panic(expected = "the default value doesn't match the layout of the field")]
+fn test_default_with_different_layout() {
+    // An i32 has the same size as an f32, but a different layout
+    let _ = PlainOldData::new(0, f32::MEMORY_LAYOUT).with_default(&1i32);
+}
+
+trait Zero: SerializeConst {
+    const ZERO: Self;
+}
+
+impl Zero for u16 {
+    const ZERO: Self = 0;
+}
+
+impl Zero for u64 {
+    const ZERO: Self = 0;
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Range<T: Zero> {
+    #[const_serialize(id = 1, default = T::ZERO)]
+    start: T,
+    #[const_serialize(id = 2)]
+    end: T,
+}
+
+#[test]
+fn test_generic_default() {
+    // Only the end is in the data, the start is filled in from the default of the type
+    assert_eq!(
+        decode::<Range<u16>>(&[1, 2, 2, 9, 0]),
+        Some(Range { start: 0, end: 9 })
+    );
+    assert_eq!(
+        decode::<Range<u64>>(&[1, 2, 8, 9, 0, 0, 0, 0, 0, 0, 0]),
+        Some(Range { start: 0, end: 9 })
+    );
+    let range = Range {
+        start: 3u16,
+        end: 5,
+    };
+    assert_eq!(decode::<Range<u16>>(&encode(&range)), Some(range));
+}
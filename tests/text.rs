@@ -0,0 +1,150 @@
+use const_serialize::{
+    base64_decoded_len, decode_base64, decode_hex, deserialize_const, encode_base64, encode_hex,
+    hex_decoded_len, serialize_const, ConstReadBuffer, ConstStr, ConstWriteBuffer, SerializeConst,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Config {
+    port: u16,
+    retries: u8,
+    ratio: f32,
+}
+
+const CONFIG: Config = Config {
+    port: 8080,
+    retries: 3,
+    ratio: 0.5,
+};
+
+const SERIALIZED: ConstWriteBuffer = serialize_const(&CONFIG, ConstWriteBuffer::new());
+
+fn buffer(bytes: &[u8]) -> ConstWriteBuffer {
+    bytes
+        .iter()
+        .fold(ConstWriteBuffer::new(), |buffer, byte| buffer.push(*byte))
+}
+
+/// Encode N bytes and decode them again with every encoding
+fn roundtrip<const N: usize>() {
+    let bytes: [u8; N] = std::array::from_fn(|i| (i * 37 + 11) as u8);
+    let input = buffer(&bytes);
+    let hex = encode_hex(&input);
+    assert_eq!(hex_decoded_len(hex.as_str()), N);
+    assert_eq!(decode_hex::<N>(hex.as_str()), bytes);
+    let base64 = encode_base64(&input);
+    assert_eq!(base64_decoded_len(base64.as_str()), N);
+    assert_eq!(decode_base64::<N>(base64.as_str()), bytes);
+    let unpadded = base64.as_str().trim_end_matches('=');
+    assert_eq!(decode_base64::<N>(unpadded), bytes);
+}
+
+#[test]
+fn test_encode_vectors() {
+    // RFC 4648 test vectors
+    for (input, hex, base64) in [
+        ("", "", ""),
+        ("f", "66", "Zg=="),
+        ("fo", "666f", "Zm8="),
+        ("foo", "666f6f", "Zm9v"),
+        ("foob", "666f6f62", "Zm9vYg=="),
+        ("fooba", "666f6f6261", "Zm9vYmE="),
+        ("foobar", "666f6f626172", "Zm9vYmFy"),
+    ] {
+        let input = buffer(input.as_bytes());
+        assert_eq!(encode_hex(&input).as_str(), hex);
+        assert_eq!(encode_base64(&input).as_str(), base64);
+    }
+    assert_eq!(encode_hex(&buffer(&[0x00, 0xab, 0xff])).as_str(), "00abff");
+    assert_eq!(encode_base64(&buffer(&[0xfb, 0xff])).as_str(), "+/8=");
+}
+
+#[test]
+fn test_decode_vectors() {
+    const EMPTY: ConstReadBuffer = decode_hex!("");
+    assert_eq!(EMPTY.as_ref(), b"");
+    const HEX: ConstReadBuffer = decode_hex!("666F6f626172");
+    assert_eq!(HEX.as_ref(), b"foobar");
+    const PADDED: ConstReadBuffer = decode_base64!("Zm9vYg==");
+    assert_eq!(PADDED.as_ref(), b"foob");
+    const UNPADDED: ConstReadBuffer = decode_base64!("Zm9vYmE");
+    assert_eq!(UNPADDED.as_ref(), b"fooba");
+    const SYMBOLS: ConstReadBuffer = decode_base64!("+/8=");
+    assert_eq!(SYMBOLS.as_ref(), [0xfb, 0xff]);
+}
+
+#[test]
+fn test_text_roundtrip() {
+    const HEX: ConstStr = encode_hex(&SERIALIZED);
+    const BASE64: ConstStr = encode_base64(&SERIALIZED);
+    assert_eq!(HEX.len(), SERIALIZED.as_ref().len() * 2);
+
+    // What a build script would put in an environment variable for `env!`
+    const HEX_CONFIG: &str = "901f030000003f";
+    const BASE64_CONFIG: &str = "kB8DAAAAPw==";
+    if cfg!(not(feature = "test-big-endian")) {
+        assert_eq!(HEX.as_str(), HEX_CONFIG);
+        assert_eq!(BASE64.as_str(), BASE64_CONFIG);
+    }
+
+    const FROM_HEX: Option<Config> = deserialize_const!(Config, decode_hex!(HEX_CONFIG));
+    const FROM_BASE64: Option<Config> = deserialize_const!(Config, decode_base64!(BASE64_CONFIG));
+    if cfg!(not(feature = "test-big-endian")) {
+        assert_eq!(FROM_HEX, Some(CONFIG));
+        assert_eq!(FROM_BASE64, Some(CONFIG));
+    }
+
+    roundtrip::<0>();
+    roundtrip::<1>();
+    roundtrip::<2>();
+    roundtrip::<3>();
+    roundtrip::<4>();
+    roundtrip::<5>();
+    roundtrip::<64>();
+}
+
+#[test]
+#[should_panic(expected = "invalid hex digit")]
+fn test_invalid_hex() {
+    decode_hex::<2>("2a0g");
+}
+
+#[test]
+#[should_panic(expected = "non-zero padding bits")]
+fn test_invalid_base64_padding_bits() {
+    decode_base64::<1>("Zh==");
+}
+
+#[test]
+fn test_decode_whitespace() {
+    // Values read from a file or a command usually end with a newline
+    const HEX: ConstReadBuffer = decode_hex!("666f 6f62\n6172\n");
+    assert_eq!(HEX.as_ref(), b"foobar");
+    const BASE64: ConstReadBuffer = decode_base64!("Zm9v\r\nYg==\n");
+    assert_eq!(BASE64.as_ref(), b"foob");
+    assert_eq!(hex_decoded_len(" \t2a\n"), 1);
+    assert_eq!(base64_decoded_len("Zm9vYmE\n"), 5);
+}
+
+#[test]
+fn test_encode_capacity() {
+    let hex = encode_hex(&buffer(&[0xab; ConstStr::CAPACITY / 2]));
+    assert_eq!(hex.len(), ConstStr::CAPACITY);
+    let base64 = encode_base64(&buffer(&[0xab; ConstStr::CAPACITY / 4 * 3]));
+    assert_eq!(base64.len(), ConstStr::CAPACITY);
+}
+
+#[test]
+#[should_panic(
+    expected = "encoding 257 bytes as hex takes 514 characters, but a ConstStr can hold at most 512"
+)]
+fn test_encode_hex_too_long() {
+    let _ = encode_hex(&buffer(&[0; 257]));
+}
+
+#[test]
+#[should_panic(
+    expected = "encoding 385 bytes as base64 takes 516 characters, but a ConstStr can hold at most 512"
+)]
+fn test_encode_base64_too_long() {
+    let _ = encode_base64(&buffer(&[0; 385]));
+}
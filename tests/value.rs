@@ -0,0 +1,306 @@
+use const_serialize::{
+    decode_value, deserialize_const, encode_value, serialize_const, ConstReadBuffer,
+    ConstWriteBuffer, DecodeError, DecodeErrorKind, EncodeError, EncodeErrorKind, EnumValue,
+    FieldValue, Primitive, SerializeConst, StructValue, Value,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Struct {
+    a: u32,
+    b: i8,
+    c: [f32; 2],
+    d: Enum,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Enum {
+    A { one: bool, two: i16 },
+    B { one: u8 } = 15,
+}
+
+const DATA: Struct = Struct {
+    a: 0x11111111,
+    b: -2,
+    c: [0.5, -1.0],
+    d: Enum::A {
+        one: true,
+        two: -300,
+    },
+};
+
+fn field(name: &str, value: Value) -> FieldValue {
+    FieldValue {
+        name: Some(name.to_string()),
+        value,
+    }
+}
+
+#[test]
+fn test_decode_value() {
+    let mut buf = ConstWriteBuffer::new();
+    buf = serialize_const(&DATA, buf);
+    let value = decode_value(buf.as_ref(), &Struct::MEMORY_LAYOUT).unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(StructValue {
+            name: Some("Struct".to_string()),
+            fields: vec![
+                field("a", Value::Primitive(Primitive::Unsigned(0x11111111))),
+                field("b", Value::Primitive(Primitive::Signed(-2))),
+                field(
+                    "c",
+                    Value::List(vec![
+                        Value::Primitive(Primitive::Float(0.5)),
+                        Value::Primitive(Primitive::Float(-1.0)),
+                    ])
+                ),
+                field(
+                    "d",
+                    Value::Enum(EnumValue {
+                        name: Some("Enum".to_string()),
+                        tag: 0,
+                        variant: Some("A".to_string()),
+                        fields: vec![
+                            field("one", Value::Primitive(Primitive::Bool(true))),
+                            field("two", Value::Primitive(Primitive::Signed(-300))),
+                        ],
+                    })
+                ),
+            ],
+        })
+    );
+}
+
+#[test]
+fn test_decode_value_tuple() {
+    let mut buf = ConstWriteBuffer::new();
+    buf = serialize_const(&(1234u32, -5i64), buf);
+    let value = decode_value(buf.as_ref(), &<(u32, i64)>::MEMORY_LAYOUT).unwrap();
+    assert_eq!(
+        value,
+        Value::Struct(StructValue {
+            name: None,
+            fields: vec![
+                FieldValue {
+                    name: None,
+                    value: Value::Primitive(Primitive::Unsigned(1234)),
+                },
+                FieldValue {
+                    name: None,
+                    value: Value::Primitive(Primitive::Signed(-5)),
+                },
+            ],
+        })
+    );
+}
+
+#[test]
+fn test_decode_value_errors() {
+    let mut buf = ConstWriteBuffer::new();
+    buf = serialize_const(&DATA, buf);
+    let bytes = buf.as_ref();
+
+    assert_eq!(
+        decode_value(&bytes[..6], &Struct::MEMORY_LAYOUT),
+        Err(DecodeError {
+            offset: 5,
+            kind: DecodeErrorKind::UnexpectedEnd,
+        })
+    );
+
+    let mut corrupted = bytes.to_vec();
+    corrupted[13] = 3;
+    assert_eq!(
+        decode_value(&corrupted, &Struct::MEMORY_LAYOUT),
+        Err(DecodeError {
+            offset: 13,
+            kind: DecodeErrorKind::UnknownVariant { tag: 3 },
+        })
+    );
+
+    let mut corrupted = bytes.to_vec();
+    corrupted[14] = 2;
+    assert_eq!(
+        decode_value(&corrupted, &Struct::MEMORY_LAYOUT),
+        Err(DecodeError {
+            offset: 14,
+            kind: DecodeErrorKind::InvalidBool { value: 2 },
+        })
+    );
+}
+
+#[test]
+fn test_value_json() {
+    let mut buf = ConstWriteBuffer::new();
+    buf = serialize_const(&DATA, buf);
+    let value = decode_value(buf.as_ref(), &Struct::MEMORY_LAYOUT).unwrap();
+    assert_eq!(
+        serde_json::to_value(&value).unwrap(),
+        serde_json::json!({
+            "a": 0x11111111,
+            "b": -2,
+            "c": [0.5, -1.0],
+            "d": { "A": { "one": true, "two": -300 } },
+        })
+    );
+
+    let mut buf = ConstWriteBuffer::new();
+    buf = serialize_const(&Enum::B { one: 7 }, buf);
+    let value = decode_value(buf.as_ref(), &Enum::MEMORY_LAYOUT).unwrap();
+    assert_eq!(
+        serde_json::to_value(&value).unwrap(),
+        serde_json::json!({ "B": { "one": 7 } })
+    );
+}
+
+#[test]
+fn test_encode_value_roundtrip() {
+    let mut buf = ConstWriteBuffer::new();
+    buf = serialize_const(&DATA, buf);
+    let value = decode_value(buf.as_ref(), &Struct::MEMORY_LAYOUT).unwrap();
+    let encoded = encode_value(&value, &Struct::MEMORY_LAYOUT).unwrap();
+    assert_eq!(encoded, buf.as_ref());
+}
+
+#[test]
+fn test_encode_value_from_json() {
+    let value: Value = serde_json::from_value(serde_json::json!({
+        "d": { "B": { "one": 7 } },
+        "c": [1, -2.5],
+        "b": -128,
+        "a": 5,
+    }))
+    .unwrap();
+    let encoded = encode_value(&value, &Struct::MEMORY_LAYOUT).unwrap();
+    let buf = ConstReadBuffer::new(&encoded);
+    assert_eq!(
+        deserialize_const!(Struct, buf),
+        Some(Struct {
+            a: 5,
+            b: -128,
+            c: [1.0, -2.5],
+            d: Enum::B { one: 7 },
+        })
+    );
+
+    // Tuples can be written as lists
+    let value: Value = serde_json::from_value(serde_json::json!([1234, -5])).unwrap();
+    let encoded = encode_value(&value, &<(u32, i64)>::MEMORY_LAYOUT).unwrap();
+    let buf = ConstReadBuffer::new(&encoded);
+    assert_eq!(deserialize_const!((u32, i64), buf), Some((1234, -5)));
+}
+
+#[test]
+fn test_encode_value_errors() {
+    let encode = |json: serde_json::Value| {
+        let value: Value = serde_json::from_value(json).unwrap();
+        encode_value(&value, &Struct::MEMORY_LAYOUT)
+    };
+
+    assert_eq!(
+        encode(serde_json::json!({ "a": 1, "b": 128, "c": [0, 0], "d": { "B": { "one": 1 } } })),
+        Err(EncodeError {
+            path: "b".to_string(),
+            kind: EncodeErrorKind::OutOfRange { size: 1 },
+        })
+    );
+    assert_eq!(
+        encode(serde_json::json!({ "a": 1, "b": 1, "c": [0], "d": { "B": { "one": 1 } } })),
+        Err(EncodeError {
+            path: "c".to_string(),
+            kind: EncodeErrorKind::ListLength {
+                expected: 2,
+                found: 1
+            },
+        })
+    );
+    assert_eq!(
+        encode(serde_json::json!({ "a": 1, "b": 1, "c": [0, 0], "d": { "C": { "one": 1 } } })),
+        Err(EncodeError {
+            path: "d".to_string(),
+            kind: EncodeErrorKind::UnknownVariant {
+                variant: "C".to_string()
+            },
+        })
+    );
+    assert_eq!(
+        encode(
+            serde_json::json!({ "a": 1, "b": 1, "c": [0, 0], "d": { "A": { "one": 1, "two": 2 } } })
+        ),
+        Err(EncodeError {
+            path: "d.A.one".to_string(),
+            kind: EncodeErrorKind::TypeMismatch {
+                expected: "bool",
+                found: "integer"
+            },
+        })
+    );
+    assert_eq!(
+        encode(serde_json::json!({ "a": 1, "b": 1, "c": [0, 0], "e": 1 })),
+        Err(EncodeError {
+            path: String::new(),
+            kind: EncodeErrorKind::MissingField {
+                name: "d".to_string()
+            },
+        })
+    );
+
+    // Numbers that can't be written exactly are rejected instead of being rounded
+    assert_eq!(
+        encode(serde_json::json!({ "a": 1, "b": 1, "c": [1e300, 0], "d": { "B": { "one": 1 } } })),
+        Err(EncodeError {
+            path: "c[0]".to_string(),
+            kind: EncodeErrorKind::OutOfRange { size: 4 },
+        })
+    );
+    assert_eq!(
+        encode(serde_json::json!({ "a": 1, "b": 1, "c": [0.1, 0], "d": { "B": { "one": 1 } } })),
+        Err(EncodeError {
+            path: "c[0]".to_string(),
+            kind: EncodeErrorKind::OutOfRange { size: 4 },
+        })
+    );
+    assert_eq!(
+        encode(serde_json::json!({ "a": 1.5, "b": 1, "c": [0, 0], "d": { "B": { "one": 1 } } })),
+        Err(EncodeError {
+            path: "a".to_string(),
+            kind: EncodeErrorKind::OutOfRange { size: 4 },
+        })
+    );
+    assert_eq!(
+        encode_value(
+            &Value::Primitive(Primitive::Unsigned(u64::MAX)),
+            &f64::MEMORY_LAYOUT
+        ),
+        Err(EncodeError {
+            path: String::new(),
+            kind: EncodeErrorKind::OutOfRange { size: 8 },
+        })
+    );
+    // Whole numbers written as floats are still integers
+    let value: Value = serde_json::from_value(
+        serde_json::json!({ "a": 3.0, "b": -1.0, "c": [0, 0], "d": { "B": { "one": 1 } } }),
+    )
+    .unwrap();
+    let encoded = encode_value(&value, &Struct::MEMORY_LAYOUT).unwrap();
+    let buf = ConstReadBuffer::new(&encoded);
+    assert!(matches!(
+        deserialize_const!(Struct, buf),
+        Some(Struct { a: 3, b: -1, .. })
+    ));
+
+    let value = Value::Enum(EnumValue {
+        name: None,
+        tag: 3,
+        variant: None,
+        fields: Vec::new(),
+    });
+    assert_eq!(
+        encode_value(&value, &Enum::MEMORY_LAYOUT),
+        Err(EncodeError {
+            path: String::new(),
+            kind: EncodeErrorKind::UnknownTag { tag: 3 },
+        })
+    );
+}
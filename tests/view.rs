@@ -0,0 +1,141 @@
+use const_serialize::{serialize_const, ConstWriteBuffer, SerializeConst};
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[const_serialize(view)]
+struct Config {
+    id: u32,
+    #[const_serialize(view)]
+    mode: Mode,
+    #[const_serialize(view)]
+    limits: Limits,
+    flags: [u8; 3],
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[const_serialize(view)]
+struct Limits {
+    min: u16,
+    max: u16,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+#[const_serialize(view)]
+enum Mode {
+    Off,
+    Fixed {
+        speed: u32,
+    },
+    Scheduled {
+        #[const_serialize(view)]
+        level: Level,
+        start: u16,
+    } = 10,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+#[const_serialize(view)]
+enum Level {
+    Low { value: u16 },
+    High { value: u16 },
+}
+
+const CONFIG: Config = Config {
+    id: 7,
+    mode: Mode::Scheduled {
+        level: Level::High { value: 5 },
+        start: 1200,
+    },
+    limits: Limits { min: 1, max: 300 },
+    flags: [1, 2, 3],
+};
+
+const DATA: ConstWriteBuffer = serialize_const(&CONFIG, ConstWriteBuffer::new());
+
+#[test]
+fn test_struct_view() {
+    let view = ConfigView::new(DATA.as_ref()).unwrap();
+    assert_eq!(view.id(), 7);
+    assert_eq!(view.limits().min(), 1);
+    assert_eq!(view.limits().max(), 300);
+    assert_eq!(view.flags(), [1, 2, 3]);
+    assert_eq!(view.as_bytes(), DATA.as_ref());
+}
+
+#[test]
+fn test_enum_view() {
+    let view = ConfigView::new(DATA.as_ref()).unwrap();
+    match view.mode() {
+        ModeView::Scheduled(scheduled) => {
+            match scheduled.level() {
+                LevelView::High(high) => assert_eq!(high.value(), 5),
+                LevelView::Low(_) => panic!("expected the high level"),
+            }
+            assert_eq!(scheduled.start(), 1200);
+        }
+        _ => panic!("expected the scheduled variant"),
+    }
+
+    let data = serialize_const(&Level::Low { value: 1 }, ConstWriteBuffer::new());
+    match LevelView::new(data.as_ref()) {
+        Some(LevelView::Low(low)) => assert_eq!(low.value(), 1),
+        _ => panic!("expected the low level"),
+    }
+
+    for (mode, expected) in [(Mode::Off, None), (Mode::Fixed { speed: 9 }, Some(9))] {
+        let config = Config { mode, ..CONFIG };
+        let data = serialize_const(&config, ConstWriteBuffer::new());
+        let view = ConfigView::new(data.as_ref()).unwrap();
+        let speed = match view.mode() {
+            ModeView::Off => None,
+            ModeView::Fixed(fixed) => Some(fixed.speed()),
+            ModeView::Scheduled(_) => panic!("expected an unscheduled variant"),
+        };
+        assert_eq!(speed, expected);
+        // The fields after the enum are found after a variant of a different size
+        assert_eq!(view.limits().max(), 300);
+        assert_eq!(view.flags(), [1, 2, 3]);
+    }
+}
+
+#[test]
+fn test_view_in_const() {
+    const START: u16 = match ConfigView::new(DATA.as_ref()) {
+        Some(view) => match view.mode() {
+            ModeView::Scheduled(scheduled) => scheduled.start(),
+            _ => 0,
+        },
+        None => panic!("invalid data"),
+    };
+    assert_eq!(START, 1200);
+
+    // Nested variant views are read through their accessors
+    const LEVEL: u16 = match ConfigView::new(DATA.as_ref()) {
+        Some(view) => match view.mode() {
+            ModeView::Scheduled(scheduled) => match scheduled.level() {
+                LevelView::Low(level) => level.value(),
+                LevelView::High(level) => level.value(),
+            },
+            _ => 0,
+        },
+        None => panic!("invalid data"),
+    };
+    assert_eq!(LEVEL, 5);
+}
+
+#[test]
+fn test_invalid_view() {
+    let data = DATA.as_ref();
+    assert!(ConfigView::new(&data[..data.len() - 1]).is_none());
+
+    // The tag of the mode comes after the id
+    let mut unknown_variant = data.to_vec();
+    unknown_variant[4] = 3;
+    assert!(ConfigView::new(&unknown_variant).is_none());
+
+    // Data after the value is not part of the view
+    let mut trailing = data.to_vec();
+    trailing.push(0xff);
+    assert_eq!(ConfigView::new(&trailing).unwrap().as_bytes(), data);
+}
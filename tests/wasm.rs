@@ -0,0 +1,197 @@
+use const_serialize::{
+    read_wasm_records, read_wasm_records_lossy, serialize_described_record, serialize_record,
+    ExtractError, SerializeConst, RECORD_HEADER_SIZE,
+};
+
+// The fixtures are generated by tests/fixtures/wasm/generate.py. The records in them depend on the
+// layout fingerprints of these types, and the data is serialized with little endian primitives.
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+struct Version {
+    major: u16,
+    minor: u16,
+    tag: Tag,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, SerializeConst)]
+#[repr(C, u8)]
+enum Tag {
+    Release,
+    Beta { number: u32 },
+}
+
+const DATA_MODULE: &[u8] = include_bytes!("fixtures/wasm/data.wasm");
+const CUSTOM_MODULE: &[u8] = include_bytes!("fixtures/wasm/custom.wasm");
+
+#[test]
+#[cfg_attr(feature = "test-big-endian", ignore)]
+fn test_wasm_data_segments() {
+    let records = read_wasm_records(DATA_MODULE, "cser").unwrap();
+    let found: Vec<_> = records
+        .iter()
+        .map(|record| (record.section.as_str(), record.address))
+        .collect();
+    assert_eq!(
+        found,
+        [
+            ("data[0]", 1024 + 6),
+            ("data[0]", 1024 + 6 + 25 + 2),
+            ("data[1]", 0)
+        ]
+    );
+
+    assert_eq!(
+        records[0].deserialize::<Version>(),
+        Ok(Version {
+            major: 1,
+            minor: 2,
+            tag: Tag::Beta { number: 3 },
+        })
+    );
+    assert_eq!(records[1].deserialize::<u64>(), Ok(0x1122334455667788));
+    assert_eq!(
+        records[2].deserialize::<Version>(),
+        Ok(Version {
+            major: 4,
+            minor: 0,
+            tag: Tag::Release,
+        })
+    );
+}
+
+#[test]
+#[cfg_attr(feature = "test-big-endian", ignore)]
+fn test_wasm_custom_sections() {
+    let records = read_wasm_records(CUSTOM_MODULE, "cser").unwrap();
+    assert_eq!(records.len(), 2);
+    assert!(records.iter().all(|record| record.section == "cser"));
+    assert_eq!(
+        records[0].deserialize::<Version>(),
+        Ok(Version {
+            major: 4,
+            minor: 0,
+            tag: Tag::Release,
+        })
+    );
+    assert_eq!(records[1].deserialize::<u64>(), Ok(0x1122334455667788));
+
+    assert_eq!(
+        read_wasm_records(CUSTOM_MODULE, "other").unwrap(),
+        Vec::new()
+    );
+
+    // Cut off the last byte of the last record and shrink the size of the cser section at 0x16 to match
+    let mut module = CUSTOM_MODULE[..CUSTOM_MODULE.len() - 1].to_vec();
+    module[0x16] -= 1;
+    assert!(matches!(
+        read_wasm_records(&module, "cser"),
+        Err(ExtractError::Truncated { address: 21, .. })
+    ));
+
+    assert!(matches!(
+        read_wasm_records(b"not wasm", "cser"),
+        Err(ExtractError::Wasm(_))
+    ));
+}
+
+/// A module with one memory and an active data segment at address 0 that holds the data
+fn data_module(data: &[u8]) -> Vec<u8> {
+    fn leb128(mut value: usize, to: &mut Vec<u8>) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                to.push(byte);
+                return;
+            }
+            to.push(byte | 0x80);
+        }
+    }
+
+    let mut segments = vec![1, 0x00, 0x41, 0x00, 0x0b];
+    leb128(data.len(), &mut segments);
+    segments.extend_from_slice(data);
+    let mut module = b"\0asm\x01\0\0\0".to_vec();
+    module.extend_from_slice(&[5, 3, 1, 0, 1]);
+    module.push(11);
+    leb128(segments.len(), &mut module);
+    module.extend_from_slice(&segments);
+    module
+}
+
+#[test]
+fn test_wasm_invalid_schema_in_data_segment() {
+    const FIRST: Version = Version {
+        major: 1,
+        minor: 2,
+        tag: Tag::Beta { number: 3 },
+    };
+    const SECOND: Version = Version {
+        major: 4,
+        minor: 0,
+        tag: Tag::Release,
+    };
+    let first = serialize_described_record(&FIRST);
+    let mut corrupt = serialize_described_record(&SECOND);
+    // Replace the first byte of the schema, after the record header and the length of the schema
+    corrupt.as_mut()[16 + 4] = 0xff;
+    let last = serialize_record(&0x1122334455667788u64);
+    let data = [first.as_ref(), corrupt.as_ref(), last.as_ref()].concat();
+    let corrupt_address = first.as_ref().len() as u64;
+
+    let module = data_module(&data);
+    let (records, skipped) = read_wasm_records_lossy(&module, "cser").unwrap();
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[0].deserialize::<Version>(), Ok(FIRST));
+    assert_eq!(records[1].deserialize::<u64>(), Ok(0x1122334455667788));
+    assert!(matches!(
+        skipped.as_slice(),
+        [ExtractError::InvalidSchema { section, address }]
+            if section == "data[0]" && *address == corrupt_address
+    ));
+    assert_eq!(read_wasm_records(&module, "cser").unwrap(), records);
+}
+
+#[test]
+fn test_wasm_corrupt_schema_record_in_data_segment() {
+    /// A schema table record for a u64 that holds the schema bytes
+    fn schema_record(schema: &[u8]) -> Vec<u8> {
+        let mut record = b"CSLY".to_vec();
+        record.extend_from_slice(&u64::MEMORY_LAYOUT.fingerprint().to_le_bytes());
+        record.extend_from_slice(&(schema.len() as u32).to_le_bytes());
+        record.extend_from_slice(schema);
+        record
+    }
+
+    // A field whose default is read past the end of the struct
+    let out_of_bounds = schema_record(&[
+        2, b'S', 0, 0, 1, 0, 0, 0, 2, 0, b'S', 0, 0, 1, 5, 0, 0, 0, b'P', 1, 0,
+    ]);
+    // A field that holds a list whose size overflows
+    let mut huge_list = vec![2, b'S', 0, 0, 1, 0, 0, 0, 1, b'L'];
+    huge_list.extend_from_slice(&[0xff; 9]);
+    huge_list.extend_from_slice(&[0x01, b'P', 4, 0]);
+    let overflow = schema_record(&huge_list);
+    let first = serialize_record(&0x1122334455667788u64);
+    let data = [first.as_ref(), &out_of_bounds, &overflow].concat();
+
+    let module = data_module(&data);
+    let (records, skipped) = read_wasm_records_lossy(&module, "cser").unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].deserialize::<u64>(), Ok(0x1122334455667788));
+    // The record only has a schema if a valid schema table entry was found
+    assert_eq!(records[0].schema, None);
+    let first_address = first.as_ref().len() as u64;
+    let second_address = first_address + out_of_bounds.len() as u64;
+    assert!(matches!(
+        skipped.as_slice(),
+        [
+            ExtractError::InvalidSchema { address: first, .. },
+            ExtractError::InvalidSchema { address: second, .. },
+        ] if *first == first_address && *second == second_address
+    ));
+
+    // Truncated schema records are skipped instead of panicking
+    for len in RECORD_HEADER_SIZE..data.len() {
+        let _ = read_wasm_records_lossy(&data_module(&data[..len]), "cser");
+    }
+}